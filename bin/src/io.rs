@@ -0,0 +1,209 @@
+//! Reading input panels and writing computed exposures.
+
+use clap::ValueEnum;
+use factors::{ColumnMapping, FactorError, Result};
+use polars::prelude::*;
+use serde_json::{Map, Number, Value};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// File format of computed exposures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// Apache Parquet
+    Parquet,
+    /// JSON array of row objects
+    Json,
+}
+
+impl OutputFormat {
+    /// Format implied by a file extension, if recognized.
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "parquet" | "pq" => Some(Self::Parquet),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// The explicit format, else the one implied by `output`, else CSV.
+    pub(crate) fn resolve(explicit: Option<Self>, output: Option<&Path>) -> Self {
+        explicit
+            .or_else(|| output.and_then(Self::from_path))
+            .unwrap_or(Self::Csv)
+    }
+}
+
+/// Scan a CSV or Parquet panel, chosen by file extension.
+///
+/// CSV date columns are parsed into dates where possible. Integer columns
+/// are read as `Float64`, since factor arithmetic on integers would truncate,
+/// except the keys (under their source names in `columns`): an integer
+/// `date` keeps its type and integer `symbol` tickers are read as strings.
+pub(crate) fn read_input(path: &Path, columns: &ColumnMapping) -> Result<LazyFrame> {
+    let mut data = match OutputFormat::from_path(path) {
+        Some(OutputFormat::Csv) => LazyCsvReader::new(path)
+            .with_has_header(true)
+            .with_try_parse_dates(true)
+            .finish()?,
+        Some(OutputFormat::Parquet) => LazyFrame::scan_parquet(path, ScanArgsParquet::default())?,
+        _ => {
+            return Err(FactorError::InvalidSpec(format!(
+                "Unsupported input file '{}': expected a .csv or .parquet extension",
+                path.display()
+            )));
+        }
+    };
+
+    let (symbol, date) = (columns.source("symbol"), columns.source("date"));
+    let integers: Vec<Expr> = data
+        .collect_schema()?
+        .iter()
+        .filter(|(name, dtype)| dtype.is_integer() && name.as_str() != date)
+        .map(|(name, _)| {
+            let dtype = if name.as_str() == symbol {
+                DataType::String
+            } else {
+                DataType::Float64
+            };
+            col(name.clone()).cast(dtype)
+        })
+        .collect();
+    Ok(data.with_columns(integers))
+}
+
+/// Read a column mapping from a `.json` file, or TOML otherwise.
+pub(crate) fn read_mapping(path: &Path) -> Result<ColumnMapping> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| FactorError::InvalidSpec(format!("cannot read '{}': {e}", path.display())))?;
+    match OutputFormat::from_path(path) {
+        Some(OutputFormat::Json) => ColumnMapping::from_json(&contents),
+        _ => ColumnMapping::from_toml(&contents),
+    }
+}
+
+/// Write `df` to `output`, or to stdout when `None`.
+pub(crate) fn write_output(
+    df: &mut DataFrame,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(File::create(path).map_err(PolarsError::from)?),
+        None if format == OutputFormat::Parquet => {
+            return Err(FactorError::InvalidSpec(
+                "Parquet output needs an --output file".to_string(),
+            ));
+        }
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    match format {
+        OutputFormat::Csv => CsvWriter::new(&mut writer).finish(df)?,
+        OutputFormat::Parquet => {
+            ParquetWriter::new(&mut writer).finish(df)?;
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, &json_records(df)?)
+                .map_err(|e| FactorError::Computation(format!("JSON output failed: {e}")))?;
+            writeln!(writer).map_err(PolarsError::from)?;
+        }
+    }
+    writer.flush().map_err(PolarsError::from)?;
+    Ok(())
+}
+
+/// Rows of `df` as JSON objects. Numbers stay numbers, non-finite floats
+/// and nulls become `null`, and everything else is written as a string.
+fn json_records(df: &DataFrame) -> Result<Value> {
+    let columns = df
+        .get_columns()
+        .iter()
+        .map(|column| {
+            let column = match column.dtype() {
+                dtype if dtype.is_primitive_numeric() || dtype.is_bool() => column.clone(),
+                _ => column.cast(&DataType::String)?,
+            };
+            Ok((column.name().to_string(), column))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut records = Vec::with_capacity(df.height());
+    for row in 0..df.height() {
+        let mut record = Map::new();
+        for (name, column) in &columns {
+            let value = match column.get(row)? {
+                AnyValue::Null => Value::Null,
+                AnyValue::Boolean(b) => Value::Bool(b),
+                AnyValue::String(s) => Value::String(s.to_string()),
+                AnyValue::Float32(x) => {
+                    Number::from_f64(f64::from(x)).map_or(Value::Null, Value::Number)
+                }
+                AnyValue::Float64(x) => Number::from_f64(x).map_or(Value::Null, Value::Number),
+                other => other.extract::<i64>().map_or_else(
+                    || Value::String(other.to_string()),
+                    |i| Value::Number(i.into()),
+                ),
+            };
+            record.insert(name.clone(), value);
+        }
+        records.push(Value::Object(record));
+    }
+    Ok(Value::Array(records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_format_and_json_records() {
+        assert_eq!(
+            OutputFormat::resolve(None, Some(Path::new("out.PARQUET"))),
+            OutputFormat::Parquet
+        );
+        assert_eq!(
+            OutputFormat::resolve(Some(OutputFormat::Json), Some(Path::new("out.csv"))),
+            OutputFormat::Json
+        );
+        assert_eq!(OutputFormat::resolve(None, None), OutputFormat::Csv);
+
+        let df = df![
+            "symbol" => ["A", "B"],
+            "count" => [1i64, 2],
+            "score" => [Some(0.5), None],
+        ]
+        .unwrap();
+        let records = json_records(&df).unwrap();
+        assert_eq!(
+            records,
+            serde_json::json!([
+                {"symbol": "A", "count": 1, "score": 0.5},
+                {"symbol": "B", "count": 2, "score": null},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_read_input_integer_keys() {
+        let path = std::env::temp_dir().join("factors-io-integer-keys.csv");
+        std::fs::write(
+            &path,
+            "ticker,date,volume
+1001,20240102,500
+",
+        )
+        .unwrap();
+        let columns = ColumnMapping::new().with_alias("symbol", "ticker");
+
+        let df = read_input(&path, &columns).unwrap().collect().unwrap();
+        let tickers = df.column("ticker").unwrap().str().unwrap();
+        assert_eq!(tickers.get(0), Some("1001"));
+        assert_eq!(df.column("date").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(df.column("volume").unwrap().dtype(), &DataType::Float64);
+    }
+}
@@ -3,9 +3,18 @@
 //! This binary provides a command-line interface for discovering, introspecting,
 //! and computing factors from the factors library.
 
-use clap::{Parser, Subcommand};
-use factors::{FactorCategory, FactorRegistry};
+mod io;
+
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
+use factors::{
+    ColumnMapping, FactorCategory, FactorError, FactorRegistry, FactorSet, JoinMode, Pipeline,
+    Result,
+};
+use io::OutputFormat;
+use polars::prelude::*;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "factors")]
@@ -19,20 +28,57 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// List all available factors
-    List,
+    List {
+        /// Only list factors in a named set (risk_core, alpha_research)
+        #[arg(long)]
+        set: Option<FactorSet>,
+    },
     /// Show information about a specific factor
     Info {
         /// Factor name
         factor: String,
-    },
-    /// Compute a factor for a symbol
-    Compute {
-        /// Stock symbol
-        symbol: String,
-        /// Factor to compute
+        /// Column mapping file (.toml or .json) to show input sources under
         #[arg(long)]
-        factor: String,
+        columns: Option<PathBuf>,
     },
+    /// Compute factors from a CSV or Parquet data file
+    Compute(ComputeArgs),
+}
+
+#[derive(Args)]
+struct ComputeArgs {
+    /// Symbols to keep in the output (all when omitted)
+    symbols: Vec<String>,
+    /// Input panel (.csv or .parquet) with `symbol`, `date` and factor inputs
+    #[arg(long)]
+    input: PathBuf,
+    /// Column mapping file (.toml or .json) from canonical names to input columns
+    #[arg(long)]
+    columns: Option<PathBuf>,
+    /// Factor to compute; repeat for several
+    #[arg(long = "factor", required_unless_present = "all")]
+    factors: Vec<String>,
+    /// Compute every registered factor, skipping those the input can't support
+    #[arg(long, conflicts_with = "factors")]
+    all: bool,
+    /// Single date to compute (YYYY-MM-DD)
+    #[arg(long, conflicts_with_all = ["start", "end"], required_unless_present_all = ["start", "end"])]
+    date: Option<NaiveDate>,
+    /// First date of a range (YYYY-MM-DD)
+    #[arg(long, requires = "end")]
+    start: Option<NaiveDate>,
+    /// Last date of a range (YYYY-MM-DD)
+    #[arg(long, requires = "start")]
+    end: Option<NaiveDate>,
+    /// Output raw factor values instead of cross-sectional z-scores
+    #[arg(long)]
+    raw: bool,
+    /// Output file (stdout when omitted)
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+    /// Output format (inferred from the output extension, CSV by default)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
 }
 
 fn main() {
@@ -40,9 +86,30 @@ fn main() {
     let registry = FactorRegistry::with_defaults();
 
     match cli.command {
-        Commands::List => list_factors(&registry),
-        Commands::Info { factor } => show_factor_info(&registry, &factor),
-        Commands::Compute { symbol, factor } => compute_factor(&registry, &symbol, &factor),
+        Commands::List { set } => match set {
+            Some(set) => list_factors(&FactorRegistry::with_set(set)),
+            None => list_factors(&registry),
+        },
+        Commands::Info { factor, columns } => {
+            let registry = match columns.as_deref().map(io::read_mapping).transpose() {
+                Ok(mapping) => registry.with_column_mapping(mapping.unwrap_or_default()),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            show_factor_info(&registry, &factor);
+        }
+        Commands::Compute(args) => {
+            let format = OutputFormat::resolve(args.format, args.output.as_deref());
+            let result = compute_factors(registry, &args).and_then(|mut exposures| {
+                io::write_output(&mut exposures, args.output.as_deref(), format)
+            });
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
     }
 }
 
@@ -99,70 +166,107 @@ fn show_factor_info(registry: &FactorRegistry, factor_name: &str) {
     for col in &info.required_columns {
         println!("  - {}", col);
     }
+    if !info.dependencies.is_empty() {
+        println!("Depends on:");
+        for dependency in &info.dependencies {
+            println!("  - {}", dependency);
+        }
+    }
 }
 
-/// Compute a factor for a given symbol.
+/// Compute the requested factors over the input file.
 ///
-/// This is a placeholder implementation. In a production system, this would:
-/// 1. Fetch historical data for the symbol
-/// 2. Prepare the data in the required format
-/// 3. Call the factor's compute method
-/// 4. Display or save the results
-fn compute_factor(registry: &FactorRegistry, symbol: &str, factor_name: &str) {
-    // Verify the factor exists
-    let all_info = registry.all_info();
-    let info = all_info
-        .iter()
-        .find(|f| f.name == factor_name)
-        .unwrap_or_else(|| {
-            eprintln!("Error: Factor '{}' not found", factor_name);
-            eprintln!("\nAvailable factors:");
-            for info in &all_info {
-                eprintln!("  {}", info.name);
-            }
-            std::process::exit(1);
-        });
-
-    println!("Computing factor '{}' for symbol '{}'", factor_name, symbol);
-    println!("\nFactor Details:");
-    println!("  Category: {}", info.category);
-    println!("  Description: {}", info.description);
-    println!("  Lookback: {} periods", info.lookback);
-    println!("\nRequired data columns:");
-    for col in &info.required_columns {
-        println!("  - {}", col);
+/// With `--all`, factors the input can't support are skipped with a warning
+/// on stderr; an explicitly requested factor that fails is an error. Factors are
+/// outer-joined, so a symbol missing one factor keeps the others. A `--columns`
+/// mapping is applied to the input before validation and computation.
+fn compute_factors(registry: FactorRegistry, args: &ComputeArgs) -> Result<DataFrame> {
+    let columns = match &args.columns {
+        Some(path) => io::read_mapping(path)?,
+        None => ColumnMapping::new(),
+    };
+    let data = io::read_input(&args.input, &columns)?;
+    let registry = registry.with_column_mapping(columns);
+    let mut registry = if args.all {
+        // Skip factors whose inputs are missing before computing anything
+        let unsupported: Vec<String> = registry
+            .validate(&data)?
+            .into_iter()
+            .filter_map(|report| {
+                let error = report.result.err()?;
+                eprintln!("Skipping {}: {}", report.name, error);
+                Some(report.name)
+            })
+            .collect();
+        registry.filter(|factor| !unsupported.iter().any(|name| name == factor.name()))
+    } else {
+        if let Some(unknown) = args
+            .factors
+            .iter()
+            .find(|name| registry.get(name).is_none())
+        {
+            return Err(FactorError::NotFound(format!(
+                "{} (run `factors list` for available factors)",
+                unknown
+            )));
+        }
+        registry.filter(|factor| args.factors.iter().any(|name| name == factor.name()))
+    };
+    if registry.is_empty() {
+        return Err(FactorError::InvalidSpec(
+            "No factor can be computed from the input".to_string(),
+        ));
+    }
+    if args.raw {
+        registry = registry.with_pipeline(Pipeline::new());
     }
 
-    println!("\n[PLACEHOLDER] Computation not yet implemented.");
-    println!("To compute this factor, you need to:");
-    println!(
-        "1. Fetch historical {} data for {}",
-        frequency_description(&info.frequency),
-        symbol
-    );
-    println!(
-        "2. Prepare a DataFrame with required columns: {:?}",
-        info.required_columns
-    );
-    println!(
-        "3. Call registry.get(\"{}\").unwrap().compute(&data, date)",
-        factor_name
-    );
-}
+    let exposures = match (args.date, args.start, args.end) {
+        (Some(date), _, _) => registry.compute_all_with_coverage(&data, date, JoinMode::Outer)?,
+        (None, Some(start), Some(end)) => {
+            registry.compute_all_panel_with_coverage(&data, start, end, JoinMode::Outer)?
+        }
+        _ => {
+            return Err(FactorError::InvalidSpec(
+                "Pass --date or both --start and --end".to_string(),
+            ));
+        }
+    };
 
-/// Get a human-readable description of the data frequency.
-const fn frequency_description(freq: &DataFrequency) -> &'static str {
-    match freq {
-        DataFrequency::Daily => "daily",
-        DataFrequency::Quarterly => "quarterly",
+    for coverage in &exposures.coverage {
+        if let Some(reason) = &coverage.dropped {
+            if !args.all {
+                return Err(FactorError::Computation(format!(
+                    "Factor '{}' failed: {}",
+                    coverage.name, reason
+                )));
+            }
+            let summary = reason.lines().next().unwrap_or_default();
+            eprintln!("Skipping {}: {}", coverage.name, summary);
+        }
+    }
+    if exposures.coverage.iter().all(|c| c.dropped.is_some()) {
+        return Err(FactorError::Computation(
+            "No factor could be computed from the input".to_string(),
+        ));
     }
-}
 
-use factors::DataFrequency;
+    let mut exposures = exposures.exposures.lazy();
+    if !args.symbols.is_empty() {
+        let wanted = args.symbols.iter().fold(lit(false), |wanted, symbol| {
+            wanted.or(col("symbol").eq(lit(symbol.as_str())))
+        });
+        exposures = exposures.filter(wanted);
+    }
+    Ok(exposures
+        .sort(["date", "symbol"], Default::default())
+        .collect()?)
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
 
     #[test]
     fn test_registry_not_empty() {
@@ -201,4 +305,136 @@ mod tests {
         assert!(categories.contains(&FactorCategory::Growth));
         assert!(categories.contains(&FactorCategory::Liquidity));
     }
+
+    /// Parse `factors compute` arguments over a small CSV panel.
+    fn compute_args(name: &str, extra: &[&str]) -> ComputeArgs {
+        compute_args_on(
+            name,
+            "symbol,date,book_equity,close,shares_outstanding\n\
+             A,2024-01-02,50,100,1\n\
+             B,2024-01-02,90,300,1\n\
+             C,2024-01-02,80,500,1\n\
+             A,2024-01-03,50,110,1\n\
+             B,2024-01-03,90,290,1\n\
+             C,2024-01-03,80,550,1\n",
+            extra,
+        )
+    }
+
+    /// Parse `factors compute` arguments over the CSV panel `csv`.
+    fn compute_args_on(name: &str, csv: &str, extra: &[&str]) -> ComputeArgs {
+        let input = std::env::temp_dir().join(format!("factors-cli-{name}.csv"));
+        std::fs::write(&input, csv).unwrap();
+        let input = input.to_str().unwrap().to_string();
+        let mut args = vec!["factors", "compute", "--input", input.as_str()];
+        args.extend(extra);
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Compute(args) => args,
+            _ => unreachable!("parsed a compute command"),
+        }
+    }
+
+    #[test]
+    fn test_compute_raw_panel_for_symbols() {
+        let args = compute_args(
+            "raw",
+            &[
+                "--factor",
+                "book_to_price",
+                "--start",
+                "2024-01-02",
+                "--end",
+                "2024-01-03",
+                "--raw",
+                "A",
+                "C",
+            ],
+        );
+        let exposures = compute_factors(FactorRegistry::with_defaults(), &args).unwrap();
+
+        assert_eq!(exposures.height(), 4);
+        let symbols: Vec<&str> = exposures
+            .column("symbol")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(symbols, ["A", "C", "A", "C"]);
+        let values = exposures.column("book_to_price").unwrap().f64().unwrap();
+        assert_eq!(values.get(0), Some(0.5));
+    }
+
+    #[test]
+    fn test_compute_all_skips_unsupported_factors() {
+        let args = compute_args("all", &["--all", "--date", "2024-01-03"]);
+        let exposures = compute_factors(FactorRegistry::with_defaults(), &args).unwrap();
+
+        assert!(exposures.column("book_to_price").is_ok());
+        assert!(exposures.column("roe").is_err());
+        // Standardized scores average zero across the cross-section
+        let scores = exposures.column("book_to_price").unwrap().f64().unwrap();
+        assert!(scores.mean().unwrap().abs() < 1e-12);
+
+        let missing = compute_args("missing", &["--factor", "roe", "--date", "2024-01-03"]);
+        assert!(compute_factors(FactorRegistry::with_defaults(), &missing).is_err());
+        assert!(Cli::try_parse_from(["factors", "compute", "--input", "x.csv", "--all"]).is_err());
+    }
+
+    #[test]
+    fn test_compute_with_integer_symbols() {
+        let args = compute_args_on(
+            "integer-symbols",
+            "symbol,date,book_equity,close,shares_outstanding\n\
+             1001,2024-01-03,50,110,1\n\
+             1002,2024-01-03,90,290,1\n\
+             1003,2024-01-03,80,550,1\n",
+            &["--all", "--date", "2024-01-03", "--raw", "1001", "1003"],
+        );
+        let exposures = compute_factors(FactorRegistry::with_defaults(), &args).unwrap();
+
+        let symbols: Vec<&str> = exposures
+            .column("symbol")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(symbols, ["1001", "1003"]);
+        let values = exposures.column("book_to_price").unwrap().f64().unwrap();
+        assert_eq!(values.get(0), Some(50.0 / 110.0));
+    }
+
+    #[test]
+    fn test_compute_with_column_mapping() {
+        let columns = std::env::temp_dir().join("factors-cli-columns.toml");
+        std::fs::write(&columns, "book_equity = \"close\"\n").unwrap();
+        let columns = columns.to_str().unwrap();
+        let args = compute_args(
+            "mapped",
+            &[
+                "--columns",
+                columns,
+                "--factor",
+                "book_to_price",
+                "--date",
+                "2024-01-03",
+                "--raw",
+            ],
+        );
+        let exposures = compute_factors(FactorRegistry::with_defaults(), &args).unwrap();
+        let values = exposures.column("book_to_price").unwrap().f64().unwrap();
+        assert!(values.into_no_null_iter().all(|value| value == 1.0));
+
+        let mapping = io::read_mapping(Path::new(columns)).unwrap();
+        let info = FactorRegistry::with_defaults()
+            .with_column_mapping(mapping)
+            .all_info();
+        let book = info.iter().find(|i| i.name == "book_to_price").unwrap();
+        assert!(
+            book.required_columns
+                .iter()
+                .any(|c| c.name == "book_equity" && c.source == "close")
+        );
+    }
 }
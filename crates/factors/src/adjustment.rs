@@ -0,0 +1,505 @@
+//! Corporate action adjustment of price and volume series.
+//!
+//! Raw prices jump on splits and ex-dividend dates: a 4:1 split reads as a
+//! -75% return. [`CorporateActions`] holds split and dividend event tables and
+//! back-adjusts a panel so every row is comparable with the latest one:
+//!
+//! - splits divide earlier prices and per-share fundamentals by the ratio and
+//!   multiply earlier volumes and share counts by it;
+//! - under [`PriceAdjustment::TotalReturn`], a cash dividend `D` also scales
+//!   earlier prices by `P / (P + D)`, where `P` is the ex-date close, so the
+//!   return into the ex-date includes the dividend.
+//!
+//! Each factor declares the series it wants with
+//! [`Factor::price_adjustment`](crate::Factor::price_adjustment), and
+//! [`FactorRegistry::with_corporate_actions`](crate::FactorRegistry::with_corporate_actions)
+//! hands it data adjusted accordingly, as of the date it computes so no later
+//! event reaches the rows it reads.
+
+use crate::{FactorError, Result, alignment::day_number, traits::on_or_before};
+use chrono::NaiveDate;
+use polars::prelude::*;
+
+/// Price columns scaled by the price adjustment factor.
+pub const PRICE_COLUMNS: &[&str] = &["open", "high", "low", "close", "bid", "ask"];
+
+/// Volume and share count columns scaled inversely to splits.
+pub const SHARE_COLUMNS: &[&str] = &[
+    "volume",
+    "shares_outstanding",
+    "float_shares",
+    "shares_short",
+];
+
+/// Per-share fundamentals divided by split ratios like prices.
+///
+/// They are left alone by dividend adjustment, which only restates prices as
+/// total returns.
+pub const PER_SHARE_COLUMNS: &[&str] = &[
+    "dividends_per_share",
+    "eps",
+    "eps_estimate",
+    "eps_expected",
+    "eps_actual",
+    "surprise_std",
+];
+
+const ROW_KEY: &str = "__adjustment_key";
+
+/// Which corporate actions price series are adjusted for.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceAdjustment {
+    /// Splits only, so price changes exclude dividends
+    #[default]
+    PriceOnly,
+    /// Splits and dividends, so price changes are total returns
+    TotalReturn,
+}
+
+/// Split and dividend events used to adjust price and volume series.
+///
+/// Split tables have columns `symbol`, `date` (ex-date) and `ratio`, the new
+/// shares per old share (`4.0` for a 4:1 split, `0.1` for a 1:10 reverse
+/// split). Dividend tables have columns `symbol`, `date` (ex-date) and
+/// `amount`, the cash paid per post-split share. An event applies from the
+/// first row of its symbol dated on or after its ex-date.
+#[derive(Default)]
+pub struct CorporateActions {
+    splits: Option<LazyFrame>,
+    dividends: Option<LazyFrame>,
+}
+
+impl std::fmt::Debug for CorporateActions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CorporateActions")
+            .field("splits", &self.splits.is_some())
+            .field("dividends", &self.dividends.is_some())
+            .finish()
+    }
+}
+
+impl CorporateActions {
+    /// Create an empty set of events, which leaves data unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the split events.
+    pub fn with_splits(mut self, splits: LazyFrame) -> Self {
+        self.splits = Some(splits);
+        self
+    }
+
+    /// Set the cash dividend events.
+    pub fn with_dividends(mut self, dividends: LazyFrame) -> Self {
+        self.dividends = Some(dividends);
+        self
+    }
+
+    /// `data` with [`PRICE_COLUMNS`], [`PER_SHARE_COLUMNS`] and
+    /// [`SHARE_COLUMNS`] back-adjusted for the events, sorted by `symbol` and
+    /// `date`.
+    ///
+    /// The latest row of each symbol is left as reported. Other columns,
+    /// including `market_cap`, are untouched. Dividends need a `close` column
+    /// and are ignored under [`PriceAdjustment::PriceOnly`].
+    pub fn adjust(&self, data: &LazyFrame, adjustment: PriceAdjustment) -> Result<LazyFrame> {
+        let dividends = match adjustment {
+            PriceAdjustment::PriceOnly => None,
+            PriceAdjustment::TotalReturn => self.dividends.as_ref(),
+        };
+        let schema = data.clone().collect_schema()?;
+        let prices: Vec<&str> = PRICE_COLUMNS
+            .iter()
+            .copied()
+            .filter(|c| schema.contains(c))
+            .collect();
+        let per_share: Vec<&str> = PER_SHARE_COLUMNS
+            .iter()
+            .copied()
+            .filter(|c| schema.contains(c))
+            .collect();
+        let shares: Vec<&str> = SHARE_COLUMNS
+            .iter()
+            .copied()
+            .filter(|c| schema.contains(c))
+            .collect();
+        if (self.splits.is_none() && dividends.is_none())
+            || (prices.is_empty() && per_share.is_empty() && shares.is_empty())
+        {
+            return Ok(data.clone());
+        }
+        if dividends.is_some() && !schema.contains("close") {
+            return Err(FactorError::MissingColumn("close".to_string()));
+        }
+
+        let rows = data
+            .clone()
+            .with_column(day_number(col("date")).alias(ROW_KEY))
+            .sort(["symbol", "date"], Default::default());
+        let split = match &self.splits {
+            Some(splits) => {
+                let events = Self::events(splits, "ratio", "split")?;
+                // Product of the ratios of splits landing on the same row
+                let ratio = col("ratio").log(std::f64::consts::E).sum().exp();
+                let per_row = Self::match_rows(events, &rows, ratio);
+                rows.join(
+                    per_row,
+                    [col("symbol"), col(ROW_KEY)],
+                    [col("symbol"), col(ROW_KEY)],
+                    JoinArgs::new(JoinType::Left),
+                )
+                .with_column(col("ratio").fill_null(lit(1.0)).alias("__split"))
+                .drop([col("ratio")])
+            }
+            None => rows.with_column(lit(1.0).alias("__split")),
+        };
+        let events = match dividends {
+            Some(dividends) => {
+                let events = Self::events(dividends, "amount", "dividend")?;
+                let per_row = Self::match_rows(events, &split, col("amount").sum());
+                split
+                    .join(
+                        per_row,
+                        [col("symbol"), col(ROW_KEY)],
+                        [col("symbol"), col(ROW_KEY)],
+                        JoinArgs::new(JoinType::Left),
+                    )
+                    .with_column(col("amount").fill_null(lit(0.0)).alias("__dividend"))
+                    .drop([col("amount")])
+            }
+            None => split.with_column(lit(0.0).alias("__dividend")),
+        };
+
+        // Multipliers applied to every row before the event row. Scaling by
+        // P / (P + D) makes the return into the ex-date (P + D) / P_prev
+        let dividend_factor = if dividends.is_some() {
+            (col("close") / (col("close") + col("__dividend"))).fill_null(lit(1.0))
+        } else {
+            lit(1.0)
+        };
+        let after = |multiplier: &str| {
+            col(multiplier)
+                .cum_prod(true)
+                .shift(lit(-1))
+                .over([col("symbol")])
+                .fill_null(lit(1.0))
+        };
+        let adjusted = events
+            .with_columns([
+                (dividend_factor / col("__split")).alias("__price_multiplier"),
+                (lit(1.0) / col("__split")).alias("__per_share_multiplier"),
+            ])
+            .with_columns([
+                after("__price_multiplier").alias("__price_factor"),
+                after("__per_share_multiplier").alias("__per_share_factor"),
+                after("__split").alias("__share_factor"),
+            ])
+            .with_columns(
+                prices
+                    .iter()
+                    .map(|c| (col(*c) * col("__price_factor")).alias(*c))
+                    .chain(
+                        per_share
+                            .iter()
+                            .map(|c| (col(*c) * col("__per_share_factor")).alias(*c)),
+                    )
+                    .chain(
+                        shares
+                            .iter()
+                            .map(|c| (col(*c) * col("__share_factor")).alias(*c)),
+                    )
+                    .collect::<Vec<_>>(),
+            )
+            .drop([
+                col(ROW_KEY),
+                col("__split"),
+                col("__dividend"),
+                col("__price_multiplier"),
+                col("__per_share_multiplier"),
+                col("__price_factor"),
+                col("__per_share_factor"),
+                col("__share_factor"),
+            ]);
+        Ok(adjusted)
+    }
+
+    /// `data` adjusted as by [`CorporateActions::adjust`] for the events with
+    /// an ex-date on or before `date` only.
+    ///
+    /// Rows on `date` are left as reported then, so a factor computed on
+    /// `date` sees no split or dividend that was not yet known.
+    pub fn adjust_as_of(
+        &self,
+        data: &LazyFrame,
+        adjustment: PriceAdjustment,
+        date: NaiveDate,
+    ) -> Result<LazyFrame> {
+        let known = |events: &LazyFrame| events.clone().filter(on_or_before(date));
+        Self {
+            splits: self.splits.as_ref().map(known),
+            dividends: self.dividends.as_ref().map(known),
+        }
+        .adjust(data, adjustment)
+    }
+
+    /// `symbol`, row key and `value` of an event table, checked for columns.
+    fn events(table: &LazyFrame, value: &str, kind: &str) -> Result<LazyFrame> {
+        let schema = table.clone().collect_schema()?;
+        let missing: Vec<String> = ["symbol", "date", value]
+            .into_iter()
+            .filter(|c| !schema.contains(c))
+            .map(str::to_string)
+            .collect();
+        if !missing.is_empty() {
+            return Err(FactorError::MissingEventColumns {
+                events: kind.to_string(),
+                columns: missing,
+            });
+        }
+        Ok(table.clone().select([
+            col("symbol"),
+            day_number(col("date")).alias(ROW_KEY),
+            col(value).cast(DataType::Float64),
+        ]))
+    }
+
+    /// Events aggregated with `aggregate` onto the first row of `rows` on or
+    /// after their date. Events after a symbol's last row are dropped, since no
+    /// row they would change remains.
+    fn match_rows(events: LazyFrame, rows: &LazyFrame, aggregate: Expr) -> LazyFrame {
+        let targets = rows
+            .clone()
+            .select([col("symbol"), col(ROW_KEY), col(ROW_KEY).alias("__row")])
+            .sort([ROW_KEY], Default::default());
+        events
+            .sort([ROW_KEY], Default::default())
+            .join_builder()
+            .with(targets)
+            .left_on([col(ROW_KEY)])
+            .right_on([col(ROW_KEY)])
+            .how(JoinType::AsOf(AsOfOptions {
+                strategy: AsofStrategy::Forward,
+                left_by: Some(vec!["symbol".into()]),
+                right_by: Some(vec!["symbol".into()]),
+                allow_eq: true,
+                check_sortedness: true,
+                ..Default::default()
+            }))
+            .finish()
+            .filter(col("__row").is_not_null())
+            .group_by([col("symbol"), col("__row").alias(ROW_KEY)])
+            .agg([aggregate])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn panel() -> LazyFrame {
+        df![
+            "symbol" => ["A", "A", "A", "A", "B"],
+            "date" => ["2024-01-02", "2024-01-03", "2024-01-05", "2024-01-08", "2024-01-02"],
+            "close" => [100.0, 102.0, 25.0, 24.0, 50.0],
+            "volume" => [10.0, 10.0, 40.0, 40.0, 7.0],
+            "market_cap" => [1.0, 1.0, 1.0, 1.0, 1.0],
+        ]
+        .unwrap()
+        .lazy()
+    }
+
+    fn column(df: &DataFrame, name: &str) -> Vec<f64> {
+        df.column(name)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_split_adjustment() {
+        // A 4:1 split with an ex-date on a non-trading day applies from the
+        // next row
+        let splits = df![
+            "symbol" => ["A", "A"],
+            "date" => ["2024-01-04", "2024-02-01"],
+            "ratio" => [4.0, 2.0],
+        ]
+        .unwrap()
+        .lazy();
+        let actions = CorporateActions::new().with_splits(splits);
+
+        let adjusted = actions
+            .adjust(&panel(), PriceAdjustment::TotalReturn)
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(column(&adjusted, "close"), [25.0, 25.5, 25.0, 24.0, 50.0]);
+        assert_eq!(column(&adjusted, "volume"), [40.0, 40.0, 40.0, 40.0, 7.0]);
+        assert_eq!(column(&adjusted, "market_cap"), [1.0; 5]);
+        assert_eq!(
+            adjusted.get_column_names(),
+            ["symbol", "date", "close", "volume", "market_cap"]
+        );
+
+        let bad = CorporateActions::new().with_splits(df!["symbol" => ["A"]].unwrap().lazy());
+        match bad.adjust(&panel(), PriceAdjustment::PriceOnly) {
+            Err(FactorError::MissingEventColumns { events, columns }) => {
+                assert_eq!(events, "split");
+                assert_eq!(columns, ["date", "ratio"]);
+            }
+            Err(other) => panic!("Expected MissingEventColumns, got {other:?}"),
+            Ok(_) => panic!("split events without dates should be rejected"),
+        }
+    }
+
+    #[test]
+    fn test_dividend_adjustment() {
+        let dividends = df![
+            "symbol" => ["A"],
+            "date" => ["2024-01-03"],
+            "amount" => [2.0],
+        ]
+        .unwrap()
+        .lazy();
+        let actions = CorporateActions::new().with_dividends(dividends);
+
+        let price_only = actions
+            .adjust(&panel(), PriceAdjustment::PriceOnly)
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(
+            column(&price_only, "close"),
+            [100.0, 102.0, 25.0, 24.0, 50.0]
+        );
+
+        let total = actions
+            .adjust(&panel(), PriceAdjustment::TotalReturn)
+            .unwrap()
+            .collect()
+            .unwrap();
+        let close = column(&total, "close");
+        // 100 -> 102 plus a 2 dividend is a 4% total return
+        assert_relative_eq!(close[0], 100.0 * 102.0 / 104.0, epsilon = 1e-12);
+        assert_relative_eq!(close[1] / close[0] - 1.0, 0.04, epsilon = 1e-12);
+        assert_eq!(close[2..], [25.0, 24.0, 50.0]);
+        assert_eq!(column(&total, "volume"), [10.0, 10.0, 40.0, 40.0, 7.0]);
+    }
+
+    #[test]
+    fn test_adjustment_is_anchored_at_compute_date() {
+        use crate::{FactorRegistry, FactorSpec, Pipeline};
+        use chrono::NaiveDate;
+
+        // A 4:1 split after the compute date, with dividends per share
+        // reported in post-split units from then on
+        let data = df![
+            "symbol" => ["A", "A", "A", "A"],
+            "date" => ["2024-01-02", "2024-01-03", "2024-01-04", "2024-01-05"],
+            "close" => [100.0, 100.0, 25.0, 25.0],
+            "dividends_per_share" => [2.0, 2.0, 0.5, 0.5],
+        ]
+        .unwrap()
+        .lazy();
+        let splits = df!["symbol" => ["A"], "date" => ["2024-01-04"], "ratio" => [4.0]]
+            .unwrap()
+            .lazy();
+        let actions = CorporateActions::new().with_splits(splits);
+        let date = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+
+        let adjusted = actions
+            .adjust(&data, PriceAdjustment::TotalReturn)
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(column(&adjusted, "close"), [25.0; 4]);
+        assert_eq!(column(&adjusted, "dividends_per_share"), [0.5; 4]);
+        let as_of = actions
+            .adjust_as_of(&data, PriceAdjustment::TotalReturn, date)
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(column(&as_of, "close"), [100.0, 100.0, 25.0, 25.0]);
+
+        let mut registry = FactorRegistry::new().with_pipeline(Pipeline::new());
+        registry
+            .register_spec(FactorSpec::new("dividend_yield"))
+            .unwrap();
+        let exposures = registry
+            .with_corporate_actions(actions)
+            .compute_all(&data, date)
+            .unwrap();
+        assert_eq!(exposures.height(), 1);
+        assert_relative_eq!(
+            column(&exposures, "dividend_yield")[0],
+            0.02,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_registry_adjusts_per_factor() {
+        use crate::{Factor, FactorRegistry, FactorSpec, Pipeline, momentum::High52Week};
+        use chrono::NaiveDate;
+
+        let data = df![
+            "symbol" => ["A", "A", "A", "A"],
+            "date" => ["2024-01-02", "2024-01-03", "2024-01-04", "2024-01-05"],
+            "close" => [100.0, 100.0, 26.0, 25.0],
+        ]
+        .unwrap()
+        .lazy();
+        let splits = df!["symbol" => ["A"], "date" => ["2024-01-04"], "ratio" => [4.0]]
+            .unwrap()
+            .lazy();
+        let momentum = FactorSpec::new("short_term_momentum")
+            .with_params(serde_json::json!({ "lookback": 2, "skip_days": 0 }));
+        let registry = || {
+            let mut registry = FactorRegistry::new().with_pipeline(Pipeline::new());
+            registry.register_spec(momentum.clone()).unwrap();
+            registry
+        };
+        let date = NaiveDate::from_ymd_opt(2024, 1, 5).unwrap();
+
+        let raw = registry().compute_all(&data, date).unwrap();
+        assert_relative_eq!(
+            column(&raw, "short_term_momentum")[0],
+            -0.75,
+            epsilon = 1e-12
+        );
+        let adjusted = registry()
+            .with_corporate_actions(CorporateActions::new().with_splits(splits))
+            .compute_all(&data, date)
+            .unwrap();
+        assert_relative_eq!(
+            column(&adjusted, "short_term_momentum")[0],
+            0.0,
+            epsilon = 1e-12
+        );
+
+        let momentum = registry();
+        let momentum = momentum.get("short_term_momentum").unwrap();
+        assert_eq!(momentum.price_adjustment(), PriceAdjustment::TotalReturn);
+        assert_eq!(
+            High52Week::default().price_adjustment(),
+            PriceAdjustment::PriceOnly
+        );
+    }
+}
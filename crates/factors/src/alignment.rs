@@ -0,0 +1,189 @@
+//! Point-in-time alignment of fundamentals to daily data.
+//!
+//! Quarterly factors evaluate `date == target`, so on their own they only
+//! produce values on filing dates. [`align_as_of`] carries the latest
+//! fundamental record known on or before each daily row forward, keyed on a
+//! report date plus a publication lag, so quarterly factors can be evaluated
+//! on any trading day without look-ahead bias.
+
+use crate::{Result, traits::as_date};
+use polars::prelude::*;
+
+/// Internal join key: the availability date as days since the Unix epoch.
+const ASOF_KEY: &str = "__asof_key";
+
+/// Configuration for point-in-time as-of alignment.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AsOfConfig {
+    /// Column in the fundamentals holding the report date
+    pub report_date_column: String,
+    /// Calendar days after the report date before a record may be used
+    ///
+    /// Use `0` if the report date is already the filing (availability) date,
+    /// or a filing delay such as `45` if it is the fiscal period end.
+    pub publication_lag_days: u32,
+}
+
+impl Default for AsOfConfig {
+    fn default() -> Self {
+        Self {
+            report_date_column: "report_date".to_string(),
+            publication_lag_days: 0,
+        }
+    }
+}
+
+impl AsOfConfig {
+    /// Set the report date column.
+    pub fn with_report_date_column(mut self, column: impl Into<String>) -> Self {
+        self.report_date_column = column.into();
+        self
+    }
+
+    /// Set the publication lag in calendar days.
+    pub const fn with_publication_lag_days(mut self, days: u32) -> Self {
+        self.publication_lag_days = days;
+        self
+    }
+}
+
+/// Join each daily row to the latest fundamental available on its date.
+///
+/// `daily` must contain `symbol` and `date`; `fundamentals` must contain
+/// `symbol` and the configured report date column. A fundamental record
+/// reported on `r` becomes available on `r + publication_lag_days` and is
+/// matched to every daily row of the same symbol dated on or after that, until
+/// a newer record becomes available. Rows with no record available yet get
+/// nulls.
+///
+/// All daily rows and columns are kept. Fundamental columns already present in
+/// `daily` (including any `date` column) are ignored, and the report date
+/// column is carried through so staleness can be inspected.
+pub fn align_as_of(
+    daily: &LazyFrame,
+    fundamentals: &LazyFrame,
+    config: &AsOfConfig,
+) -> Result<LazyFrame> {
+    let daily_schema = daily.clone().collect_schema()?;
+    let fundamental_columns: Vec<Expr> = fundamentals
+        .clone()
+        .collect_schema()?
+        .iter_names()
+        .filter(|name| name.as_str() == "symbol" || !daily_schema.contains(name))
+        .map(|name| col(name.clone()))
+        .collect();
+
+    let lag = config.publication_lag_days as i32;
+    let left = daily
+        .clone()
+        .with_column(day_number(col("date")).alias(ASOF_KEY))
+        .sort([ASOF_KEY], Default::default());
+    let right = fundamentals
+        .clone()
+        .select(fundamental_columns)
+        .with_column(
+            (day_number(col(config.report_date_column.as_str())) + lit(lag)).alias(ASOF_KEY),
+        )
+        .sort([ASOF_KEY], Default::default());
+
+    let joined = left
+        .join_builder()
+        .with(right)
+        .left_on([col(ASOF_KEY)])
+        .right_on([col(ASOF_KEY)])
+        .how(JoinType::AsOf(AsOfOptions {
+            strategy: AsofStrategy::Backward,
+            left_by: Some(vec!["symbol".into()]),
+            right_by: Some(vec!["symbol".into()]),
+            allow_eq: true,
+            check_sortedness: true,
+            ..Default::default()
+        }))
+        .finish()
+        .drop([col(ASOF_KEY)])
+        .sort(["symbol", "date"], Default::default());
+
+    Ok(joined)
+}
+
+/// Days since the Unix epoch for a date column, as accepted by [`as_date`].
+pub(crate) fn day_number(expr: Expr) -> Expr {
+    as_date(expr).cast(DataType::Int32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Factor, value::BookToPrice};
+    use chrono::NaiveDate;
+
+    fn daily() -> LazyFrame {
+        df![
+            "symbol" => ["AAPL", "AAPL", "AAPL", "AAPL", "MSFT"],
+            "date" => ["2024-03-29", "2024-04-01", "2024-05-14", "2024-05-15", "2024-05-15"],
+            "market_cap" => [100.0, 100.0, 200.0, 200.0, 300.0],
+        ]
+        .unwrap()
+        .lazy()
+    }
+
+    fn fundamentals() -> LazyFrame {
+        df![
+            "symbol" => ["AAPL", "AAPL", "MSFT"],
+            "report_date" => ["2023-12-31", "2024-03-31", "2024-06-30"],
+            "book_equity" => [10.0, 20.0, 30.0],
+        ]
+        .unwrap()
+        .lazy()
+    }
+
+    fn book_equity(config: &AsOfConfig) -> Vec<Option<f64>> {
+        let aligned = align_as_of(&daily(), &fundamentals(), config)
+            .unwrap()
+            .collect()
+            .unwrap();
+        aligned
+            .column("book_equity")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_align_as_of_carries_latest_record() {
+        let values = book_equity(&AsOfConfig::default());
+        assert_eq!(
+            values,
+            vec![Some(10.0), Some(20.0), Some(20.0), Some(20.0), None]
+        );
+    }
+
+    #[test]
+    fn test_align_as_of_respects_publication_lag() {
+        let config = AsOfConfig::default().with_publication_lag_days(45);
+        let values = book_equity(&config);
+        // The Q1 report (2024-03-31) only becomes usable on 2024-05-15.
+        assert_eq!(
+            values,
+            vec![Some(10.0), Some(10.0), Some(10.0), Some(20.0), None]
+        );
+    }
+
+    #[test]
+    fn test_quarterly_factor_on_any_trading_day() {
+        let aligned = align_as_of(&daily(), &fundamentals(), &AsOfConfig::default()).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 5, 14).unwrap();
+        let result = BookToPrice::default().compute_raw(&aligned, date).unwrap();
+
+        assert_eq!(result.height(), 1);
+        let value = result
+            .column("book_to_price")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0);
+        assert_eq!(value, Some(0.1));
+    }
+}
@@ -0,0 +1,490 @@
+//! Trading calendars for day-count lookbacks.
+//!
+//! Daily factors count lookbacks and skip periods in rows, which match trading
+//! days only when a panel has exactly one row per session. A
+//! [`TradingCalendar`] defines the sessions, either by weekday and NYSE-style
+//! holiday rules or from a user-supplied list, and
+//! [`TradingCalendar::align`] reindexes a panel onto them: missing sessions
+//! become null rows, and rows on closed days are an error. With
+//! [`FactorRegistry::with_calendar`](crate::FactorRegistry::with_calendar)
+//! every row-based daily factor sees aligned data, so `n` rows always span `n`
+//! trading days. Standalone, [`Factor::compute`](crate::Factor::compute)
+//! counts rows as given. [`TradingCalendar::all_days`] is the calendar-day
+//! mode for row-based factors: aligned to it, `n` rows span `n` calendar days.
+//!
+//! Factors whose windows are dates rather than rows, such as event data, take
+//! a [`DayCount`]: trading days of a calendar, or plain calendar days. The
+//! registry passes its calendar to them through
+//! [`Factor::on_calendar`](crate::Factor::on_calendar).
+
+use crate::{FactorError, Result, traits::date_column};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use polars::prelude::*;
+use std::collections::BTreeSet;
+
+/// Days from 0001-01-01 to the Unix epoch, as counted by chrono.
+const UNIX_EPOCH_DAYS: i32 = 719_163;
+
+/// Sessions on which a market trades.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "calendar", rename_all = "snake_case")]
+pub enum TradingCalendar {
+    /// Monday to Friday, except `holidays`
+    Weekdays {
+        /// Additional closed dates
+        #[serde(default)]
+        holidays: BTreeSet<NaiveDate>,
+    },
+    /// Monday to Friday, except NYSE full-day holidays and `holidays`.
+    ///
+    /// The rules cover New Year's Day, Martin Luther King Jr. Day (from 1998),
+    /// Washington's Birthday, Good Friday, Memorial Day, Juneteenth (from
+    /// 2022), Independence Day, Labor Day, Thanksgiving and Christmas, with
+    /// Saturday holidays observed on Friday and Sunday holidays on Monday
+    /// (except New Year's Day on a Saturday, which is not observed).
+    /// Unscheduled closures belong in `holidays`.
+    Nyse {
+        /// Additional closed dates
+        #[serde(default)]
+        holidays: BTreeSet<NaiveDate>,
+    },
+    /// Every day of the week, except `holidays`
+    AllDays {
+        /// Additional closed dates
+        #[serde(default)]
+        holidays: BTreeSet<NaiveDate>,
+    },
+    /// Exactly the listed sessions
+    Custom {
+        /// Trading dates
+        sessions: BTreeSet<NaiveDate>,
+    },
+}
+
+impl Default for TradingCalendar {
+    fn default() -> Self {
+        Self::nyse()
+    }
+}
+
+impl TradingCalendar {
+    /// NYSE calendar without extra closures.
+    pub const fn nyse() -> Self {
+        Self::Nyse {
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    /// Every weekday, with no holidays.
+    pub const fn weekdays() -> Self {
+        Self::Weekdays {
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    /// Every calendar day, so aligned rows count calendar days.
+    pub const fn all_days() -> Self {
+        Self::AllDays {
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    /// Calendar trading exactly on `sessions`.
+    pub fn from_sessions(sessions: impl IntoIterator<Item = NaiveDate>) -> Self {
+        Self::Custom {
+            sessions: sessions.into_iter().collect(),
+        }
+    }
+
+    /// Close the market on `dates` as well.
+    pub fn with_holidays(mut self, dates: impl IntoIterator<Item = NaiveDate>) -> Self {
+        match &mut self {
+            Self::Weekdays { holidays } | Self::Nyse { holidays } | Self::AllDays { holidays } => {
+                holidays.extend(dates);
+            }
+            Self::Custom { sessions } => {
+                for date in dates {
+                    sessions.remove(&date);
+                }
+            }
+        }
+        self
+    }
+
+    /// Whether the market trades on `date`.
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        match self {
+            Self::Weekdays { holidays } => is_weekday(date) && !holidays.contains(&date),
+            Self::Nyse { holidays } => {
+                is_weekday(date) && !holidays.contains(&date) && !is_nyse_holiday(date)
+            }
+            Self::AllDays { holidays } => !holidays.contains(&date),
+            Self::Custom { sessions } => sessions.contains(&date),
+        }
+    }
+
+    /// Sessions in `[start, end]`, in order.
+    pub fn trading_days(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        match self {
+            Self::Custom { sessions } if start <= end => {
+                sessions.range(start..=end).copied().collect()
+            }
+            _ => start
+                .iter_days()
+                .take_while(|date| *date <= end)
+                .filter(|date| self.is_trading_day(*date))
+                .collect(),
+        }
+    }
+
+    /// The session `n` sessions before `date`, counting the last session
+    /// before `date` as the first, or `None` if the calendar has too few.
+    ///
+    /// A window of `n` trading days ending on `date` covers the dates after
+    /// the result up to `date`.
+    pub fn sub_trading_days(&self, date: NaiveDate, n: usize) -> Option<NaiveDate> {
+        if n == 0 {
+            return Some(date);
+        }
+        match self {
+            Self::Custom { sessions } => sessions.range(..date).rev().nth(n - 1).copied(),
+            _ => date
+                .pred_opt()?
+                .iter_days()
+                .rev()
+                .filter(|day| self.is_trading_day(*day))
+                .nth(n - 1),
+        }
+    }
+
+    /// Reindex a `symbol`/`date` panel onto the calendar's sessions.
+    ///
+    /// Each symbol gets one row per session from its first to its last date,
+    /// with missing sessions added as null rows. Rows dated on days the
+    /// calendar is closed are an error rather than dropped. The result keeps
+    /// the input's `symbol` type, has a `Date` `date` column and is sorted by
+    /// `symbol` and `date`.
+    pub fn align(&self, data: &LazyFrame) -> Result<LazyFrame> {
+        let data = data.clone().with_column(date_column());
+        let dates = data
+            .clone()
+            .select([col("date").unique().sort(Default::default())])
+            .collect()?;
+        let closed: Vec<NaiveDate> = dates
+            .column("date")?
+            .date()?
+            .into_iter()
+            .flatten()
+            .map(from_epoch_days)
+            .filter(|date| !self.is_trading_day(*date))
+            .collect();
+        if let Some(first) = closed.first() {
+            return Err(FactorError::Computation(format!(
+                "{} input date(s) fall on days the calendar is closed, first {first}",
+                closed.len()
+            )));
+        }
+
+        let spans = data
+            .clone()
+            .group_by([col("symbol")])
+            .agg([
+                col("date").min().alias("first"),
+                col("date").max().alias("last"),
+            ])
+            .collect()?;
+
+        // Each span's row once per session, to repeat its symbol by
+        let mut rows: Vec<IdxSize> = Vec::new();
+        let mut days: Vec<i32> = Vec::new();
+        let symbols = spans.column("symbol")?;
+        let firsts = spans.column("first")?.date()?;
+        let lasts = spans.column("last")?.date()?;
+        for (row, (first, last)) in firsts.into_iter().zip(&**lasts).enumerate() {
+            let (Some(first), Some(last)) = (first, last) else {
+                continue;
+            };
+            if symbols.get(row)?.is_null() {
+                continue;
+            }
+            for session in self.trading_days(from_epoch_days(first), from_epoch_days(last)) {
+                rows.push(row as IdxSize);
+                days.push(session.num_days_from_ce() - UNIX_EPOCH_DAYS);
+            }
+        }
+
+        let grid = DataFrame::new(vec![
+            symbols.take(&IdxCa::from_vec("symbol".into(), rows))?,
+            Column::new("date".into(), days).cast(&DataType::Date)?,
+        ])?;
+        Ok(grid
+            .lazy()
+            .join(
+                data,
+                [col("symbol"), col("date")],
+                [col("symbol"), col("date")],
+                JoinArgs::new(JoinType::Left),
+            )
+            .sort(["symbol", "date"], Default::default()))
+    }
+}
+
+/// How a lookback measured in days is counted.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayCount {
+    /// Sessions of a trading calendar
+    Trading(TradingCalendar),
+    /// Every calendar day, open or not
+    Calendar,
+}
+
+impl Default for DayCount {
+    fn default() -> Self {
+        Self::Trading(TradingCalendar::default())
+    }
+}
+
+impl DayCount {
+    /// Exclusive start of the `days`-long window ending on `date`.
+    ///
+    /// The window covers dates strictly after the result up to `date`. Counts
+    /// reaching past the start of the calendar return [`NaiveDate::MIN`].
+    pub fn window_start(&self, date: NaiveDate, days: usize) -> NaiveDate {
+        match self {
+            Self::Trading(calendar) => calendar.sub_trading_days(date, days),
+            Self::Calendar => date.checked_sub_days(Days::new(days as u64)),
+        }
+        .unwrap_or(NaiveDate::MIN)
+    }
+}
+
+fn is_weekday(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+fn from_epoch_days(days: i32) -> NaiveDate {
+    NaiveDate::from_num_days_from_ce_opt(days + UNIX_EPOCH_DAYS).unwrap_or(NaiveDate::MIN)
+}
+
+/// Whether `date` is a full-day NYSE holiday under current rules.
+fn is_nyse_holiday(date: NaiveDate) -> bool {
+    let year = date.year();
+    let day = |month, day| NaiveDate::from_ymd_opt(year, month, day);
+    // Saturday holidays close the Friday before, Sunday holidays the Monday after
+    let observed = |holiday: Option<NaiveDate>| {
+        holiday.and_then(|d| match d.weekday() {
+            Weekday::Sat => d.pred_opt(),
+            Weekday::Sun => d.succ_opt(),
+            _ => Some(d),
+        })
+    };
+    let new_year = day(1, 1).and_then(|d| match d.weekday() {
+        Weekday::Sun => d.succ_opt(),
+        _ => Some(d),
+    });
+
+    [
+        new_year,
+        (year >= 1998)
+            .then(|| NaiveDate::from_weekday_of_month_opt(year, 1, Weekday::Mon, 3))
+            .flatten(),
+        NaiveDate::from_weekday_of_month_opt(year, 2, Weekday::Mon, 3),
+        easter(year).and_then(|d| d.checked_sub_days(Days::new(2))),
+        last_weekday_of_month(year, 5, Weekday::Mon),
+        (year >= 2022).then(|| observed(day(6, 19))).flatten(),
+        observed(day(7, 4)),
+        NaiveDate::from_weekday_of_month_opt(year, 9, Weekday::Mon, 1),
+        NaiveDate::from_weekday_of_month_opt(year, 11, Weekday::Thu, 4),
+        observed(day(12, 25)),
+    ]
+    .contains(&Some(date))
+}
+
+/// Last `weekday` of `month` in `year`.
+fn last_weekday_of_month(year: i32, month: u32, weekday: Weekday) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, 5)
+        .or_else(|| NaiveDate::from_weekday_of_month_opt(year, month, weekday, 4))
+}
+
+/// Western Easter Sunday (anonymous Gregorian algorithm).
+const fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_nyse_holidays() {
+        let nyse = TradingCalendar::nyse();
+        // 2024: New Year, MLK, Presidents, Good Friday, Memorial, Juneteenth,
+        // Independence, Labor, Thanksgiving, Christmas
+        let holidays: Vec<NaiveDate> = date("2024-01-01")
+            .iter_days()
+            .take_while(|d| d.year() == 2024)
+            .filter(|d| is_weekday(*d) && !nyse.is_trading_day(*d))
+            .collect();
+        assert_eq!(
+            holidays,
+            [
+                "2024-01-01",
+                "2024-01-15",
+                "2024-02-19",
+                "2024-03-29",
+                "2024-05-27",
+                "2024-06-19",
+                "2024-07-04",
+                "2024-09-02",
+                "2024-11-28",
+                "2024-12-25"
+            ]
+            .map(date)
+        );
+        // Observed on Friday and Monday; New Year's Day on a Saturday is not
+        assert!(!nyse.is_trading_day(date("2021-07-05")));
+        assert!(!nyse.is_trading_day(date("2021-12-24")));
+        assert!(nyse.is_trading_day(date("2021-12-31")));
+        assert_eq!(
+            nyse.trading_days(date("2024-01-01"), date("2024-12-31"))
+                .len(),
+            252
+        );
+
+        let closed = nyse.with_holidays([date("2025-01-09")]);
+        assert!(!closed.is_trading_day(date("2025-01-09")));
+    }
+
+    #[test]
+    fn test_day_counts() {
+        let nyse = TradingCalendar::nyse();
+        // Five sessions before Tuesday 2024-01-16 skip the weekend and MLK Day
+        assert_eq!(
+            nyse.sub_trading_days(date("2024-01-16"), 5),
+            Some(date("2024-01-08"))
+        );
+        assert_eq!(
+            DayCount::Calendar.window_start(date("2024-01-16"), 5),
+            date("2024-01-11")
+        );
+
+        let custom = TradingCalendar::from_sessions(["2024-01-02", "2024-01-04"].map(date));
+        assert_eq!(
+            custom.sub_trading_days(date("2024-01-05"), 2),
+            Some(date("2024-01-02"))
+        );
+        assert_eq!(custom.sub_trading_days(date("2024-01-05"), 3), None);
+        assert_eq!(
+            DayCount::Trading(custom).window_start(date("2024-01-05"), 3),
+            NaiveDate::MIN
+        );
+
+        assert_eq!(
+            TradingCalendar::all_days().sub_trading_days(date("2024-01-16"), 5),
+            Some(date("2024-01-11"))
+        );
+
+        let json = serde_json::to_string(&DayCount::default()).unwrap();
+        assert_eq!(json, r#"{"trading":{"calendar":"nyse","holidays":[]}}"#);
+        assert_eq!(
+            serde_json::from_str::<DayCount>(r#"{"trading":{"calendar":"weekdays"}}"#).unwrap(),
+            DayCount::Trading(TradingCalendar::weekdays())
+        );
+    }
+
+    #[test]
+    fn test_align_reindexes_onto_sessions() {
+        let data = df![
+            "symbol" => ["A", "A", "B"],
+            "date" => ["2024-01-11", "2024-01-16", "2024-01-12"],
+            "close" => [1.0, 3.0, 4.0],
+        ]
+        .unwrap()
+        .lazy();
+
+        let aligned = TradingCalendar::nyse()
+            .align(&data)
+            .unwrap()
+            .collect()
+            .unwrap();
+        let dates: Vec<String> = aligned
+            .column("date")
+            .unwrap()
+            .cast(&DataType::String)
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .map(String::from)
+            .collect();
+        // Friday is added and MLK Day skipped
+        assert_eq!(
+            dates,
+            ["2024-01-11", "2024-01-12", "2024-01-16", "2024-01-12"]
+        );
+        let close: Vec<Option<f64>> = aligned
+            .column("close")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(close, [Some(1.0), None, Some(3.0), Some(4.0)]);
+
+        // A Saturday row is reported rather than silently dropped
+        let weekend = df!["symbol" => ["A"], "date" => ["2024-01-13"], "close" => [2.0]]
+            .unwrap()
+            .lazy();
+        let stray = concat([data, weekend], Default::default()).unwrap();
+        let Err(err) = TradingCalendar::nyse().align(&stray) else {
+            panic!("closed-day rows should not be dropped");
+        };
+        assert!(err.to_string().contains("2024-01-13"), "{err}");
+        assert!(TradingCalendar::all_days().align(&stray).is_ok());
+    }
+
+    #[test]
+    fn test_align_keeps_symbol_type() {
+        let data = df![
+            "symbol" => [7i64, 7, 9],
+            "date" => ["2024-01-11", "2024-01-16", "2024-01-12"],
+            "close" => [1.0, 3.0, 4.0],
+        ]
+        .unwrap()
+        .lazy();
+
+        let aligned = TradingCalendar::nyse()
+            .align(&data)
+            .unwrap()
+            .collect()
+            .unwrap();
+        let symbols: Vec<i64> = aligned
+            .column("symbol")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(symbols, [7, 7, 7, 9]);
+        assert_eq!(aligned.column("close").unwrap().null_count(), 1);
+    }
+}
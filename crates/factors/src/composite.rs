@@ -0,0 +1,395 @@
+//! Composite factors built from other factors' scores.
+//!
+//! A [`CompositeFactor`] combines the standardized scores of its components,
+//! e.g. a value composite of `book_to_price`, `earnings_yield` and
+//! `fcf_yield`. A component's score is always its [`Factor::compute`] output:
+//! raw values through the component's own pipeline, a z-score by default.
+//!
+//! Components are declared through [`Factor::dependencies`], so inside a
+//! [`FactorRegistry`](crate::FactorRegistry) that also holds factors of those
+//! names the composite reads their scores instead of recomputing them. The
+//! registry passes those same [`Factor::compute`] scores, whatever pipeline it
+//! applies to its own output, so a composite scores identically inside and
+//! outside a registry. Standalone, it computes each component itself.
+
+use crate::{
+    Factor, FactorError, Result,
+    registry::FactorCategory,
+    traits::{DataFrequency, between_dates, check_date_range, on_date},
+};
+use chrono::NaiveDate;
+use polars::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// How a [`CompositeFactor`] weights its components.
+///
+/// Weights are scaled to unit absolute sum. Where a component is missing for
+/// a row, the remaining weights are rescaled over the components present.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Weighting {
+    /// Every component counts the same
+    #[default]
+    Equal,
+    /// Weights proportional to each component's information coefficient,
+    /// keyed by component name; a negative IC flips the component's sign
+    InformationCoefficient {
+        /// IC per component
+        ics: BTreeMap<String, f64>,
+    },
+    /// User-supplied weights, keyed by component name
+    Custom {
+        /// Weight per component
+        weights: BTreeMap<String, f64>,
+    },
+}
+
+impl Weighting {
+    /// Normalized weight for each of `names`, in order.
+    pub fn resolve(&self, names: &[&str]) -> Result<Vec<f64>> {
+        let lookup = |map: &BTreeMap<String, f64>, kind: &str| {
+            names
+                .iter()
+                .map(|name| {
+                    map.get(*name).copied().ok_or_else(|| {
+                        FactorError::InvalidSpec(format!("No {kind} for component '{name}'"))
+                    })
+                })
+                .collect::<Result<Vec<f64>>>()
+        };
+        let raw = match self {
+            Self::Equal => vec![1.0; names.len()],
+            Self::InformationCoefficient { ics } => lookup(ics, "IC")?,
+            Self::Custom { weights } => lookup(weights, "weight")?,
+        };
+
+        let total: f64 = raw.iter().map(|w| w.abs()).sum();
+        if total == 0.0 || !total.is_finite() {
+            return Err(FactorError::InvalidSpec(
+                "Composite weights must have a finite, non-zero sum".to_string(),
+            ));
+        }
+        Ok(raw.into_iter().map(|w| w / total).collect())
+    }
+}
+
+/// Weighted combination of other factors' standardized scores.
+///
+/// # Returns
+/// DataFrame with columns: `symbol`, `date`, and the composite's name
+#[derive(Debug, Clone)]
+pub struct CompositeFactor {
+    name: String,
+    description: String,
+    category: FactorCategory,
+    components: Vec<Arc<dyn Factor>>,
+    weighting: Weighting,
+}
+
+impl CompositeFactor {
+    /// Create an equal-weighted composite of `components`.
+    pub fn new(
+        name: impl Into<String>,
+        category: FactorCategory,
+        components: Vec<Arc<dyn Factor>>,
+    ) -> Self {
+        let names: Vec<&str> = components.iter().map(|c| c.name()).collect();
+        Self {
+            description: format!("Composite of {}", names.join(", ")),
+            name: name.into(),
+            category,
+            components,
+            weighting: Weighting::Equal,
+        }
+    }
+
+    /// Set how components are weighted.
+    pub fn with_weighting(mut self, weighting: Weighting) -> Self {
+        self.weighting = weighting;
+        self
+    }
+
+    /// Replace the generated description.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// The component factors.
+    pub fn components(&self) -> &[Arc<dyn Factor>] {
+        &self.components
+    }
+
+    /// How components are weighted.
+    pub const fn weighting(&self) -> &Weighting {
+        &self.weighting
+    }
+
+    /// Combine component scores for the rows selected by `rows`.
+    ///
+    /// Scores already present in `data` as columns named after a component
+    /// are used directly; the rest come from `compute`.
+    fn combine<F>(&self, data: &LazyFrame, rows: Expr, compute: F) -> Result<DataFrame>
+    where
+        F: Fn(&dyn Factor) -> Result<DataFrame>,
+    {
+        if self.components.is_empty() {
+            return Err(FactorError::InvalidSpec(format!(
+                "Composite '{}' has no components",
+                self.name
+            )));
+        }
+        let names: Vec<&str> = self.components.iter().map(|c| c.name()).collect();
+        let weights = self.weighting.resolve(&names)?;
+        let schema = data.clone().collect_schema()?;
+
+        let keys = [col("symbol"), col("date")];
+        let mut scores: Option<LazyFrame> = None;
+        for component in &self.components {
+            let name = component.name();
+            let frame = if schema.contains(name) {
+                data.clone()
+                    .filter(rows.clone())
+                    .select([col("symbol"), col("date"), col(name)])
+            } else {
+                compute(component.as_ref())?.lazy()
+            };
+            scores = Some(match scores {
+                None => frame,
+                Some(scores) => scores.join(
+                    frame,
+                    keys.clone(),
+                    keys.clone(),
+                    JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
+                ),
+            });
+        }
+
+        // Σ w·z over the components present, rescaled by their Σ|w|
+        let (weighted, present) = names.iter().zip(&weights).fold(
+            (lit(0.0), lit(0.0)),
+            |(weighted, present), (name, weight)| {
+                let available = col(*name).is_not_null();
+                (
+                    weighted
+                        + when(available.clone())
+                            .then(col(*name) * lit(*weight))
+                            .otherwise(lit(0.0)),
+                    present + when(available).then(lit(weight.abs())).otherwise(lit(0.0)),
+                )
+            },
+        );
+
+        Ok(scores
+            .expect("components is non-empty")
+            .with_column(
+                when(present.clone().gt(lit(0.0)))
+                    .then(weighted / present)
+                    .otherwise(lit(NULL))
+                    .alias(self.name.as_str()),
+            )
+            .select([col("symbol"), col("date"), col(self.name.as_str())])
+            .filter(col(self.name.as_str()).is_not_null())
+            .sort(["symbol", "date"], Default::default())
+            .collect()?)
+    }
+}
+
+impl Factor for CompositeFactor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn category(&self) -> FactorCategory {
+        self.category
+    }
+
+    /// Only the row keys: component inputs are checked by the components.
+    fn required_columns(&self) -> &[&str] {
+        &["symbol", "date"]
+    }
+
+    fn lookback(&self) -> usize {
+        self.components
+            .iter()
+            .map(|c| c.lookback())
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn frequency(&self) -> DataFrequency {
+        if self
+            .components
+            .iter()
+            .any(|c| c.frequency() == DataFrequency::Daily)
+        {
+            DataFrequency::Daily
+        } else {
+            DataFrequency::Quarterly
+        }
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.components
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect()
+    }
+
+    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        self.combine(data, on_date(date), |component| {
+            component.compute(data, date)
+        })
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+        self.combine(data, between_dates(start, end), |component| {
+            component.compute_panel(data, start, end)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        FactorRegistry, JoinMode,
+        pipeline::Pipeline,
+        value::{BookToPrice, EarningsYield},
+    };
+    use approx::assert_relative_eq;
+
+    fn value_data() -> LazyFrame {
+        df![
+            "symbol" => ["A", "B", "C", "D"],
+            "date" => ["2024-03-31"; 4],
+            "book_equity" => [10.0, 20.0, 30.0, 40.0],
+            "net_income" => [Some(4.0), Some(3.0), None, Some(1.0)],
+            "market_cap" => [100.0, 100.0, 100.0, 100.0],
+        ]
+        .unwrap()
+        .lazy()
+    }
+
+    fn composite() -> CompositeFactor {
+        CompositeFactor::new(
+            "value_composite",
+            FactorCategory::Value,
+            vec![
+                Arc::new(BookToPrice::default()),
+                Arc::new(EarningsYield::default()),
+            ],
+        )
+    }
+
+    fn column(df: &DataFrame, name: &str) -> Vec<f64> {
+        df.column(name)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_weighting_resolve() {
+        let names = ["a", "b"];
+        assert_eq!(Weighting::Equal.resolve(&names).unwrap(), [0.5, 0.5]);
+
+        let ics = Weighting::InformationCoefficient {
+            ics: BTreeMap::from([("a".to_string(), 0.03), ("b".to_string(), -0.01)]),
+        };
+        let weights = ics.resolve(&names).unwrap();
+        assert_relative_eq!(weights[0], 0.75);
+        assert_relative_eq!(weights[1], -0.25);
+
+        let custom = Weighting::Custom {
+            weights: BTreeMap::from([("a".to_string(), 1.0)]),
+        };
+        assert!(matches!(
+            custom.resolve(&names),
+            Err(FactorError::InvalidSpec(_))
+        ));
+    }
+
+    #[test]
+    fn test_composite_averages_available_scores() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let composite = composite();
+        assert_eq!(
+            composite.dependencies(),
+            ["book_to_price", "earnings_yield"]
+        );
+
+        let result = composite.compute_raw(&value_data(), date).unwrap();
+        let book = BookToPrice::default().compute(&value_data(), date).unwrap();
+        let earnings = EarningsYield::default()
+            .compute(&value_data(), date)
+            .unwrap();
+
+        let book = column(&book, "book_to_price");
+        let earnings = column(&earnings, "earnings_yield");
+        let values = column(&result, "value_composite");
+        assert_eq!(values.len(), 4);
+        assert_relative_eq!(values[0], (book[0] + earnings[0]) / 2.0, epsilon = 1e-12);
+        // C has no earnings, so only book-to-price counts
+        assert_relative_eq!(values[2], book[2], epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_composite_reads_precomputed_scores() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let data = df![
+            "symbol" => ["A", "B"],
+            "date" => ["2024-03-31"; 2],
+            "book_to_price" => [1.0, -1.0],
+            "earnings_yield" => [3.0, 1.0],
+        ]
+        .unwrap()
+        .lazy();
+        let composite = composite().with_weighting(Weighting::Custom {
+            weights: BTreeMap::from([
+                ("book_to_price".to_string(), 3.0),
+                ("earnings_yield".to_string(), 1.0),
+            ]),
+        });
+
+        let result = composite.compute_raw(&data, date).unwrap();
+        assert_eq!(column(&result, "value_composite"), [1.5, -0.5]);
+    }
+
+    #[test]
+    fn test_registry_matches_standalone() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        // The registry reports raw values, but components still feed in as
+        // z-scores
+        let raw = Pipeline::new();
+        let mut registry = FactorRegistry::new().with_pipeline(raw.clone());
+        registry.register(Arc::new(composite()));
+        registry.register(Arc::new(BookToPrice::default()));
+        registry.register(Arc::new(EarningsYield::default()));
+
+        let exposures = registry
+            .compute_all_with_coverage(&value_data(), date, JoinMode::Inner)
+            .unwrap()
+            .exposures;
+        let standalone = composite()
+            .compute_pipeline(&value_data(), date, &raw)
+            .unwrap();
+        assert_eq!(
+            column(&exposures, "value_composite"),
+            column(&standalone, "value_composite")
+        );
+    }
+}
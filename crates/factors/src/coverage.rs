@@ -0,0 +1,262 @@
+//! Joining per-factor exposures and reporting their coverage.
+//!
+//! [`FactorRegistry::compute_all`](crate::FactorRegistry::compute_all) inner
+//! joins every factor, so one sparse factor can silently shrink the universe.
+//! [`FactorRegistry::compute_all_with_coverage`](crate::FactorRegistry::compute_all_with_coverage)
+//! takes a [`JoinMode`] and returns the exposures alongside a
+//! [`FactorCoverage`] entry per factor.
+
+use crate::{FactorError, Result, pipeline::Pipeline};
+use polars::prelude::*;
+
+/// How per-factor exposures are combined on `symbol` and `date`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinMode {
+    /// Keep only rows every factor produced
+    #[default]
+    Inner,
+    /// Keep rows any factor produced, with nulls where a factor is missing
+    Outer,
+    /// Keep every `symbol`/`date` row of the input data, with nulls where a
+    /// factor is missing
+    Universe,
+}
+
+/// Coverage of a single factor in a joined exposure frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactorCoverage {
+    /// Factor name
+    pub name: String,
+    /// Rows the factor produced before joining
+    pub rows: usize,
+    /// Null values among those rows
+    pub null_count: usize,
+    /// Share of rows in the joined exposures with a non-null value
+    pub coverage: f64,
+    /// Why the factor was left out of the exposures, if it was
+    pub dropped: Option<String>,
+    /// Preprocessing applied to the factor's raw values
+    pub pipeline: Pipeline,
+}
+
+/// Joined exposures with per-factor coverage.
+#[derive(Debug, Clone)]
+pub struct Exposures {
+    /// Columns `symbol`, `date`, then one column per factor in name order
+    pub exposures: DataFrame,
+    /// One entry per factor, in name order
+    pub coverage: Vec<FactorCoverage>,
+}
+
+impl Exposures {
+    /// Coverage report as a DataFrame with columns `factor`, `rows`,
+    /// `null_count`, `coverage`, `dropped` and `pipeline`, the last holding
+    /// each factor's transforms as JSON.
+    pub fn coverage_frame(&self) -> Result<DataFrame> {
+        let names: Vec<&str> = self.coverage.iter().map(|c| c.name.as_str()).collect();
+        let rows: Vec<u64> = self.coverage.iter().map(|c| c.rows as u64).collect();
+        let nulls: Vec<u64> = self.coverage.iter().map(|c| c.null_count as u64).collect();
+        let coverage: Vec<f64> = self.coverage.iter().map(|c| c.coverage).collect();
+        let dropped: Vec<Option<&str>> =
+            self.coverage.iter().map(|c| c.dropped.as_deref()).collect();
+        let pipelines = self
+            .coverage
+            .iter()
+            .map(|c| serde_json::to_string(c.pipeline.transforms()))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| FactorError::Computation(format!("pipeline serialization: {e}")))?;
+
+        Ok(df![
+            "factor" => names,
+            "rows" => rows,
+            "null_count" => nulls,
+            "coverage" => coverage,
+            "dropped" => dropped,
+            "pipeline" => pipelines,
+        ]?)
+    }
+}
+
+/// Inner-join exposures, failing on the first factor error.
+///
+/// Used by the plain `compute_all*` methods; rows are sorted by `symbol` and
+/// `date`.
+pub(crate) fn join_exposures(
+    exposures: impl Iterator<Item = Result<DataFrame>>,
+) -> Result<DataFrame> {
+    let frames = exposures.collect::<Result<Vec<_>>>()?;
+    if frames.is_empty() {
+        return Err(FactorError::Computation(
+            "No factors registered".to_string(),
+        ));
+    }
+    join(frames, JoinMode::Inner, None)
+}
+
+/// Join exposures, recording failed factors in the coverage report instead of
+/// failing.
+///
+/// `results` pairs each factor name with the pipeline applied to it and its
+/// exposures; `universe` holds the distinct `symbol`/`date` rows of the input. If every factor fails, the
+/// exposures contain only the universe keys.
+pub(crate) fn collect_exposures(
+    results: Vec<(String, Pipeline, Result<DataFrame>)>,
+    mode: JoinMode,
+    universe: LazyFrame,
+) -> Result<Exposures> {
+    let mut coverage = Vec::with_capacity(results.len());
+    let mut frames = Vec::with_capacity(results.len());
+
+    for (name, pipeline, result) in results {
+        match result {
+            Ok(df) => {
+                coverage.push(FactorCoverage {
+                    rows: df.height(),
+                    null_count: df.column(&name)?.null_count(),
+                    coverage: 0.0,
+                    dropped: None,
+                    name,
+                    pipeline,
+                });
+                frames.push(df);
+            }
+            Err(e) => coverage.push(FactorCoverage {
+                name,
+                rows: 0,
+                null_count: 0,
+                coverage: 0.0,
+                dropped: Some(e.to_string()),
+                pipeline,
+            }),
+        }
+    }
+
+    let exposures = if frames.is_empty() {
+        universe.collect()?
+    } else {
+        join(frames, mode, Some(universe))?
+    };
+
+    if exposures.height() > 0 {
+        for entry in coverage.iter_mut().filter(|c| c.dropped.is_none()) {
+            let column = exposures.column(&entry.name)?;
+            let present = column.len() - column.null_count();
+            entry.coverage = present as f64 / exposures.height() as f64;
+        }
+    }
+
+    Ok(Exposures {
+        exposures,
+        coverage,
+    })
+}
+
+/// Join non-empty `frames` on `symbol` and `date` and sort the rows.
+fn join(frames: Vec<DataFrame>, mode: JoinMode, universe: Option<LazyFrame>) -> Result<DataFrame> {
+    let keys = [col("symbol"), col("date")];
+    let mut frames = frames.into_iter().map(DataFrame::lazy);
+
+    let (mut result, how) = match (mode, universe) {
+        (JoinMode::Universe, Some(universe)) => (universe, JoinType::Left),
+        (JoinMode::Universe, None) | (JoinMode::Inner, _) => {
+            (frames.next().expect("frames is non-empty"), JoinType::Inner)
+        }
+        (JoinMode::Outer, _) => (frames.next().expect("frames is non-empty"), JoinType::Full),
+    };
+
+    for frame in frames {
+        let args = JoinArgs::new(how.clone()).with_coalesce(JoinCoalesce::CoalesceColumns);
+        result = result.join(frame, keys.clone(), keys.clone(), args);
+    }
+
+    Ok(result
+        .sort(["symbol", "date"], Default::default())
+        .collect()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(name: &str, symbols: &[&str]) -> DataFrame {
+        let n = symbols.len();
+        DataFrame::new(vec![
+            Column::new("symbol".into(), symbols),
+            Column::new("date".into(), vec!["2024-01-02"; n]),
+            Column::new(name.into(), vec![1.0; n]),
+        ])
+        .unwrap()
+    }
+
+    fn universe() -> LazyFrame {
+        df![
+            "symbol" => ["A", "B", "C", "D"],
+            "date" => ["2024-01-02"; 4],
+        ]
+        .unwrap()
+        .lazy()
+    }
+
+    fn results() -> Vec<(String, Pipeline, Result<DataFrame>)> {
+        vec![
+            (
+                "dense".to_string(),
+                Pipeline::default(),
+                Ok(frame("dense", &["C", "A", "B"])),
+            ),
+            (
+                "missing".to_string(),
+                Pipeline::default(),
+                Err(FactorError::MissingColumn("bid".to_string())),
+            ),
+            (
+                "sparse".to_string(),
+                Pipeline::new(),
+                Ok(frame("sparse", &["B"])),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_join_modes() {
+        let heights: Vec<usize> = [JoinMode::Inner, JoinMode::Outer, JoinMode::Universe]
+            .into_iter()
+            .map(|mode| {
+                collect_exposures(results(), mode, universe())
+                    .unwrap()
+                    .exposures
+                    .height()
+            })
+            .collect();
+        assert_eq!(heights, [1, 3, 4]);
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        let report = collect_exposures(results(), JoinMode::Universe, universe()).unwrap();
+
+        assert_eq!(
+            report.exposures.get_column_names(),
+            ["symbol", "date", "dense", "sparse"]
+        );
+        let symbols: Vec<&str> = report
+            .exposures
+            .column("symbol")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(symbols, ["A", "B", "C", "D"]);
+
+        let dense = &report.coverage[0];
+        assert_eq!((dense.rows, dense.coverage), (3, 0.75));
+        let missing = &report.coverage[1];
+        assert!(missing.dropped.as_deref().unwrap().contains("bid"));
+        let sparse = &report.coverage[2];
+        assert_eq!((sparse.rows, sparse.coverage), (1, 0.25));
+
+        assert_eq!(report.coverage_frame().unwrap().height(), 3);
+    }
+}
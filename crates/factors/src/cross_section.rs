@@ -0,0 +1,56 @@
+//! Per-date helpers shared by the cross-sectional operations.
+//!
+//! Neutralization, orthogonalization, factor-return regressions and
+//! redundancy analysis all work on one date's rows at a time, usually fitting
+//! a least-squares regression to them.
+
+use crate::{FactorError, Result};
+use ndarray::{Array1, Array2};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// `columns` of `df` cast to `Float64`.
+pub(crate) fn float_columns(df: &DataFrame, columns: &[&str]) -> Result<Vec<Float64Chunked>> {
+    columns
+        .iter()
+        .map(|&name| Ok(df.column(name)?.cast(&DataType::Float64)?.f64()?.clone()))
+        .collect()
+}
+
+/// Indices of the rows passing `keep`, grouped by date.
+pub(crate) fn rows_by_date(
+    df: &DataFrame,
+    keep: impl Fn(usize) -> bool,
+) -> Result<HashMap<String, Vec<usize>>> {
+    let dates = df.column("date")?.cast(&DataType::String)?;
+    let dates = dates.str()?;
+
+    let mut rows_by_date: HashMap<String, Vec<usize>> = HashMap::new();
+    for row in 0..df.height() {
+        if let (true, Some(date)) = (keep(row), dates.get(row)) {
+            rows_by_date.entry(date.to_string()).or_default().push(row);
+        }
+    }
+    Ok(rows_by_date)
+}
+
+/// Fit `y` on `x` by weighted least squares and store each of `rows`'
+/// residuals in `residuals`.
+///
+/// Rows are scaled by `w` before squaring, so pass square roots of regression
+/// weights. `operation` names the caller in errors.
+pub(crate) fn fit_residuals(
+    rows: &[usize],
+    y: &Array1<f64>,
+    x: &Array2<f64>,
+    w: &Array1<f64>,
+    operation: &str,
+    residuals: &mut [Option<f64>],
+) -> Result<()> {
+    let fit = toraniko_math::weighted_least_squares(y, x, w)
+        .map_err(|e| FactorError::Computation(format!("{operation} failed: {e}")))?;
+    for (&row, &residual) in rows.iter().zip(fit.residuals.iter()) {
+        residuals[row] = Some(residual);
+    }
+    Ok(())
+}
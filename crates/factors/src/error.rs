@@ -12,6 +12,30 @@ pub enum FactorError {
     #[error("Missing required column: {0}")]
     MissingColumn(String),
 
+    /// Several required columns missing from input data
+    #[error("Missing required columns: {}", .0.join(", "))]
+    MissingColumns(Vec<String>),
+
+    /// Required columns missing from a corporate action event table
+    #[error("Missing required columns in {events} events: {}", .columns.join(", "))]
+    MissingEventColumns {
+        /// Kind of event table, such as `split` or `dividend`
+        events: String,
+        /// Missing column names
+        columns: Vec<String>,
+    },
+
+    /// Input column has an incompatible data type
+    #[error("Invalid type for column {column}: expected {expected}, got {actual}")]
+    InvalidColumnType {
+        /// Column name
+        column: String,
+        /// Expected kind of data type
+        expected: String,
+        /// Actual data type found in the input
+        actual: String,
+    },
+
     /// Insufficient data for lookback period
     #[error("Insufficient data: need {required} periods, got {available}")]
     InsufficientData {
@@ -34,10 +58,18 @@ pub enum FactorError {
     #[error("DataFrame error: {0}")]
     Polars(#[from] polars::error::PolarsError),
 
+    /// Invalid registry or factor specification
+    #[error("Invalid spec: {0}")]
+    InvalidSpec(String),
+
     /// Factor not found in registry
     #[error("Factor not found: {0}")]
     NotFound(String),
 
+    /// Factor dependencies form a cycle, listed from and back to one factor
+    #[error("Dependency cycle: {}", .0.join(" -> "))]
+    DependencyCycle(Vec<String>),
+
     /// Computation error
     #[error("Computation error: {0}")]
     Computation(String),
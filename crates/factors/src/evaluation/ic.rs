@@ -0,0 +1,335 @@
+//! Information coefficient (IC) analysis.
+//!
+//! The IC on a date is the cross-sectional correlation between exposures and
+//! the following returns. Pearson IC uses the values directly; rank IC
+//! (Spearman) uses their ranks and is robust to outliers.
+
+use super::{forward_return_column, forward_returns, stack};
+use crate::Result;
+use polars::prelude::*;
+
+/// Per-date Pearson and rank IC of every factor.
+///
+/// Exposures and returns are matched on `symbol` and `date`; rows where
+/// either is null are skipped. Dates where a correlation is undefined, e.g.
+/// fewer than two rows or constant exposures, get a null IC.
+///
+/// # Arguments
+///
+/// * `exposures` - Wide exposures: `symbol`, `date`, one column per factor
+/// * `returns` - DataFrame with `symbol`, `date` and `return_column`
+/// * `return_column` - Forward return column, e.g. from
+///   [`forward_returns`](super::forward_returns)
+///
+/// # Returns
+///
+/// DataFrame with columns: `factor`, `date`, `ic`, `rank_ic`, `count`, sorted
+/// by factor and date.
+pub fn information_coefficient(
+    exposures: &DataFrame,
+    returns: &DataFrame,
+    return_column: &str,
+) -> Result<DataFrame> {
+    let keys = [col("symbol"), col("date")];
+    let group = [col("factor"), col("date")];
+    let demeaned = |name: &str| col(name) - col(name).mean().over(group.clone());
+    let correlation = |x: &str, y: &str| {
+        let corr = (col(x) * col(y)).sum() / (col(x).pow(2).sum() * col(y).pow(2).sum()).sqrt();
+        when(corr.clone().is_finite())
+            .then(corr)
+            .otherwise(lit(NULL))
+    };
+    let rank = |name: &str| {
+        col(name)
+            .rank(
+                RankOptions {
+                    method: RankMethod::Average,
+                    descending: false,
+                },
+                None,
+            )
+            .over(group.clone())
+            .cast(DataType::Float64)
+    };
+
+    let result = stack(exposures)?
+        .join(
+            returns.clone().lazy().select([
+                col("symbol"),
+                col("date"),
+                col(return_column).cast(DataType::Float64).alias("return"),
+            ]),
+            keys.clone(),
+            keys,
+            JoinArgs::new(JoinType::Inner),
+        )
+        .filter(
+            col("value")
+                .is_not_null()
+                .and(col("return").is_not_null())
+                .and(col("value").is_finite())
+                .and(col("return").is_finite()),
+        )
+        .with_columns([
+            rank("value").alias("value_rank"),
+            rank("return").alias("return_rank"),
+        ])
+        .with_columns([
+            demeaned("value").alias("value"),
+            demeaned("return").alias("return"),
+            demeaned("value_rank").alias("value_rank"),
+            demeaned("return_rank").alias("return_rank"),
+        ])
+        .group_by(group.clone())
+        .agg([
+            correlation("value", "return").alias("ic"),
+            correlation("value_rank", "return_rank").alias("rank_ic"),
+            len().cast(DataType::UInt64).alias("count"),
+        ])
+        .sort(["factor", "date"], Default::default())
+        .collect()?;
+
+    Ok(result)
+}
+
+/// Summary statistics of per-date ICs for each factor.
+///
+/// For both `ic` and `rank_ic`, reports the mean, standard deviation,
+/// information ratio (mean / std), t-statistic (IR × √periods) and hit rate
+/// (share of dates with a positive IC). The t-statistic assumes independent
+/// dates, which overlapping multi-period returns violate.
+///
+/// # Arguments
+///
+/// * `ic` - Output of [`information_coefficient`]
+///
+/// # Returns
+///
+/// DataFrame with columns: `factor`, `periods`, `ic_mean`, `ic_std`, `ic_ir`,
+/// `ic_t_stat`, `ic_hit_rate`, and the same five with a `rank_ic_` prefix,
+/// sorted by factor.
+pub fn ic_summary(ic: &DataFrame) -> Result<DataFrame> {
+    let stats = |name: &str| {
+        let mean = col(name).mean();
+        let std = col(name).std(1);
+        let ir = mean.clone() / std.clone();
+        [
+            mean.alias(format!("{name}_mean")),
+            std.alias(format!("{name}_std")),
+            ir.clone().alias(format!("{name}_ir")),
+            (ir * col(name).count().cast(DataType::Float64).sqrt()).alias(format!("{name}_t_stat")),
+            col(name)
+                .gt(lit(0.0))
+                .cast(DataType::Float64)
+                .mean()
+                .alias(format!("{name}_hit_rate")),
+        ]
+    };
+
+    let mut aggs = vec![col("ic").count().cast(DataType::UInt64).alias("periods")];
+    aggs.extend(stats("ic"));
+    aggs.extend(stats("rank_ic"));
+
+    let result = ic
+        .clone()
+        .lazy()
+        .group_by([col("factor")])
+        .agg(aggs)
+        .sort(["factor"], Default::default())
+        .collect()?;
+
+    Ok(result)
+}
+
+/// Mean IC at each forward horizon from 1 to `max_horizon` periods.
+///
+/// Shows how quickly a factor's predictive power fades.
+///
+/// # Arguments
+///
+/// * `exposures` - Wide exposures: `symbol`, `date`, one column per factor
+/// * `prices` - Data with columns: `symbol`, `date`, `close`
+/// * `max_horizon` - Longest forward horizon in periods
+///
+/// # Returns
+///
+/// DataFrame with columns: `factor`, `horizon`, `periods`, `ic_mean`,
+/// `ic_ir`, `rank_ic_mean`, `rank_ic_ir`, sorted by factor and horizon.
+pub fn ic_decay(
+    exposures: &DataFrame,
+    prices: &LazyFrame,
+    max_horizon: usize,
+) -> Result<DataFrame> {
+    let horizons: Vec<usize> = (1..=max_horizon).collect();
+    let returns = forward_returns(prices, &horizons)?;
+
+    let mut frames = Vec::with_capacity(horizons.len());
+    for horizon in horizons {
+        let ic = information_coefficient(exposures, &returns, &forward_return_column(horizon))?;
+        let summary = ic_summary(&ic)?.lazy().select([
+            col("factor"),
+            lit(horizon as u64).cast(DataType::UInt64).alias("horizon"),
+            col("periods"),
+            col("ic_mean"),
+            col("ic_ir"),
+            col("rank_ic_mean"),
+            col("rank_ic_ir"),
+        ]);
+        frames.push(summary);
+    }
+    if frames.is_empty() {
+        return Err(crate::FactorError::InvalidSpec(
+            "IC decay needs a horizon of at least 1".to_string(),
+        ));
+    }
+
+    let result = concat(frames, UnionArgs::default())?
+        .sort(["factor", "horizon"], Default::default())
+        .collect()?;
+
+    Ok(result)
+}
+
+/// Trailing mean of per-date ICs over `window` dates, per factor.
+///
+/// # Arguments
+///
+/// * `ic` - Output of [`information_coefficient`]
+/// * `window` - Number of dates in the rolling window
+///
+/// # Returns
+///
+/// DataFrame with columns: `factor`, `date`, `rolling_ic`, `rolling_rank_ic`.
+/// Dates before a full window of non-null ICs are null.
+pub fn rolling_ic(ic: &DataFrame, window: usize) -> Result<DataFrame> {
+    let rolling = |name: &str| {
+        col(name)
+            .rolling_mean(RollingOptionsFixedWindow {
+                window_size: window,
+                min_periods: window,
+                ..Default::default()
+            })
+            .over([col("factor")])
+    };
+
+    let result = ic
+        .clone()
+        .lazy()
+        .sort(["factor", "date"], Default::default())
+        .select([
+            col("factor"),
+            col("date"),
+            rolling("ic").alias("rolling_ic"),
+            rolling("rank_ic").alias("rolling_rank_ic"),
+        ])
+        .collect()?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Five symbols over six days; `signal` ranks tomorrow's return
+    /// perfectly and `noise` does not.
+    fn panel() -> (DataFrame, LazyFrame) {
+        let symbols = ["A", "B", "C", "D", "E"];
+        let mut sym = Vec::new();
+        let mut dates = Vec::new();
+        let mut close = Vec::new();
+        let mut signal = Vec::new();
+        let mut noise = Vec::new();
+        for (s, symbol) in symbols.into_iter().enumerate() {
+            let mut price = 100.0;
+            for day in 0..6 {
+                // Tomorrow's return grows with the symbol index, cubed so
+                // Pearson and rank IC differ
+                let next =
+                    0.001 * ((s as f64) - 2.0).powi(3) * if day % 2 == 0 { 1.0 } else { 2.0 };
+                sym.push(symbol);
+                dates.push(format!("2024-01-0{}", day + 1));
+                close.push(price);
+                signal.push(s as f64);
+                noise.push(((s * 7 + day * 3) % 5) as f64);
+                price *= 1.0 + next;
+            }
+        }
+        let exposures = df![
+            "symbol" => sym.clone(),
+            "date" => dates.clone(),
+            "signal" => signal,
+            "noise" => noise,
+        ]
+        .unwrap();
+        let prices = df![
+            "symbol" => sym,
+            "date" => dates,
+            "close" => close,
+        ]
+        .unwrap()
+        .lazy();
+        (exposures, prices)
+    }
+
+    #[test]
+    fn test_information_coefficient_and_summary() {
+        let (exposures, prices) = panel();
+        let returns = forward_returns(&prices, &[1]).unwrap();
+        let ic = information_coefficient(&exposures, &returns, "forward_return_1").unwrap();
+
+        // Five dates have a next-day return for each of two factors
+        assert_eq!(ic.height(), 10);
+        let signal = ic
+            .clone()
+            .lazy()
+            .filter(col("factor").eq(lit("signal")))
+            .collect()
+            .unwrap();
+        for rank_ic in signal.column("rank_ic").unwrap().f64().unwrap() {
+            assert_relative_eq!(rank_ic.unwrap(), 1.0, epsilon = 1e-12);
+        }
+        let pearson = signal.column("ic").unwrap().f64().unwrap().get(0).unwrap();
+        assert!(pearson > 0.8 && pearson < 1.0);
+
+        let summary = ic_summary(&ic).unwrap();
+        assert_eq!(
+            summary.column("factor").unwrap().str().unwrap().get(1),
+            Some("signal")
+        );
+        let hit_rate = summary.column("rank_ic_hit_rate").unwrap().f64().unwrap();
+        assert_relative_eq!(hit_rate.get(1).unwrap(), 1.0);
+        assert_eq!(
+            summary.column("periods").unwrap().u64().unwrap().get(1),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn test_ic_decay_and_rolling_ic() {
+        let (exposures, prices) = panel();
+
+        let decay = ic_decay(&exposures, &prices, 3).unwrap();
+        assert_eq!(decay.height(), 6);
+        let horizons: Vec<u64> = decay
+            .column("horizon")
+            .unwrap()
+            .u64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(horizons, [1, 2, 3, 1, 2, 3]);
+        let periods = decay.column("periods").unwrap().u64().unwrap();
+        assert_eq!(periods.get(3), Some(5));
+        assert_eq!(periods.get(5), Some(3));
+
+        let returns = forward_returns(&prices, &[1]).unwrap();
+        let ic = information_coefficient(&exposures, &returns, "forward_return_1").unwrap();
+        let rolling = rolling_ic(&ic, 2).unwrap();
+        assert_eq!(rolling.height(), ic.height());
+        let signal = rolling.column("rolling_rank_ic").unwrap().f64().unwrap();
+        assert!(signal.get(5).is_none());
+        assert_relative_eq!(signal.get(6).unwrap(), 1.0, epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,124 @@
+//! Factor evaluation - how well exposures predict returns
+//!
+//! Evaluation functions take wide exposure frames as produced by
+//! [`Factor::compute`](crate::Factor::compute) or
+//! [`FactorRegistry::compute_all`](crate::FactorRegistry::compute_all):
+//! `symbol`, `date`, then one column per factor. Forward returns come from
+//! [`forward_returns`]. Results are long DataFrames keyed by a `factor`
+//! column.
+
+pub mod ic;
+pub mod quantile;
+pub mod redundancy;
+pub mod turnover;
+
+pub use ic::{ic_decay, ic_summary, information_coefficient, rolling_ic};
+pub use quantile::{BucketWeighting, QuantileConfig, QuantileReport, quantile_backtest};
+pub use redundancy::{
+    CorrelationMethod, RedundancyConfig, RedundancyReport, factor_correlation, redundancy_report,
+};
+pub use turnover::{TurnoverSummary, quantile_turnover, rank_autocorrelation, turnover_summary};
+
+use crate::Result;
+use polars::prelude::*;
+
+/// Name of the forward return column for `horizon` periods.
+pub fn forward_return_column(horizon: usize) -> String {
+    format!("forward_return_{horizon}")
+}
+
+/// Forward close-to-close returns per symbol.
+///
+/// For each horizon `h`, adds `forward_return_{h}` = `close[t + h] / close[t] - 1`,
+/// counting rows (trading days) per symbol. The last `h` rows of each symbol
+/// are null.
+///
+/// # Arguments
+///
+/// * `prices` - Data with columns: `symbol`, `date`, `close`
+/// * `horizons` - Forward horizons in periods
+///
+/// # Returns
+///
+/// DataFrame with columns: `symbol`, `date`, and one column per horizon.
+pub fn forward_returns(prices: &LazyFrame, horizons: &[usize]) -> Result<DataFrame> {
+    let mut columns = vec![col("symbol"), col("date")];
+    columns.extend(horizons.iter().map(|&h| {
+        (col("close").shift(lit(-(h as i64))).over([col("symbol")]) / col("close") - lit(1.0))
+            .alias(forward_return_column(h))
+    }));
+
+    Ok(prices
+        .clone()
+        .sort(
+            ["symbol", "date"],
+            SortMultipleOptions::default().with_order_descending_multi([false, false]),
+        )
+        .select(columns)
+        .collect()?)
+}
+
+/// Factor columns of a wide exposure frame: every column but `symbol` and
+/// `date`.
+pub(crate) fn factor_columns(exposures: &DataFrame) -> Vec<String> {
+    exposures
+        .get_column_names()
+        .into_iter()
+        .filter(|name| !matches!(name.as_str(), "symbol" | "date"))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Exposures stacked into long form with columns `factor`, `symbol`, `date`
+/// and `value`.
+pub(crate) fn stack(exposures: &DataFrame) -> Result<LazyFrame> {
+    let frames: Vec<LazyFrame> = factor_columns(exposures)
+        .into_iter()
+        .map(|name| {
+            exposures.clone().lazy().select([
+                lit(name.as_str()).alias("factor"),
+                col("symbol"),
+                col("date"),
+                col(name.as_str()).cast(DataType::Float64).alias("value"),
+            ])
+        })
+        .collect();
+    if frames.is_empty() {
+        return Err(crate::FactorError::MissingColumn(
+            "exposures have no factor columns".to_string(),
+        ));
+    }
+    Ok(concat(frames, UnionArgs::default())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_returns() {
+        let prices = df![
+            "symbol" => ["A", "A", "A", "B", "B", "B"],
+            "date" => ["2024-01-03", "2024-01-02", "2024-01-04", "2024-01-02", "2024-01-03", "2024-01-04"],
+            "close" => [110.0, 100.0, 99.0, 50.0, 55.0, 44.0],
+        ]
+        .unwrap()
+        .lazy();
+
+        let returns = forward_returns(&prices, &[1, 2]).unwrap();
+        let one: Vec<Option<f64>> = returns
+            .column("forward_return_1")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.map(|r| (r * 1e6).round() / 1e6))
+            .collect();
+        assert_eq!(
+            one,
+            [Some(0.1), Some(-0.1), None, Some(0.1), Some(-0.2), None]
+        );
+        let two = returns.column("forward_return_2").unwrap();
+        assert_eq!(two.null_count(), 4);
+    }
+}
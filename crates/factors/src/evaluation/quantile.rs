@@ -0,0 +1,605 @@
+//! Quantile portfolio backtests.
+//!
+//! On each rebalance date, every factor's cross-section is sorted into `N`
+//! equal-count buckets, bucket 1 holding the lowest exposures and bucket `N`
+//! the highest. Each bucket is held as an equal- or cap-weighted portfolio
+//! for the holding period. When the holding period is longer than the
+//! rebalance interval the portfolios overlap, and each period's bucket return
+//! averages the portfolios still held (Jegadeesh-Titman).
+
+use super::stack;
+use crate::{FactorError, Result};
+use polars::prelude::*;
+
+/// How stocks are weighted within a quantile bucket.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum BucketWeighting {
+    /// Every stock in a bucket gets the same weight
+    #[default]
+    Equal,
+    /// Weight by a column of the returns panel, e.g. `market_cap`
+    CapWeighted {
+        /// Weight column
+        column: String,
+    },
+}
+
+/// Configuration for [`quantile_backtest`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct QuantileConfig {
+    /// Number of buckets, e.g. 5 for quintiles or 10 for deciles
+    pub quantiles: usize,
+    /// Column of the returns panel holding each row's one-period forward
+    /// return
+    pub return_column: String,
+    /// Weighting within buckets
+    pub weighting: BucketWeighting,
+    /// Periods between rebalances
+    pub rebalance_every: usize,
+    /// Periods each portfolio is held; `None` holds until the next rebalance
+    pub holding_period: Option<usize>,
+}
+
+impl Default for QuantileConfig {
+    fn default() -> Self {
+        Self {
+            quantiles: 5,
+            return_column: "forward_return_1".to_string(),
+            weighting: BucketWeighting::Equal,
+            rebalance_every: 1,
+            holding_period: None,
+        }
+    }
+}
+
+impl QuantileConfig {
+    /// Set the number of buckets.
+    pub const fn with_quantiles(mut self, quantiles: usize) -> Self {
+        self.quantiles = quantiles;
+        self
+    }
+
+    /// Set the forward return column.
+    pub fn with_return_column(mut self, column: impl Into<String>) -> Self {
+        self.return_column = column.into();
+        self
+    }
+
+    /// Set the weighting within buckets.
+    pub fn with_weighting(mut self, weighting: BucketWeighting) -> Self {
+        self.weighting = weighting;
+        self
+    }
+
+    /// Set the number of periods between rebalances.
+    pub const fn with_rebalance_every(mut self, periods: usize) -> Self {
+        self.rebalance_every = periods;
+        self
+    }
+
+    /// Set the number of periods each portfolio is held.
+    pub const fn with_holding_period(mut self, periods: usize) -> Self {
+        self.holding_period = Some(periods);
+        self
+    }
+
+    /// Periods each portfolio is held.
+    pub fn holding(&self) -> usize {
+        self.holding_period.unwrap_or(self.rebalance_every)
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.quantiles < 2 {
+            return Err(FactorError::InvalidSpec(
+                "quantile backtests need at least 2 buckets".to_string(),
+            ));
+        }
+        if self.rebalance_every == 0 || self.holding() == 0 {
+            return Err(FactorError::InvalidSpec(
+                "rebalance interval and holding period must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Results of a [`quantile_backtest`], all keyed by `factor`.
+#[derive(Debug, Clone)]
+pub struct QuantileReport {
+    /// Columns `factor`, `date`, `bucket`, `return`, `cumulative_return`,
+    /// `drawdown`: each bucket's return over the period starting at `date`
+    pub returns: DataFrame,
+    /// Columns `factor`, `date`, `long_short`, `cumulative_return`,
+    /// `drawdown`: top bucket minus bottom bucket
+    pub long_short: DataFrame,
+    /// Columns `factor`, `date`, `bucket`, `turnover`: one-way turnover of
+    /// each bucket at every rebalance after the first
+    pub turnover: DataFrame,
+    /// Columns `factor`, `bucket`, `periods`, `mean_return`,
+    /// `cumulative_return`, `max_drawdown`, `mean_turnover`
+    pub buckets: DataFrame,
+    /// Columns `factor`, `long_short_mean`, `long_short_cumulative`,
+    /// `long_short_max_drawdown`, `monotonicity`: the last is the rank
+    /// correlation between bucket number and mean bucket return, `1` when
+    /// returns rise strictly from bottom to top bucket
+    pub summary: DataFrame,
+}
+
+/// Sort exposures into quantile portfolios and track their returns.
+///
+/// Rebalance dates are every `rebalance_every`-th date of the returns panel,
+/// starting from its first. Stocks without a forward return in a period are
+/// left out of that period's bucket return, with the remaining weights
+/// rescaled.
+///
+/// # Arguments
+///
+/// * `exposures` - Wide exposures: `symbol`, `date`, one column per factor
+/// * `returns` - Panel with `symbol`, `date`, the configured return column
+///   and, for cap weighting, the weight column
+/// * `config` - Bucket count, weighting, rebalance and holding settings
+pub fn quantile_backtest(
+    exposures: &DataFrame,
+    returns: &DataFrame,
+    config: &QuantileConfig,
+) -> Result<QuantileReport> {
+    config.check()?;
+    let n = config.quantiles as i64;
+    let step = config.rebalance_every as i64;
+
+    // Period index of every date in the returns panel
+    let calendar = returns
+        .clone()
+        .lazy()
+        .select([col("date")])
+        .unique(None, UniqueKeepStrategy::Any)
+        .sort(["date"], Default::default())
+        .with_row_index("period", None)
+        .with_column(col("period").cast(DataType::Int64))
+        .collect()?
+        .lazy();
+
+    let positions = positions(exposures, returns, &calendar, config)?;
+
+    // Each portfolio is held for `holding` periods from its rebalance
+    let held = concat(
+        (0..config.holding() as i64)
+            .map(|k| {
+                positions
+                    .clone()
+                    .with_column((col("period") + lit(k)).alias("held_period"))
+            })
+            .collect::<Vec<_>>(),
+        UnionArgs::default(),
+    )?;
+    let period_returns = returns
+        .clone()
+        .lazy()
+        .join(
+            calendar.clone(),
+            [col("date")],
+            [col("date")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .select([
+            col("symbol"),
+            col("period").alias("held_period"),
+            col(config.return_column.as_str())
+                .cast(DataType::Float64)
+                .alias("return"),
+        ])
+        .filter(col("return").is_not_null().and(col("return").is_finite()));
+
+    let bucket_keys = [col("factor"), col("bucket")];
+    let bucket_returns = held
+        .join(
+            period_returns,
+            [col("symbol"), col("held_period")],
+            [col("symbol"), col("held_period")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        // Return of each portfolio still held, then their average
+        .group_by([
+            col("factor"),
+            col("held_period"),
+            col("period"),
+            col("bucket"),
+        ])
+        .agg([((col("weight") * col("return")).sum() / col("weight").sum()).alias("return")])
+        .group_by([col("factor"), col("held_period"), col("bucket")])
+        .agg([col("return").mean()])
+        .join(
+            calendar.clone(),
+            [col("held_period")],
+            [col("period")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .select([col("factor"), col("date"), col("bucket"), col("return")])
+        .sort(["factor", "bucket", "date"], Default::default());
+    let bucket_returns = performance(bucket_returns, &bucket_keys).collect()?;
+
+    let side = |bucket: i64, name: &str| {
+        bucket_returns
+            .clone()
+            .lazy()
+            .filter(col("bucket").eq(lit(bucket)))
+            .select([col("factor"), col("date"), col("return").alias(name)])
+    };
+    let long_short = side(n, "top")
+        .join(
+            side(1, "bottom"),
+            [col("factor"), col("date")],
+            [col("factor"), col("date")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .select([
+            col("factor"),
+            col("date"),
+            (col("top") - col("bottom")).alias("return"),
+        ])
+        .sort(["factor", "date"], Default::default());
+    let long_short = performance(long_short, &[col("factor")])
+        .rename(["return"], ["long_short"], true)
+        .collect()?;
+
+    let turnover = turnover(&positions, step)?
+        .join(
+            calendar,
+            [col("period")],
+            [col("period")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .select([col("factor"), col("date"), col("bucket"), col("turnover")])
+        .sort(["factor", "bucket", "date"], Default::default())
+        .collect()?;
+
+    let buckets = bucket_returns
+        .clone()
+        .lazy()
+        .group_by(bucket_keys.clone())
+        .agg([
+            len().cast(DataType::UInt64).alias("periods"),
+            col("return").mean().alias("mean_return"),
+            col("cumulative_return").last(),
+            col("drawdown").min().alias("max_drawdown"),
+        ])
+        .join(
+            turnover
+                .clone()
+                .lazy()
+                .group_by(bucket_keys.clone())
+                .agg([col("turnover").mean().alias("mean_turnover")]),
+            bucket_keys.clone(),
+            bucket_keys,
+            JoinArgs::new(JoinType::Left),
+        )
+        .sort(["factor", "bucket"], Default::default())
+        .collect()?;
+
+    // Rank correlation of bucket number with mean return
+    let demeaned = |name: &str| col(name) - col(name).mean().over([col("factor")]);
+    let monotonicity = buckets
+        .clone()
+        .lazy()
+        .with_columns([
+            col("bucket").cast(DataType::Float64).alias("x"),
+            col("mean_return")
+                .rank(
+                    RankOptions {
+                        method: RankMethod::Average,
+                        descending: false,
+                    },
+                    None,
+                )
+                .over([col("factor")])
+                .cast(DataType::Float64)
+                .alias("y"),
+        ])
+        .with_columns([demeaned("x").alias("x"), demeaned("y").alias("y")])
+        .group_by([col("factor")])
+        .agg([((col("x") * col("y")).sum()
+            / (col("x").pow(2).sum() * col("y").pow(2).sum()).sqrt())
+        .alias("monotonicity")]);
+
+    let summary = long_short
+        .clone()
+        .lazy()
+        .group_by([col("factor")])
+        .agg([
+            col("long_short").mean().alias("long_short_mean"),
+            col("cumulative_return")
+                .last()
+                .alias("long_short_cumulative"),
+            col("drawdown").min().alias("long_short_max_drawdown"),
+        ])
+        .join(
+            monotonicity,
+            [col("factor")],
+            [col("factor")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .sort(["factor"], Default::default())
+        .collect()?;
+
+    Ok(QuantileReport {
+        returns: bucket_returns,
+        long_short,
+        turnover,
+        buckets,
+        summary,
+    })
+}
+
+/// Bucket weights on each rebalance period: columns `factor`, `period`,
+/// `symbol`, `bucket` and `weight`.
+fn positions(
+    exposures: &DataFrame,
+    returns: &DataFrame,
+    calendar: &LazyFrame,
+    config: &QuantileConfig,
+) -> Result<LazyFrame> {
+    let n = config.quantiles as i64;
+    let cross_section = [col("factor"), col("period")];
+    let keys = [col("symbol"), col("date")];
+
+    let mut scores = stack(exposures)?
+        .filter(col("value").is_not_null().and(col("value").is_finite()))
+        .join(
+            calendar.clone(),
+            [col("date")],
+            [col("date")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .filter((col("period") % lit(config.rebalance_every as i64)).eq(lit(0)));
+
+    scores = match &config.weighting {
+        BucketWeighting::Equal => scores.with_column(lit(1.0).alias("raw_weight")),
+        BucketWeighting::CapWeighted { column } => scores
+            .join(
+                returns.clone().lazy().select([
+                    col("symbol"),
+                    col("date"),
+                    col(column.as_str())
+                        .cast(DataType::Float64)
+                        .alias("raw_weight"),
+                ]),
+                keys.clone(),
+                keys,
+                JoinArgs::new(JoinType::Inner),
+            )
+            .filter(col("raw_weight").gt(lit(0.0))),
+    };
+
+    // Equal-count buckets from ordinal ranks; ties split by input order
+    let rank = col("value")
+        .rank(
+            RankOptions {
+                method: RankMethod::Ordinal,
+                descending: false,
+            },
+            None,
+        )
+        .over(cross_section.clone())
+        .cast(DataType::Int64);
+    let count = len().over(cross_section).cast(DataType::Int64);
+
+    Ok(scores
+        .with_columns([rank.alias("rank"), count.alias("count")])
+        .filter(col("count").gt_eq(lit(n)))
+        .with_column(((col("rank") - lit(1)) * lit(n) / col("count") + lit(1)).alias("bucket"))
+        .with_column(
+            (col("raw_weight")
+                / col("raw_weight")
+                    .sum()
+                    .over([col("factor"), col("period"), col("bucket")]))
+            .alias("weight"),
+        )
+        .select([
+            col("factor"),
+            col("period"),
+            col("symbol"),
+            col("bucket"),
+            col("weight"),
+        ])
+        .collect()?
+        .lazy())
+}
+
+/// One-way turnover of each bucket against the previous rebalance, `step`
+/// periods earlier: half the summed absolute weight changes. `positions` has
+/// columns `factor`, `period`, `symbol`, `bucket` and `weight`.
+pub(super) fn turnover(positions: &LazyFrame, step: i64) -> Result<LazyFrame> {
+    let keys = [col("factor"), col("period"), col("bucket"), col("symbol")];
+    let rebalances = [col("factor"), col("period")];
+    let previous = positions
+        .clone()
+        .with_column(col("period") + lit(step))
+        .rename(["weight"], ["previous_weight"], true);
+
+    // Rebalances with a rebalance `step` periods before them
+    let current_periods = positions
+        .clone()
+        .select(rebalances.clone())
+        .unique(None, UniqueKeepStrategy::Any);
+    let paired = previous
+        .clone()
+        .select(rebalances.clone())
+        .unique(None, UniqueKeepStrategy::Any)
+        .join(
+            current_periods,
+            rebalances.clone(),
+            rebalances.clone(),
+            JoinArgs::new(JoinType::Inner),
+        );
+
+    Ok(positions
+        .clone()
+        .join(
+            previous,
+            keys.clone(),
+            keys,
+            JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
+        )
+        .join(
+            paired,
+            rebalances.clone(),
+            rebalances,
+            JoinArgs::new(JoinType::Inner),
+        )
+        .group_by([col("factor"), col("period"), col("bucket")])
+        .agg([
+            ((col("weight").fill_null(lit(0.0)) - col("previous_weight").fill_null(lit(0.0)))
+                .abs()
+                .sum()
+                * lit(0.5))
+            .alias("turnover"),
+        ]))
+}
+
+/// Add `cumulative_return` and `drawdown` of the `return` column, compounded
+/// in row order within `groups`.
+fn performance(frame: LazyFrame, groups: &[Expr]) -> LazyFrame {
+    frame
+        .with_column(
+            (lit(1.0) + col("return"))
+                .cum_prod(false)
+                .over(groups)
+                .alias("wealth"),
+        )
+        .with_columns([
+            (col("wealth") - lit(1.0)).alias("cumulative_return"),
+            (col("wealth") / col("wealth").cum_max(false).over(groups) - lit(1.0))
+                .alias("drawdown"),
+        ])
+        .select([all().exclude(["wealth"])])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Six symbols over six days. `signal` ranks next-day returns perfectly;
+    /// symbol `F` is large and has the best returns.
+    fn panel() -> (DataFrame, DataFrame) {
+        let mut symbols = Vec::new();
+        let mut dates = Vec::new();
+        let mut signal = Vec::new();
+        let mut returns = Vec::new();
+        let mut caps = Vec::new();
+        for (s, symbol) in ["A", "B", "C", "D", "E", "F"].into_iter().enumerate() {
+            for day in 0..6 {
+                symbols.push(symbol);
+                dates.push(format!("2024-01-0{}", day + 1));
+                // Day 3 reverses the sort so the long-short draws down
+                let sign = if day == 3 { -1.0 } else { 1.0 };
+                signal.push(s as f64);
+                returns.push(sign * 0.01 * (s as f64 - 2.5));
+                caps.push(if s == 5 { 100.0 } else { 1.0 });
+            }
+        }
+        let exposures = df![
+            "symbol" => symbols.clone(),
+            "date" => dates.clone(),
+            "signal" => signal,
+        ]
+        .unwrap();
+        let returns = df![
+            "symbol" => symbols,
+            "date" => dates,
+            "forward_return_1" => returns,
+            "market_cap" => caps,
+        ]
+        .unwrap();
+        (exposures, returns)
+    }
+
+    fn column(df: &DataFrame, name: &str) -> Vec<f64> {
+        df.column(name)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_quantile_backtest_daily() {
+        let (exposures, returns) = panel();
+        let config = QuantileConfig::default().with_quantiles(3);
+        let report = quantile_backtest(&exposures, &returns, &config).unwrap();
+
+        // Buckets {A, B}, {C, D}, {E, F} on each of six days
+        assert_eq!(report.returns.height(), 18);
+        let long_short = column(&report.long_short, "long_short");
+        assert_relative_eq!(long_short[0], 0.04, epsilon = 1e-12);
+        assert_relative_eq!(long_short[3], -0.04, epsilon = 1e-12);
+
+        let drawdown = column(&report.long_short, "drawdown");
+        assert_relative_eq!(drawdown[2], 0.0, epsilon = 1e-12);
+        assert_relative_eq!(drawdown[3], -0.04, epsilon = 1e-12);
+
+        let summary = &report.summary;
+        assert_relative_eq!(column(summary, "monotonicity")[0], 1.0, epsilon = 1e-12);
+        assert_relative_eq!(
+            column(summary, "long_short_max_drawdown")[0],
+            -0.04,
+            epsilon = 1e-12
+        );
+
+        // The same stocks every day: no turnover after the first rebalance
+        assert_eq!(report.turnover.height(), 15);
+        assert!(
+            column(&report.turnover, "turnover")
+                .iter()
+                .all(|t| *t == 0.0)
+        );
+    }
+
+    #[test]
+    fn test_quantile_backtest_cap_weighted_overlapping() {
+        let (exposures, returns) = panel();
+        let config = QuantileConfig::default()
+            .with_quantiles(3)
+            .with_weighting(BucketWeighting::CapWeighted {
+                column: "market_cap".to_string(),
+            })
+            .with_rebalance_every(2)
+            .with_holding_period(4);
+        let report = quantile_backtest(&exposures, &returns, &config).unwrap();
+
+        // Top bucket is dominated by F's return of 0.025
+        let top = report
+            .returns
+            .clone()
+            .lazy()
+            .filter(col("bucket").eq(lit(3i64)))
+            .collect()
+            .unwrap();
+        let top = column(&top, "return");
+        assert_eq!(top.len(), 6);
+        let expected = (100.0 * 0.025 + 0.015) / 101.0;
+        assert_relative_eq!(top[0], expected, epsilon = 1e-12);
+
+        // Rebalances on days 0, 2 and 4; turnover from the second on
+        let dates: Vec<&str> = report
+            .turnover
+            .column("date")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(dates[..2], ["2024-01-03", "2024-01-05"]);
+
+        assert!(matches!(
+            quantile_backtest(
+                &exposures,
+                &returns,
+                &QuantileConfig::default().with_quantiles(1)
+            ),
+            Err(FactorError::InvalidSpec(_))
+        ));
+    }
+}
@@ -0,0 +1,498 @@
+//! Factor correlation and redundancy analysis.
+//!
+//! Overlapping signals, e.g. several momentum horizons or spread estimators,
+//! add little beyond each other. [`redundancy_report`] finds them from the
+//! time-averaged cross-sectional correlation between exposures:
+//!
+//! - pairs correlated above a threshold,
+//! - hierarchical clusters of factors (average linkage on `1 - |ρ|`),
+//! - each factor's variance inflation factor (VIF) against all the others.
+
+use super::factor_columns;
+use crate::{
+    FactorError, Result,
+    cross_section::{float_columns, rows_by_date},
+    orthogonalize::symmetric_eigen,
+};
+use ndarray::{Array2, Axis};
+use polars::prelude::*;
+
+/// Correlation measure for [`factor_correlation`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrelationMethod {
+    /// Pearson correlation of exposures
+    Pearson,
+    /// Spearman correlation: Pearson on per-date ranks
+    #[default]
+    Spearman,
+}
+
+/// Configuration for [`redundancy_report`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RedundancyConfig {
+    /// Correlation measure
+    pub method: CorrelationMethod,
+    /// Absolute correlation at or above which a pair is flagged, and the
+    /// level at which the cluster tree is cut
+    pub threshold: f64,
+}
+
+impl Default for RedundancyConfig {
+    fn default() -> Self {
+        Self {
+            method: CorrelationMethod::Spearman,
+            threshold: 0.7,
+        }
+    }
+}
+
+impl RedundancyConfig {
+    /// Set the correlation measure.
+    pub const fn with_method(mut self, method: CorrelationMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Set the redundancy threshold.
+    pub const fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+}
+
+/// Output of [`redundancy_report`].
+#[derive(Debug, Clone)]
+pub struct RedundancyReport {
+    /// Output of [`factor_correlation`]
+    pub correlation: DataFrame,
+    /// Columns `factor_a`, `factor_b`, `correlation`: pairs at or above the
+    /// threshold in absolute value, most correlated first
+    pub redundant_pairs: DataFrame,
+    /// Columns `factor`, `cluster`: clusters from cutting the tree at
+    /// distance `1 - threshold`, numbered from 1 in order of first member
+    pub clusters: DataFrame,
+    /// Columns `step`, `left`, `right`, `distance`, `size`: the merges of the
+    /// cluster tree. Leaves are numbered `0..k` in factor order and the
+    /// cluster formed at step `s` is `k + s`
+    pub linkage: DataFrame,
+    /// Columns `factor`, `vif`: `1 / (1 - R²)` from regressing each factor on
+    /// all others, infinite for exact collinearity
+    pub vif: DataFrame,
+}
+
+/// Time-averaged cross-sectional correlation matrix of factor exposures.
+///
+/// Correlations are computed on each date over the rows where both factors
+/// are present, then averaged over dates with at least three such rows.
+///
+/// # Arguments
+///
+/// * `exposures` - Wide exposures: `symbol`, `date`, one column per factor
+/// * `method` - Pearson or Spearman
+///
+/// # Returns
+///
+/// Square DataFrame with a `factor` column followed by one column per
+/// factor, in the exposures' column order. Pairs never observed together are
+/// null.
+pub fn factor_correlation(exposures: &DataFrame, method: CorrelationMethod) -> Result<DataFrame> {
+    let (factors, matrix) = correlation_matrix(exposures, method)?;
+    correlation_frame(&factors, &matrix)
+}
+
+/// Correlation matrix, redundant pairs, clusters and VIFs of a factor set.
+///
+/// Pairs never observed together count as uncorrelated for clustering and
+/// VIFs.
+///
+/// # Arguments
+///
+/// * `exposures` - Wide exposures: `symbol`, `date`, one column per factor
+/// * `config` - Correlation method and threshold
+pub fn redundancy_report(
+    exposures: &DataFrame,
+    config: &RedundancyConfig,
+) -> Result<RedundancyReport> {
+    if !(0.0..=1.0).contains(&config.threshold) {
+        return Err(FactorError::InvalidSpec(format!(
+            "redundancy threshold must be between 0 and 1, got {}",
+            config.threshold
+        )));
+    }
+    let (factors, matrix) = correlation_matrix(exposures, config.method)?;
+    let k = factors.len();
+    let correlation = matrix.mapv(|rho| rho.unwrap_or(0.0));
+
+    let mut pairs: Vec<(&str, &str, f64)> = Vec::new();
+    for i in 0..k {
+        for j in i + 1..k {
+            if let Some(rho) = matrix[[i, j]]
+                && rho.abs() >= config.threshold
+            {
+                pairs.push((&factors[i], &factors[j], rho));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.2.abs().total_cmp(&a.2.abs()));
+    let redundant_pairs = DataFrame::new(vec![
+        Column::new(
+            "factor_a".into(),
+            pairs.iter().map(|p| p.0).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "factor_b".into(),
+            pairs.iter().map(|p| p.1).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "correlation".into(),
+            pairs.iter().map(|p| p.2).collect::<Vec<_>>(),
+        ),
+    ])?;
+
+    let merges = average_linkage(&correlation.mapv(|rho| 1.0 - rho.abs()));
+    let assignments = cut(&merges, k, 1.0 - config.threshold);
+    let clusters = DataFrame::new(vec![
+        Column::new("factor".into(), factors.clone()),
+        Column::new("cluster".into(), assignments),
+    ])?;
+    let linkage = DataFrame::new(vec![
+        Column::new("step".into(), (0..merges.len() as u64).collect::<Vec<_>>()),
+        Column::new(
+            "left".into(),
+            merges.iter().map(|m| m.left as u64).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "right".into(),
+            merges.iter().map(|m| m.right as u64).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "distance".into(),
+            merges.iter().map(|m| m.distance).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "size".into(),
+            merges.iter().map(|m| m.size as u64).collect::<Vec<_>>(),
+        ),
+    ])?;
+
+    let vif = DataFrame::new(vec![
+        Column::new("factor".into(), factors.clone()),
+        Column::new("vif".into(), variance_inflation(&correlation)),
+    ])?;
+
+    Ok(RedundancyReport {
+        correlation: correlation_frame(&factors, &matrix)?,
+        redundant_pairs,
+        clusters,
+        linkage,
+        vif,
+    })
+}
+
+/// A correlation matrix as a square DataFrame.
+fn correlation_frame(factors: &[String], matrix: &Array2<Option<f64>>) -> Result<DataFrame> {
+    let mut columns = vec![Column::new("factor".into(), factors.to_vec())];
+    for (j, name) in factors.iter().enumerate() {
+        let values: Vec<Option<f64>> = matrix.column(j).to_vec();
+        columns.push(Column::new(name.as_str().into(), values));
+    }
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Factor names and their time-averaged correlation matrix.
+fn correlation_matrix(
+    exposures: &DataFrame,
+    method: CorrelationMethod,
+) -> Result<(Vec<String>, Array2<Option<f64>>)> {
+    let factors = factor_columns(exposures);
+    if factors.is_empty() {
+        return Err(FactorError::MissingColumn(
+            "exposures have no factor columns".to_string(),
+        ));
+    }
+
+    let panel = match method {
+        CorrelationMethod::Pearson => exposures.clone(),
+        CorrelationMethod::Spearman => exposures
+            .clone()
+            .lazy()
+            .with_columns(
+                factors
+                    .iter()
+                    .map(|name| {
+                        col(name.as_str())
+                            .cast(DataType::Float64)
+                            .rank(
+                                RankOptions {
+                                    method: RankMethod::Average,
+                                    descending: false,
+                                },
+                                None,
+                            )
+                            .over([col("date")])
+                            .cast(DataType::Float64)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .collect()?,
+    };
+    let names: Vec<&str> = factors.iter().map(String::as_str).collect();
+    let values = float_columns(&panel, &names)?;
+    let rows_by_date = rows_by_date(&panel, |_| true)?;
+
+    let k = factors.len();
+    let mut sums = Array2::<f64>::zeros((k, k));
+    let mut counts = Array2::<u32>::zeros((k, k));
+    for rows in rows_by_date.values() {
+        for i in 0..k {
+            for j in i..k {
+                let pairs: Vec<(f64, f64)> = rows
+                    .iter()
+                    .filter_map(|&row| Some((values[i].get(row)?, values[j].get(row)?)))
+                    .filter(|(x, y)| x.is_finite() && y.is_finite())
+                    .collect();
+                if let Some(rho) = pearson(&pairs) {
+                    sums[[i, j]] += rho;
+                    counts[[i, j]] += 1;
+                }
+            }
+        }
+    }
+
+    let mut matrix = Array2::from_elem((k, k), None);
+    for i in 0..k {
+        for j in i..k {
+            let mean = (counts[[i, j]] > 0).then(|| sums[[i, j]] / f64::from(counts[[i, j]]));
+            matrix[[i, j]] = if i == j { mean.map(|_| 1.0) } else { mean };
+            matrix[[j, i]] = matrix[[i, j]];
+        }
+    }
+    Ok((factors, matrix))
+}
+
+/// Pearson correlation of paired observations, if defined.
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 3 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let (mut xy, mut xx, mut yy) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        xy += dx * dy;
+        xx += dx * dx;
+        yy += dy * dy;
+    }
+    let rho = xy / (xx * yy).sqrt();
+    rho.is_finite().then_some(rho)
+}
+
+/// A merge in the cluster tree.
+#[derive(Debug, Clone, Copy)]
+struct Merge {
+    left: usize,
+    right: usize,
+    distance: f64,
+    size: usize,
+}
+
+/// Agglomerative clustering with average linkage on a distance matrix.
+fn average_linkage(distance: &Array2<f64>) -> Vec<Merge> {
+    let k = distance.nrows();
+    // Active clusters: id and member leaves
+    let mut clusters: Vec<(usize, Vec<usize>)> = (0..k).map(|i| (i, vec![i])).collect();
+    let mut merges = Vec::with_capacity(k.saturating_sub(1));
+
+    while clusters.len() > 1 {
+        let mut best = (0, 1, f64::INFINITY);
+        for a in 0..clusters.len() {
+            for b in a + 1..clusters.len() {
+                let (left, right) = (&clusters[a].1, &clusters[b].1);
+                let total: f64 = left
+                    .iter()
+                    .flat_map(|&i| right.iter().map(move |&j| distance[[i, j]]))
+                    .sum();
+                let average = total / (left.len() * right.len()) as f64;
+                if average < best.2 {
+                    best = (a, b, average);
+                }
+            }
+        }
+
+        let (a, b, average) = best;
+        let (right_id, right) = clusters.remove(b);
+        let (left_id, mut left) = clusters.remove(a);
+        left.extend(right);
+        merges.push(Merge {
+            left: left_id,
+            right: right_id,
+            distance: average,
+            size: left.len(),
+        });
+        clusters.push((k + merges.len() - 1, left));
+    }
+    merges
+}
+
+/// Cluster number of each leaf after applying merges closer than `height`.
+fn cut(merges: &[Merge], k: usize, height: f64) -> Vec<u32> {
+    // Union-find over leaves and merged clusters
+    let mut parent: Vec<usize> = (0..k + merges.len()).collect();
+    fn root(parent: &mut [usize], mut node: usize) -> usize {
+        while parent[node] != node {
+            parent[node] = parent[parent[node]];
+            node = parent[node];
+        }
+        node
+    }
+    for (step, merge) in merges.iter().enumerate() {
+        if merge.distance <= height {
+            let id = k + step;
+            parent[merge.left] = id;
+            parent[merge.right] = id;
+        }
+    }
+
+    let mut labels: Vec<(usize, u32)> = Vec::new();
+    (0..k)
+        .map(|leaf| {
+            let r = root(&mut parent, leaf);
+            match labels.iter().find(|(node, _)| *node == r) {
+                Some((_, label)) => *label,
+                None => {
+                    let label = labels.len() as u32 + 1;
+                    labels.push((r, label));
+                    label
+                }
+            }
+        })
+        .collect()
+}
+
+/// `1 / (1 - R²)` of each factor regressed on the others, with `R²` from
+/// their correlations; infinite when a factor is a combination of others.
+fn variance_inflation(correlation: &Array2<f64>) -> Vec<f64> {
+    let k = correlation.nrows();
+    (0..k)
+        .map(|i| {
+            let others: Vec<usize> = (0..k).filter(|&j| j != i).collect();
+            if others.is_empty() {
+                return 1.0;
+            }
+            let among = correlation
+                .select(Axis(0), &others)
+                .select(Axis(1), &others);
+            let with_target = correlation.column(i).select(Axis(0), &others);
+
+            // R² = rᵀ R⁺ r, with a pseudo-inverse for collinear regressors
+            let (eigenvalues, eigenvectors) = symmetric_eigen(&among);
+            let largest = eigenvalues.iter().copied().fold(0.0, f64::max);
+            let r_squared: f64 = eigenvalues
+                .iter()
+                .zip(eigenvectors.columns())
+                .filter(|(l, _)| **l > largest * 1e-10)
+                .map(|(l, v)| v.dot(&with_target).powi(2) / l)
+                .sum();
+            if r_squared >= 1.0 - 1e-10 {
+                f64::INFINITY
+            } else {
+                1.0 / (1.0 - r_squared)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Eight symbols over three days. `mom_6m` and `mom_12m` are near
+    /// copies; `size` is unrelated.
+    fn exposures() -> DataFrame {
+        let mut symbols = Vec::new();
+        let mut dates = Vec::new();
+        let (mut short, mut long, mut size) = (Vec::new(), Vec::new(), Vec::new());
+        for day in 0..3 {
+            for s in 0..8 {
+                let x = ((s * 3 + day) % 8) as f64;
+                symbols.push(format!("S{s}"));
+                dates.push(format!("2024-01-0{}", day + 1));
+                short.push(x);
+                long.push(2.0 * x + if s == 0 { 0.5 } else { 0.0 });
+                size.push(if s % 2 == 0 { 1.0 } else { -1.0 } * (s / 2) as f64);
+            }
+        }
+        df![
+            "symbol" => symbols,
+            "date" => dates,
+            "mom_6m" => short,
+            "mom_12m" => long,
+            "size" => size,
+        ]
+        .unwrap()
+    }
+
+    #[test]
+    fn test_factor_correlation() {
+        let pearson = factor_correlation(&exposures(), CorrelationMethod::Pearson).unwrap();
+        assert_eq!(pearson.shape(), (3, 4));
+        let column = pearson.column("mom_6m").unwrap().f64().unwrap();
+        assert_relative_eq!(column.get(0).unwrap(), 1.0);
+        assert!(column.get(1).unwrap() > 0.99);
+
+        // Ranks ignore the small perturbation wherever it keeps the order
+        let spearman = factor_correlation(&exposures(), CorrelationMethod::Spearman).unwrap();
+        let column = spearman.column("mom_12m").unwrap().f64().unwrap();
+        assert_relative_eq!(column.get(0).unwrap(), 1.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_redundancy_report() {
+        let report = redundancy_report(&exposures(), &RedundancyConfig::default()).unwrap();
+
+        assert_eq!(report.redundant_pairs.height(), 1);
+        let pair = &report.redundant_pairs;
+        assert_eq!(
+            pair.column("factor_a").unwrap().str().unwrap().get(0),
+            Some("mom_6m")
+        );
+        assert_eq!(
+            pair.column("factor_b").unwrap().str().unwrap().get(0),
+            Some("mom_12m")
+        );
+
+        let clusters: Vec<u32> = report
+            .clusters
+            .column("cluster")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(clusters, [1, 1, 2]);
+
+        // The first merge joins the momentum pair; the last spans everything
+        let left = report.linkage.column("left").unwrap().u64().unwrap();
+        let right = report.linkage.column("right").unwrap().u64().unwrap();
+        assert_eq!((left.get(0), right.get(0)), (Some(0), Some(1)));
+        let size = report.linkage.column("size").unwrap().u64().unwrap();
+        assert_eq!(size.get(1), Some(3));
+
+        let vif = report.vif.column("vif").unwrap().f64().unwrap();
+        // The momentum pair ranks identically, so only `size` has a finite VIF
+        assert!(vif.get(0).unwrap().is_infinite());
+        assert!(vif.get(2).unwrap() < 2.0);
+
+        assert!(
+            redundancy_report(
+                &exposures(),
+                &RedundancyConfig::default().with_threshold(2.0)
+            )
+            .is_err()
+        );
+    }
+}
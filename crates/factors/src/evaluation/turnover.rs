@@ -0,0 +1,414 @@
+//! Turnover and rank autocorrelation diagnostics.
+//!
+//! A factor whose ranking reshuffles every day forces heavy trading, and its
+//! paper returns rarely survive costs. Rank autocorrelation measures how
+//! persistent the ranking is over a lag; quantile turnover measures how much
+//! of the top and bottom portfolios would be traded each period.
+
+use super::{quantile, stack};
+use crate::{FactorError, FactorRegistry, Result};
+use polars::prelude::*;
+
+/// Per-date rank autocorrelation of every factor at each lag.
+///
+/// For lag `L`, the Spearman correlation between each stock's exposure on a
+/// date and its exposure `L` dates earlier, over the stocks present on both.
+/// Lags count distinct dates in `exposures`.
+///
+/// # Arguments
+///
+/// * `exposures` - Wide exposures: `symbol`, `date`, one column per factor
+/// * `lags` - Lags in periods, e.g. `[1, 5, 21]`
+///
+/// # Returns
+///
+/// DataFrame with columns: `factor`, `date`, `lag`, `rank_autocorrelation`,
+/// `count`, sorted by factor, lag and date.
+pub fn rank_autocorrelation(exposures: &DataFrame, lags: &[usize]) -> Result<DataFrame> {
+    if lags.is_empty() || lags.contains(&0) {
+        return Err(FactorError::InvalidSpec(
+            "rank autocorrelation needs lags of at least 1".to_string(),
+        ));
+    }
+    let scores = periods(exposures)?;
+    let group = [col("factor"), col("date")];
+    let rank = |name: &str| {
+        col(name)
+            .rank(
+                RankOptions {
+                    method: RankMethod::Average,
+                    descending: false,
+                },
+                None,
+            )
+            .over(group.clone())
+            .cast(DataType::Float64)
+    };
+    let demeaned = |name: &str| col(name) - col(name).mean().over(group.clone());
+    let keys = [col("factor"), col("symbol"), col("period")];
+
+    let frames: Vec<LazyFrame> = lags
+        .iter()
+        .map(|&lag| {
+            let previous = scores.clone().select([
+                col("factor"),
+                col("symbol"),
+                (col("period") + lit(lag as i64)).alias("period"),
+                col("value").alias("previous"),
+            ]);
+            let correlation = (col("current") * col("previous")).sum()
+                / (col("current").pow(2).sum() * col("previous").pow(2).sum()).sqrt();
+            scores
+                .clone()
+                .join(
+                    previous,
+                    keys.clone(),
+                    keys.clone(),
+                    JoinArgs::new(JoinType::Inner),
+                )
+                .with_columns([rank("value").alias("current"), rank("previous")])
+                .with_columns([
+                    demeaned("current"),
+                    demeaned("previous"),
+                    lit(lag as u64).cast(DataType::UInt64).alias("lag"),
+                ])
+                .group_by([col("factor"), col("date"), col("lag")])
+                .agg([
+                    when(correlation.clone().is_finite())
+                        .then(correlation)
+                        .otherwise(lit(NULL))
+                        .alias("rank_autocorrelation"),
+                    len().cast(DataType::UInt64).alias("count"),
+                ])
+        })
+        .collect();
+
+    Ok(concat(frames, UnionArgs::default())?
+        .sort(["factor", "lag", "date"], Default::default())
+        .collect()?)
+}
+
+/// Per-date turnover of each factor's top and bottom quantile portfolios.
+///
+/// On every date, the stocks in the top and bottom of `quantiles`
+/// equal-count buckets are weighted by absolute exposure, so the most extreme
+/// names count the most. Turnover is half the summed absolute weight change
+/// from the previous date: 0 for an unchanged portfolio, 1 for a complete
+/// replacement.
+///
+/// # Arguments
+///
+/// * `exposures` - Wide exposures: `symbol`, `date`, one column per factor
+/// * `quantiles` - Number of buckets, e.g. 5 for quintiles
+///
+/// # Returns
+///
+/// DataFrame with columns: `factor`, `date`, `side` (`top` or `bottom`),
+/// `turnover`, sorted by factor, side and date. The first date has no
+/// turnover.
+pub fn quantile_turnover(exposures: &DataFrame, quantiles: usize) -> Result<DataFrame> {
+    if quantiles < 2 {
+        return Err(FactorError::InvalidSpec(
+            "quantile turnover needs at least 2 buckets".to_string(),
+        ));
+    }
+    let n = quantiles as i64;
+    let cross_section = [col("factor"), col("period")];
+    let scores = periods(exposures)?;
+    let dates = scores
+        .clone()
+        .select([col("period"), col("date")])
+        .unique(None, UniqueKeepStrategy::Any);
+
+    let rank = col("value")
+        .rank(
+            RankOptions {
+                method: RankMethod::Ordinal,
+                descending: false,
+            },
+            None,
+        )
+        .over(cross_section.clone())
+        .cast(DataType::Int64);
+    let count = len().over(cross_section).cast(DataType::Int64);
+    let positions = scores
+        .with_columns([rank.alias("rank"), count.alias("count")])
+        .filter(col("count").gt_eq(lit(n)))
+        .with_column(((col("rank") - lit(1)) * lit(n) / col("count") + lit(1)).alias("bucket"))
+        .filter(col("bucket").eq(lit(1)).or(col("bucket").eq(lit(n))))
+        .with_column(col("value").abs().alias("weight"))
+        .with_column(
+            (col("weight")
+                / col("weight")
+                    .sum()
+                    .over([col("factor"), col("period"), col("bucket")]))
+            .alias("weight"),
+        )
+        .filter(col("weight").is_finite())
+        .select([
+            col("factor"),
+            col("period"),
+            col("symbol"),
+            col("bucket"),
+            col("weight"),
+        ])
+        .collect()?
+        .lazy();
+
+    Ok(quantile::turnover(&positions, 1)?
+        .join(
+            dates,
+            [col("period")],
+            [col("period")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .select([
+            col("factor"),
+            col("date"),
+            when(col("bucket").eq(lit(n)))
+                .then(lit("top"))
+                .otherwise(lit("bottom"))
+                .alias("side"),
+            col("turnover"),
+        ])
+        .sort(["factor", "side", "date"], Default::default())
+        .collect()?)
+}
+
+/// Turnover diagnostics averaged per factor and per category.
+#[derive(Debug, Clone)]
+pub struct TurnoverSummary {
+    /// Columns `factor`, `category`, one `rank_autocorrelation_{lag}` per
+    /// lag, `top_turnover`, `bottom_turnover`, sorted by factor
+    pub by_factor: DataFrame,
+    /// Columns `category`, `factors`, then the `by_factor` averages over the
+    /// category's factors, sorted by category
+    pub by_category: DataFrame,
+}
+
+/// Average [`rank_autocorrelation`] and [`quantile_turnover`] output per
+/// factor and per [`FactorCategory`](crate::FactorCategory).
+///
+/// Categories come from `registry`; factors it does not hold get a null
+/// category.
+pub fn turnover_summary(
+    autocorrelation: &DataFrame,
+    turnover: &DataFrame,
+    registry: &FactorRegistry,
+) -> Result<TurnoverSummary> {
+    let mut lags: Vec<u64> = autocorrelation
+        .column("lag")?
+        .u64()?
+        .into_no_null_iter()
+        .collect();
+    lags.sort_unstable();
+    lags.dedup();
+
+    let factor = [col("factor")];
+    let mut by_factor = turnover.clone().lazy().group_by(factor.clone()).agg([
+        col("turnover")
+            .filter(col("side").eq(lit("top")))
+            .mean()
+            .alias("top_turnover"),
+        col("turnover")
+            .filter(col("side").eq(lit("bottom")))
+            .mean()
+            .alias("bottom_turnover"),
+    ]);
+    let mut columns = vec![col("factor"), col("category")];
+    for &lag in lags.iter().rev() {
+        let name = format!("rank_autocorrelation_{lag}");
+        let means = autocorrelation
+            .clone()
+            .lazy()
+            .filter(col("lag").eq(lit(lag).cast(DataType::UInt64)))
+            .group_by(factor.clone())
+            .agg([col("rank_autocorrelation").mean().alias(name.as_str())]);
+        by_factor = means.join(
+            by_factor,
+            factor.clone(),
+            factor.clone(),
+            JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
+        );
+    }
+    columns.extend(
+        lags.iter()
+            .map(|lag| col(format!("rank_autocorrelation_{lag}").as_str())),
+    );
+    columns.extend([col("top_turnover"), col("bottom_turnover")]);
+
+    let by_factor = by_factor.collect()?;
+    let categories: Vec<Option<String>> = by_factor
+        .column("factor")?
+        .str()?
+        .into_iter()
+        .map(|name| {
+            name.and_then(|name| registry.get(name))
+                .map(|factor| factor.category().to_string())
+        })
+        .collect();
+    let by_factor = by_factor
+        .lazy()
+        .with_column(lit(Series::new("category".into(), categories)))
+        .select(columns)
+        .sort(["factor"], Default::default())
+        .collect()?;
+
+    let by_category = by_factor
+        .clone()
+        .lazy()
+        .group_by([col("category")])
+        .agg([
+            len().cast(DataType::UInt64).alias("factors"),
+            all().exclude(["factor", "category"]).mean(),
+        ])
+        .sort(["category"], Default::default())
+        .collect()?;
+
+    Ok(TurnoverSummary {
+        by_factor,
+        by_category,
+    })
+}
+
+/// Stacked exposures with a `period` index counting distinct dates.
+fn periods(exposures: &DataFrame) -> Result<LazyFrame> {
+    let calendar = exposures
+        .clone()
+        .lazy()
+        .select([col("date")])
+        .unique(None, UniqueKeepStrategy::Any)
+        .sort(["date"], Default::default())
+        .with_row_index("period", None)
+        .with_column(col("period").cast(DataType::Int64));
+
+    Ok(stack(exposures)?
+        .filter(col("value").is_not_null().and(col("value").is_finite()))
+        .join(
+            calendar,
+            [col("date")],
+            [col("date")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .collect()?
+        .lazy())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompositeFactor, FactorCategory, size::MarketCap};
+    use approx::assert_relative_eq;
+    use std::sync::Arc;
+
+    /// Six symbols over five days: `steady` keeps its ranking, `flip`
+    /// reverses it every day.
+    fn exposures() -> DataFrame {
+        let mut symbols = Vec::new();
+        let mut dates = Vec::new();
+        let mut steady = Vec::new();
+        let mut flip = Vec::new();
+        for s in 0..6 {
+            for day in 0..5 {
+                let x = s as f64 - 2.5;
+                symbols.push(format!("S{s}"));
+                dates.push(format!("2024-01-0{}", day + 1));
+                steady.push(x);
+                flip.push(if day % 2 == 0 { x } else { -x });
+            }
+        }
+        df![
+            "symbol" => symbols,
+            "date" => dates,
+            "steady" => steady,
+            "flip" => flip,
+        ]
+        .unwrap()
+    }
+
+    fn values(df: &DataFrame, factor: &str, column: &str) -> Vec<f64> {
+        df.clone()
+            .lazy()
+            .filter(col("factor").eq(lit(factor)))
+            .collect()
+            .unwrap()
+            .column(column)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_autocorrelation() {
+        let autocorrelation = rank_autocorrelation(&exposures(), &[1, 2]).unwrap();
+        // Four one-day and three two-day pairs per factor
+        assert_eq!(autocorrelation.height(), 14);
+
+        let flip = values(&autocorrelation, "flip", "rank_autocorrelation");
+        for (i, rho) in flip.into_iter().enumerate() {
+            let expected = if i < 4 { -1.0 } else { 1.0 };
+            assert_relative_eq!(rho, expected, epsilon = 1e-12);
+        }
+        for rho in values(&autocorrelation, "steady", "rank_autocorrelation") {
+            assert_relative_eq!(rho, 1.0, epsilon = 1e-12);
+        }
+        assert!(rank_autocorrelation(&exposures(), &[0]).is_err());
+    }
+
+    #[test]
+    fn test_quantile_turnover_and_summary() {
+        let exposures = exposures();
+        let turnover = quantile_turnover(&exposures, 3).unwrap();
+        assert_eq!(turnover.height(), 16);
+        // The top and bottom thirds swap every day
+        for t in values(&turnover, "flip", "turnover") {
+            assert_relative_eq!(t, 1.0, epsilon = 1e-12);
+        }
+        for t in values(&turnover, "steady", "turnover") {
+            assert_relative_eq!(t, 0.0, epsilon = 1e-12);
+        }
+
+        // Register `steady` under the momentum category
+        let mut registry = FactorRegistry::new();
+        registry.register(Arc::new(CompositeFactor::new(
+            "steady",
+            FactorCategory::Momentum,
+            vec![Arc::new(MarketCap::default())],
+        )));
+
+        let autocorrelation = rank_autocorrelation(&exposures, &[1]).unwrap();
+        let summary = turnover_summary(&autocorrelation, &turnover, &registry).unwrap();
+        let names: Vec<&str> = summary
+            .by_factor
+            .get_column_names()
+            .into_iter()
+            .map(|name| name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "factor",
+                "category",
+                "rank_autocorrelation_1",
+                "top_turnover",
+                "bottom_turnover"
+            ]
+        );
+        let category = summary.by_factor.column("category").unwrap().str().unwrap();
+        assert_eq!(category.get(0), None);
+        assert_eq!(category.get(1), Some("Momentum"));
+
+        assert_eq!(summary.by_category.height(), 2);
+        let momentum = summary
+            .by_category
+            .clone()
+            .lazy()
+            .filter(col("category").eq(lit("Momentum")))
+            .collect()
+            .unwrap();
+        let top = momentum.column("top_turnover").unwrap().f64().unwrap();
+        assert_relative_eq!(top.get(0).unwrap(), 0.0);
+    }
+}
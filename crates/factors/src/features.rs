@@ -0,0 +1,251 @@
+//! Derived inputs shared across factors.
+//!
+//! Many daily factors start from the same intermediate series: close-to-close
+//! returns, or a rolling market model of those returns against
+//! `market_return`. A
+//! factor lists the [`Feature`]s it consumes in [`Factor::features`] and reads
+//! them as ordinary columns after calling [`with_features`].
+//!
+//! [`FactorRegistry`](crate::FactorRegistry) computes the union of its factors'
+//! features once per run, so each factor finds its inputs already present
+//! instead of re-deriving them.
+//!
+//! Feature columns have reserved names starting with `__`, so a column in the
+//! input data is never mistaken for a feature: only a frame that went through
+//! [`with_features`] carries them.
+//!
+//! [`Factor::features`]: crate::Factor::features
+
+use crate::Result;
+use polars::prelude::*;
+
+/// A derived input computed from the raw market data.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(tag = "feature", rename_all = "snake_case")]
+pub enum Feature {
+    /// Simple close-to-close return per symbol, column [`DAILY_RETURN`]
+    DailyReturn,
+    /// Log close-to-close return per symbol, column [`LOG_RETURN`]
+    LogReturn,
+    /// Rolling market model of `daily_return` against `market_return` per
+    /// symbol.
+    ///
+    /// Produces the columns named by [`market_model_columns`]. Beta is the
+    /// ratio of the trailing `window`-row standard deviations of the stock and
+    /// market returns, and the residual is `daily_return - beta *
+    /// market_return`.
+    MarketModel {
+        /// Rolling window length in rows
+        window: usize,
+        /// Minimum observations for a fit
+        min_periods: usize,
+    },
+}
+
+impl Feature {
+    /// Columns this feature adds.
+    pub fn columns(&self) -> Vec<String> {
+        match *self {
+            Self::DailyReturn => vec![DAILY_RETURN.to_string()],
+            Self::LogReturn => vec![LOG_RETURN.to_string()],
+            Self::MarketModel {
+                window,
+                min_periods,
+            } => {
+                let columns = market_model_columns(window, min_periods);
+                vec![columns.beta, columns.residual]
+            }
+        }
+    }
+
+    /// Raw input columns needed to compute this feature.
+    pub const fn required_columns(&self) -> &'static [&'static str] {
+        match self {
+            Self::DailyReturn | Self::LogReturn => &["symbol", "date", "close"],
+            Self::MarketModel { .. } => &["symbol", "date", "close", "market_return"],
+        }
+    }
+
+    /// Add this feature's columns to `data`, which is sorted by `symbol` and
+    /// `date` and already carries any feature this one builds on.
+    fn add(self, data: LazyFrame) -> LazyFrame {
+        let lag = col("close").shift(lit(1)).over([col("symbol")]);
+        match self {
+            Self::DailyReturn => {
+                data.with_column(((col("close") - lag.clone()) / lag).alias(DAILY_RETURN))
+            }
+            Self::LogReturn => data.with_column(
+                (col("close") / lag)
+                    .log(std::f64::consts::E)
+                    .alias(LOG_RETURN),
+            ),
+            Self::MarketModel {
+                window,
+                min_periods,
+            } => {
+                let columns = market_model_columns(window, min_periods);
+                let rolling_std = |column: &str| {
+                    col(column)
+                        .rolling_std(RollingOptionsFixedWindow {
+                            window_size: window,
+                            min_periods,
+                            ..Default::default()
+                        })
+                        .over([col("symbol")])
+                };
+
+                data.with_column(
+                    (rolling_std(DAILY_RETURN) / rolling_std("market_return")).alias(&columns.beta),
+                )
+                .with_column(
+                    (col(DAILY_RETURN) - col(&columns.beta) * col("market_return"))
+                        .alias(&columns.residual),
+                )
+            }
+        }
+    }
+}
+
+/// Column holding [`Feature::DailyReturn`].
+pub const DAILY_RETURN: &str = "__daily_return";
+
+/// Column holding [`Feature::LogReturn`].
+pub const LOG_RETURN: &str = "__log_return";
+
+/// Output columns of a [`Feature::MarketModel`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketModelColumns {
+    /// `std(daily_return) / std(market_return)` over the window
+    pub beta: String,
+    /// `daily_return - beta * market_return`
+    pub residual: String,
+}
+
+/// Column names of the market model with the given window.
+pub fn market_model_columns(window: usize, min_periods: usize) -> MarketModelColumns {
+    let name = |stat: &str| format!("__market_{stat}_{window}_{min_periods}");
+    MarketModelColumns {
+        beta: name("beta"),
+        residual: name("residual"),
+    }
+}
+
+/// Sort `data` by `symbol` and `date` and add every feature in `features`
+/// (and the features they build on) whose columns are not already present.
+///
+/// The sort always runs, so rolling expressions see rows in date order even
+/// when the features came precomputed from the registry.
+pub fn with_features(mut data: LazyFrame, features: &[Feature]) -> Result<LazyFrame> {
+    let schema = data.collect_schema()?;
+    let mut result = data.sort(
+        ["symbol", "date"],
+        SortMultipleOptions::default().with_order_descending_multi([false, false]),
+    );
+    for feature in expand(features) {
+        if !feature.columns().iter().all(|c| schema.contains(c)) {
+            result = feature.add(result);
+        }
+    }
+    Ok(result)
+}
+
+/// `features` plus their dependencies, deduplicated and in dependency order.
+fn expand(features: &[Feature]) -> Vec<Feature> {
+    let mut expanded = features.to_vec();
+    if features
+        .iter()
+        .any(|f| matches!(f, Feature::MarketModel { .. }))
+    {
+        expanded.push(Feature::DailyReturn);
+    }
+    // `DailyReturn` orders first, ahead of the market models built on it
+    expanded.sort();
+    expanded.dedup();
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn single_symbol() -> LazyFrame {
+        let market: Vec<f64> = (0..40).map(|i| (i as f64 * 0.7).sin() * 0.01).collect();
+        let mut close = vec![100.0];
+        for m in &market[1..] {
+            let last = *close.last().unwrap();
+            close.push(last * (1.0 + 0.001 + 1.5 * m));
+        }
+        df![
+            "symbol" => vec!["A"; 40],
+            "date" => (0..40).map(|i| 1_000 - i).collect::<Vec<i32>>(),
+            "close" => close.into_iter().rev().collect::<Vec<_>>(),
+            "market_return" => market.into_iter().rev().collect::<Vec<_>>(),
+        ]
+        .unwrap()
+        .lazy()
+    }
+
+    #[test]
+    fn test_market_model_recovers_beta() {
+        let feature = Feature::MarketModel {
+            window: 20,
+            min_periods: 20,
+        };
+        let columns = market_model_columns(20, 20);
+        let df = with_features(single_symbol(), &[feature])
+            .unwrap()
+            .collect()
+            .unwrap();
+
+        assert!(df.column(DAILY_RETURN).is_ok());
+        let beta = df.column(&columns.beta).unwrap().f64().unwrap();
+        assert_eq!(beta.null_count(), 20);
+        assert_relative_eq!(beta.get(39).unwrap(), 1.5, epsilon = 1e-9);
+        let residual = df.column(&columns.residual).unwrap().f64().unwrap();
+        assert_relative_eq!(residual.get(39).unwrap(), 0.001, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_input_columns_are_not_features() {
+        // An input column named like the return is ordinary data
+        let data = single_symbol().with_column(lit(0.5).alias("daily_return"));
+        let df = with_features(data, &[Feature::DailyReturn])
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(df.column("date").unwrap().i32().unwrap().get(0), Some(961));
+        let returns = df.column(DAILY_RETURN).unwrap().f64().unwrap();
+        assert!(returns.get(0).is_none());
+        assert!(returns.into_no_null_iter().all(|r| r != 0.5));
+
+        // Precomputed features are reused, but the frame is still sorted
+        let shuffled = df.lazy().sort(
+            ["date"],
+            SortMultipleOptions::default().with_order_descending(true),
+        );
+        let df = with_features(shuffled, &[Feature::DailyReturn])
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(df.column("date").unwrap().i32().unwrap().get(0), Some(961));
+        assert!(
+            df.column(DAILY_RETURN)
+                .unwrap()
+                .f64()
+                .unwrap()
+                .get(0)
+                .is_none()
+        );
+
+        let df = with_features(single_symbol(), &[Feature::LogReturn])
+            .unwrap()
+            .collect()
+            .unwrap();
+        let log = df.column(LOG_RETURN).unwrap().f64().unwrap();
+        assert!(log.get(0).is_none());
+        assert!(log.get(1).is_some());
+    }
+}
@@ -9,7 +9,7 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date, on_or_before},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -73,10 +73,7 @@ impl Factor for SalesGrowth {
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the specified date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         // Sort by symbol and date
         let sorted = filtered
@@ -99,7 +96,7 @@ impl Factor for SalesGrowth {
                     .over([col("symbol")])
                     .alias(&lag_alias),
             )
-            .filter(col("date").eq(lit(date.to_string())))
+            .filter(on_date(date))
             .with_column(((col("revenue") / col(&lag_alias)) - lit(1.0)).alias("sales_growth"))
             .select([col("symbol"), col("date"), col("sales_growth")])
             .collect()?;
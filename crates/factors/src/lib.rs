@@ -4,24 +4,60 @@
 #![warn(missing_docs)]
 #![forbid(unsafe_code)]
 
+pub mod adjustment;
+pub mod alignment;
+pub mod calendar;
+pub mod composite;
+pub mod coverage;
+mod cross_section;
 pub mod error;
+pub mod evaluation;
+pub mod features;
 pub mod growth;
 pub mod liquidity;
+pub mod mapping;
 pub mod momentum;
+pub mod orthogonalize;
+pub mod pipeline;
 pub mod quality;
 pub mod registry;
+pub mod regression;
+pub mod risk;
+pub mod schema;
 pub mod sentiment;
 pub mod size;
+pub mod spec;
 pub mod standardize;
 pub mod traits;
+pub mod validation;
 pub mod value;
 pub mod volatility;
 
 // Re-export core types
+pub use adjustment::{CorporateActions, PriceAdjustment};
+pub use alignment::{AsOfConfig, align_as_of};
+pub use calendar::{DayCount, TradingCalendar};
+pub use composite::{CompositeFactor, Weighting};
+pub use coverage::{Exposures, FactorCoverage, JoinMode};
 pub use error::{FactorError, Result};
-pub use registry::{FactorCategory, FactorInfo, FactorRegistry};
-pub use standardize::{cross_sectional_standardize, robust_standardize, winsorize};
+pub use features::Feature;
+pub use mapping::ColumnMapping;
+pub use orthogonalize::{Orthogonalization, orthogonalize, symmetric_orthogonalize};
+pub use pipeline::{Pipeline, PipelineFactor, Transform};
+pub use registry::{
+    Execution, FactorCategory, FactorInfo, FactorRegistry, FactorSet, RequiredColumn,
+};
+pub use regression::{CrossSectionConfig, FactorReturns, cross_sectional_regression, fama_macbeth};
+pub use risk::{RiskConfig, RiskForecast, factor_covariance, specific_risk};
+pub use schema::{CanonicalField, Derivation, FieldGroup, derive_fields};
+pub use spec::{FactorSpec, RegistrySpec};
+pub use standardize::{
+    FillStrategy, Standardization, cap_weighted_standardize, cross_sectional_standardize,
+    fill_missing, group_standardize, mad_clip, neutralize, rank_transform, robust_standardize,
+    sigma_clip, winsorize,
+};
 pub use traits::{ConfigurableFactor, DataFrequency, Factor, FactorConfig};
+pub use validation::FactorValidation;
 
 /// Crate version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -5,8 +5,12 @@
 
 use crate::{
     Result,
+    features::{self, DAILY_RETURN, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -122,31 +126,52 @@ impl Factor for AmihudIlliquidity {
         DataFrequency::Daily
     }
 
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::DailyReturn]
+    }
+
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let result = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())))
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            // Calculate lagged close price
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            // Calculate daily return
-            .with_column(
-                ((col("close") - col("close_lag")) / col("close_lag")).alias("daily_return"),
-            )
+        let result = self
+            .rolling(data, date)?
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)?
+            .filter(between_dates(start, end))
+            .collect()?;
+
+        Ok(result)
+    }
+}
+
+impl AmihudIlliquidity {
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> Result<LazyFrame> {
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Daily returns, shared across factors when run through the registry
+        let with_returns = features::with_features(filtered, &self.features())?;
+
+        Ok(with_returns
             // Calculate dollar volume (close * volume)
             .with_column((col("close") * col("volume")).alias("dollar_volume"))
             // Calculate daily Amihud illiquidity: |return| / dollar_volume
             // Add small epsilon to avoid division by zero
             .with_column(
-                (col("daily_return").abs() / (col("dollar_volume") + lit(1e-10)))
+                (col(DAILY_RETURN).abs() / (col("dollar_volume") + lit(1e-10)))
                     .alias("daily_illiquidity"),
             )
             // Rolling mean over lookback period
@@ -160,13 +185,8 @@ impl Factor for AmihudIlliquidity {
                     .over([col("symbol")])
                     .alias("amihud_illiquidity"),
             )
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
             // Select output columns
-            .select([col("symbol"), col("date"), col("amihud_illiquidity")])
-            .collect()?;
-
-        Ok(result)
+            .select([col("symbol"), col("date"), col("amihud_illiquidity")]))
     }
 }
 
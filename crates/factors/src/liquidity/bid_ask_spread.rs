@@ -6,7 +6,10 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -73,6 +76,26 @@ impl BidAskSpread {
             config: BidAskSpreadConfig { lookback },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> LazyFrame {
+        data.clone()
+            .filter(on_or_before(date))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            // Calculate mid-price
+            .with_column(((col("ask") + col("bid")) / lit(2.0)).alias("mid_price"))
+            // Calculate relative spread: (ask - bid) / mid
+            // Add small epsilon to avoid division by zero
+            .with_column(
+                ((col("ask") - col("bid")) / (col("mid_price") + lit(1e-10)))
+                    .alias("bid_ask_spread"),
+            )
+            // Select output columns
+            .select([col("symbol"), col("date"), col("bid_ask_spread")])
+    }
 }
 
 impl Default for BidAskSpread {
@@ -107,25 +130,26 @@ impl Factor for BidAskSpread {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let result = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())))
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            // Calculate mid-price
-            .with_column(((col("ask") + col("bid")) / lit(2.0)).alias("mid_price"))
-            // Calculate relative spread: (ask - bid) / mid
-            // Add small epsilon to avoid division by zero
-            .with_column(
-                ((col("ask") - col("bid")) / (col("mid_price") + lit(1e-10)))
-                    .alias("bid_ask_spread"),
-            )
+        let result = self
+            .rolling(data, date)
             // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            // Select output columns
-            .select([col("symbol"), col("date"), col("bid_ask_spread")])
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
@@ -6,7 +6,10 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -87,46 +90,14 @@ impl CorwinSchultz {
             },
         }
     }
-}
-
-impl Default for CorwinSchultz {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl Factor for CorwinSchultz {
-    fn name(&self) -> &str {
-        "corwin_schultz_spread"
-    }
-
-    fn description(&self) -> &str {
-        "High-low spread estimator using 2-day high/low ranges over 20 days"
-    }
-
-    fn category(&self) -> FactorCategory {
-        FactorCategory::Liquidity
-    }
-
-    fn required_columns(&self) -> &[&str] {
-        &["symbol", "date", "high", "low"]
-    }
 
-    fn lookback(&self) -> usize {
-        self.config.lookback
-    }
-
-    fn frequency(&self) -> DataFrequency {
-        DataFrequency::Daily
-    }
-
-    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> LazyFrame {
         let sqrt2 = std::f64::consts::SQRT_2;
         let denominator = 3.0 - 2.0 * sqrt2;
 
-        let result = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())))
+        data.clone()
+            .filter(on_or_before(date))
             .sort(
                 ["symbol", "date"],
                 SortMultipleOptions::default().with_order_descending_multi([false, false]),
@@ -212,10 +183,63 @@ impl Factor for CorwinSchultz {
                     .over([col("symbol")])
                     .alias("corwin_schultz_spread"),
             )
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
             // Select output columns
             .select([col("symbol"), col("date"), col("corwin_schultz_spread")])
+    }
+}
+
+impl Default for CorwinSchultz {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Factor for CorwinSchultz {
+    fn name(&self) -> &str {
+        "corwin_schultz_spread"
+    }
+
+    fn description(&self) -> &str {
+        "High-low spread estimator using 2-day high/low ranges over 20 days"
+    }
+
+    fn category(&self) -> FactorCategory {
+        FactorCategory::Liquidity
+    }
+
+    fn required_columns(&self) -> &[&str] {
+        &["symbol", "date", "high", "low"]
+    }
+
+    fn lookback(&self) -> usize {
+        self.config.lookback
+    }
+
+    fn frequency(&self) -> DataFrequency {
+        DataFrequency::Daily
+    }
+
+    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        let result = self
+            .rolling(data, date)
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
@@ -6,7 +6,10 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -84,6 +87,31 @@ impl DollarVolume {
             },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> LazyFrame {
+        data.clone()
+            .filter(on_or_before(date))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            // Calculate daily dollar volume
+            .with_column((col("close") * col("volume")).alias("daily_dollar_volume"))
+            // Rolling mean over lookback period
+            .with_column(
+                col("daily_dollar_volume")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("dollar_volume"),
+            )
+            // Select output columns
+            .select([col("symbol"), col("date"), col("dollar_volume")])
+    }
 }
 
 impl Default for DollarVolume {
@@ -118,30 +146,26 @@ impl Factor for DollarVolume {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let result = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())))
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            // Calculate daily dollar volume
-            .with_column((col("close") * col("volume")).alias("daily_dollar_volume"))
-            // Rolling mean over lookback period
-            .with_column(
-                col("daily_dollar_volume")
-                    .rolling_mean(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("dollar_volume"),
-            )
+        let result = self
+            .rolling(data, date)
             // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            // Select output columns
-            .select([col("symbol"), col("date"), col("dollar_volume")])
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
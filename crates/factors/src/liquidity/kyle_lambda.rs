@@ -5,8 +5,12 @@
 
 use crate::{
     Result,
+    features::{self, DAILY_RETURN, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -88,64 +92,23 @@ impl KyleLambda {
             },
         }
     }
-}
-
-impl Default for KyleLambda {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl Factor for KyleLambda {
-    fn name(&self) -> &str {
-        "kyle_lambda"
-    }
-
-    fn description(&self) -> &str {
-        "Price impact coefficient from regressing absolute return on signed volume over 20 days"
-    }
-
-    fn category(&self) -> FactorCategory {
-        FactorCategory::Liquidity
-    }
 
-    fn required_columns(&self) -> &[&str] {
-        &["symbol", "date", "close", "volume"]
-    }
-
-    fn lookback(&self) -> usize {
-        self.config.lookback
-    }
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> Result<LazyFrame> {
+        let filtered = data.clone().filter(on_or_before(date));
 
-    fn frequency(&self) -> DataFrequency {
-        DataFrequency::Daily
-    }
+        // Daily returns, shared across factors when run through the registry
+        let with_returns = features::with_features(filtered, &self.features())?;
 
-    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let result = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())))
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            // Calculate lagged close for returns
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            // Calculate returns
-            .with_column(((col("close") - col("close_lag")) / col("close_lag")).alias("returns"))
+        Ok(with_returns
             // Calculate absolute returns
-            .with_column(col("returns").abs().alias("abs_returns"))
+            .with_column(col(DAILY_RETURN).abs().alias("abs_returns"))
             // Calculate signed volume: volume * sign(returns)
             // sign(x) = 1 if x > 0, -1 if x < 0, 0 if x == 0
             .with_column(
-                when(col("returns").gt(lit(0.0)))
+                when(col(DAILY_RETURN).gt(lit(0.0)))
                     .then(col("volume"))
-                    .when(col("returns").lt(lit(0.0)))
+                    .when(col(DAILY_RETURN).lt(lit(0.0)))
                     .then(-col("volume"))
                     .otherwise(lit(0.0))
                     .alias("signed_volume"),
@@ -210,10 +173,67 @@ impl Factor for KyleLambda {
                     .otherwise(col("kyle_lambda_raw"))
                     .alias("kyle_lambda"),
             )
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
             // Select output columns
-            .select([col("symbol"), col("date"), col("kyle_lambda")])
+            .select([col("symbol"), col("date"), col("kyle_lambda")]))
+    }
+}
+
+impl Default for KyleLambda {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Factor for KyleLambda {
+    fn name(&self) -> &str {
+        "kyle_lambda"
+    }
+
+    fn description(&self) -> &str {
+        "Price impact coefficient from regressing absolute return on signed volume over 20 days"
+    }
+
+    fn category(&self) -> FactorCategory {
+        FactorCategory::Liquidity
+    }
+
+    fn required_columns(&self) -> &[&str] {
+        &["symbol", "date", "close", "volume"]
+    }
+
+    fn lookback(&self) -> usize {
+        self.config.lookback
+    }
+
+    fn frequency(&self) -> DataFrequency {
+        DataFrequency::Daily
+    }
+
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::DailyReturn]
+    }
+
+    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        let result = self
+            .rolling(data, date)?
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)?
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
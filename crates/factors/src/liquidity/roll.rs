@@ -6,7 +6,10 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -85,43 +88,11 @@ impl RollMeasure {
             },
         }
     }
-}
-
-impl Default for RollMeasure {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl Factor for RollMeasure {
-    fn name(&self) -> &str {
-        "roll_spread"
-    }
-
-    fn description(&self) -> &str {
-        "Implied spread from serial covariance of price changes over 20 days"
-    }
-
-    fn category(&self) -> FactorCategory {
-        FactorCategory::Liquidity
-    }
-
-    fn required_columns(&self) -> &[&str] {
-        &["symbol", "date", "close"]
-    }
 
-    fn lookback(&self) -> usize {
-        self.config.lookback
-    }
-
-    fn frequency(&self) -> DataFrequency {
-        DataFrequency::Daily
-    }
-
-    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let result = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())))
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> LazyFrame {
+        data.clone()
+            .filter(on_or_before(date))
             .sort(
                 ["symbol", "date"],
                 SortMultipleOptions::default().with_order_descending_multi([false, false]),
@@ -187,10 +158,63 @@ impl Factor for RollMeasure {
                     .otherwise(lit(0.0))
                     .alias("roll_spread"),
             )
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
             // Select output columns
             .select([col("symbol"), col("date"), col("roll_spread")])
+    }
+}
+
+impl Default for RollMeasure {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Factor for RollMeasure {
+    fn name(&self) -> &str {
+        "roll_spread"
+    }
+
+    fn description(&self) -> &str {
+        "Implied spread from serial covariance of price changes over 20 days"
+    }
+
+    fn category(&self) -> FactorCategory {
+        FactorCategory::Liquidity
+    }
+
+    fn required_columns(&self) -> &[&str] {
+        &["symbol", "date", "close"]
+    }
+
+    fn lookback(&self) -> usize {
+        self.config.lookback
+    }
+
+    fn frequency(&self) -> DataFrequency {
+        DataFrequency::Daily
+    }
+
+    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        let result = self
+            .rolling(data, date)
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
@@ -6,7 +6,10 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -85,6 +88,31 @@ impl TurnoverRatio {
             },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> LazyFrame {
+        data.clone()
+            .filter(on_or_before(date))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            // Calculate daily turnover ratio
+            .with_column((col("volume") / col("shares_outstanding")).alias("daily_turnover"))
+            // Rolling mean over lookback period
+            .with_column(
+                col("daily_turnover")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("turnover_ratio"),
+            )
+            // Select output columns
+            .select([col("symbol"), col("date"), col("turnover_ratio")])
+    }
 }
 
 impl Default for TurnoverRatio {
@@ -119,30 +147,26 @@ impl Factor for TurnoverRatio {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let result = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())))
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            // Calculate daily turnover ratio
-            .with_column((col("volume") / col("shares_outstanding")).alias("daily_turnover"))
-            // Rolling mean over lookback period
-            .with_column(
-                col("daily_turnover")
-                    .rolling_mean(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("turnover_ratio"),
-            )
+        let result = self
+            .rolling(data, date)
             // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            // Select output columns
-            .select([col("symbol"), col("date"), col("turnover_ratio")])
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
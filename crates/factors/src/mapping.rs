@@ -0,0 +1,216 @@
+//! Column mappings for heterogeneous input schemas.
+//!
+//! Factors read fixed canonical column names such as `book_equity` or
+//! `shareholders_equity`, while vendor files name the same data differently.
+//! A [`ColumnMapping`] maps canonical names to the source columns that hold
+//! them and is applied to the input before any factor runs. Mappings
+//! round-trip through TOML or JSON as a flat table of canonical name to
+//! source column:
+//!
+//! ```toml
+//! book_equity = "total_equity"
+//! shareholders_equity = "total_equity"
+//! market_cap = "mkt_val"
+//! ```
+//!
+//! Attach one to a registry with
+//! [`FactorRegistry::with_column_mapping`](crate::FactorRegistry::with_column_mapping)
+//! or through the `columns` table of a [`RegistrySpec`](crate::RegistrySpec).
+
+use crate::{FactorError, Result};
+use polars::prelude::*;
+use std::collections::BTreeMap;
+
+/// Mapping from canonical column names to source columns in the input.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct ColumnMapping {
+    sources: BTreeMap<String, String>,
+}
+
+impl ColumnMapping {
+    /// Create an empty mapping, which leaves the input unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the canonical column `canonical` from the source column `source`.
+    pub fn with_alias(mut self, canonical: impl Into<String>, source: impl Into<String>) -> Self {
+        self.sources.insert(canonical.into(), source.into());
+        self
+    }
+
+    /// Source column for `canonical`, which is `canonical` itself when unmapped.
+    pub fn source<'a>(&'a self, canonical: &'a str) -> &'a str {
+        self.sources
+            .get(canonical)
+            .map_or(canonical, String::as_str)
+    }
+
+    /// Mapped `(canonical, source)` pairs, sorted by canonical name.
+    pub fn aliases(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.sources.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Whether no column is mapped.
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// `data` with each mapped source column copied to its canonical name.
+    ///
+    /// A present source replaces any existing column of the canonical name and
+    /// is itself kept, so one source can feed several canonical columns. Sources
+    /// missing from `data` are skipped, leaving validation to report the
+    /// canonical column as missing.
+    pub fn apply(&self, data: LazyFrame) -> Result<LazyFrame> {
+        if self.is_empty() {
+            return Ok(data);
+        }
+        self.check()?;
+
+        let mut data = data;
+        let schema = data.collect_schema()?;
+        let copies: Vec<Expr> = self
+            .aliases()
+            .filter(|(canonical, source)| canonical != source && schema.contains(source))
+            .map(|(canonical, source)| col(source).alias(canonical))
+            .collect();
+        Ok(data.with_columns(copies))
+    }
+
+    /// `canonical`, annotated with its source column when mapped.
+    pub fn describe(&self, canonical: &str) -> String {
+        match self.source(canonical) {
+            source if source == canonical => canonical.to_string(),
+            source => format!("{canonical} (from {source})"),
+        }
+    }
+
+    /// Rewrite a missing-column error to name the mapped sources.
+    pub(crate) fn describe_error(&self, error: FactorError) -> FactorError {
+        match error {
+            FactorError::MissingColumn(column) => {
+                FactorError::MissingColumn(self.describe(&column))
+            }
+            FactorError::MissingColumns(columns) => FactorError::MissingColumns(
+                columns.iter().map(|column| self.describe(column)).collect(),
+            ),
+            other => other,
+        }
+    }
+
+    /// Reject empty column names.
+    fn check(&self) -> Result<()> {
+        match self
+            .aliases()
+            .find(|(canonical, source)| canonical.is_empty() || source.is_empty())
+        {
+            Some((canonical, source)) => Err(FactorError::InvalidSpec(format!(
+                "empty column name in mapping '{canonical}' = '{source}'"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Parse a mapping from TOML.
+    pub fn from_toml(input: &str) -> Result<Self> {
+        toml::from_str(input).map_err(|e| FactorError::InvalidSpec(e.to_string()))
+    }
+
+    /// Serialize the mapping to TOML.
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).map_err(|e| FactorError::InvalidSpec(e.to_string()))
+    }
+
+    /// Parse a mapping from JSON.
+    pub fn from_json(input: &str) -> Result<Self> {
+        serde_json::from_str(input).map_err(|e| FactorError::InvalidSpec(e.to_string()))
+    }
+
+    /// Serialize the mapping to pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| FactorError::InvalidSpec(e.to_string()))
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for ColumnMapping {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self {
+            sources: iter
+                .into_iter()
+                .map(|(canonical, source)| (canonical.into(), source.into()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_copies_sources_to_canonical_names() {
+        let data = df![
+            "symbol" => ["A", "B"],
+            "total_equity" => [10.0, 20.0],
+            "book_equity" => [1.0, 2.0],
+        ]
+        .unwrap()
+        .lazy();
+        let mapping = ColumnMapping::new()
+            .with_alias("book_equity", "total_equity")
+            .with_alias("shareholders_equity", "total_equity")
+            .with_alias("market_cap", "mkt_val");
+
+        let mapped = mapping.apply(data).unwrap().collect().unwrap();
+        for name in ["total_equity", "book_equity", "shareholders_equity"] {
+            let values: Vec<f64> = mapped
+                .column(name)
+                .unwrap()
+                .f64()
+                .unwrap()
+                .into_no_null_iter()
+                .collect();
+            assert_eq!(values, [10.0, 20.0], "{name}");
+        }
+        assert!(mapped.column("market_cap").is_err());
+
+        assert_eq!(mapping.source("market_cap"), "mkt_val");
+        assert_eq!(mapping.source("symbol"), "symbol");
+        assert_eq!(mapping.describe("market_cap"), "market_cap (from mkt_val)");
+        let error = mapping.describe_error(FactorError::MissingColumns(vec![
+            "symbol".to_string(),
+            "market_cap".to_string(),
+        ]));
+        assert_eq!(
+            error.to_string(),
+            "Missing required columns: symbol, market_cap (from mkt_val)"
+        );
+    }
+
+    #[test]
+    fn test_mapping_round_trips() {
+        let toml = "book_equity = \"total_equity\"\nmarket_cap = \"mkt_val\"\n";
+        let mapping = ColumnMapping::from_toml(toml).unwrap();
+        assert_eq!(
+            mapping,
+            ColumnMapping::from_iter([("market_cap", "mkt_val"), ("book_equity", "total_equity")])
+        );
+        assert_eq!(
+            ColumnMapping::from_toml(&mapping.to_toml().unwrap()).unwrap(),
+            mapping
+        );
+        assert_eq!(
+            ColumnMapping::from_json(&mapping.to_json().unwrap()).unwrap(),
+            mapping
+        );
+
+        assert!(ColumnMapping::from_toml("book_equity = 3").is_err());
+        let empty = ColumnMapping::new().with_alias("book_equity", "");
+        assert!(matches!(
+            empty.apply(LazyFrame::default()),
+            Err(FactorError::InvalidSpec(_))
+        ));
+    }
+}
@@ -3,7 +3,9 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -78,10 +80,7 @@ impl Factor for MomentumAcceleration {
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         let short_days = self.config.short_term_days;
         let long_days = self.config.long_term_days;
@@ -126,6 +125,48 @@ impl Factor for MomentumAcceleration {
 
         Ok(result)
     }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let short_days = self.config.short_term_days;
+        let long_days = self.config.long_term_days;
+
+        // Shift within each symbol so every row sees its own lagged prices
+        let result = data
+            .clone()
+            .filter(on_or_before(end))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            .with_column(
+                col("close")
+                    .shift(lit(short_days as i64))
+                    .over([col("symbol")])
+                    .alias("price_short"),
+            )
+            .with_column(
+                col("close")
+                    .shift(lit(long_days as i64))
+                    .over([col("symbol")])
+                    .alias("price_long"),
+            )
+            .with_column(((col("close") / col("price_short")) - lit(1.0)).alias("mom_short"))
+            .with_column(((col("close") / col("price_long")) - lit(1.0)).alias("mom_long"))
+            .with_column((col("mom_short") - col("mom_long")).alias(self.name()))
+            .filter(between_dates(start, end))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .filter(col(self.name()).is_not_null())
+            .collect()?;
+
+        Ok(result)
+    }
 }
 
 impl ConfigurableFactor for MomentumAcceleration {
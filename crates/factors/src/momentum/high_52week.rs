@@ -2,8 +2,11 @@
 
 use crate::{
     Result,
+    adjustment::PriceAdjustment,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -69,12 +72,14 @@ impl Factor for High52Week {
         DataFrequency::Daily
     }
 
+    /// Compares price levels with their own highs, so dividends are left in.
+    fn price_adjustment(&self) -> PriceAdjustment {
+        PriceAdjustment::PriceOnly
+    }
+
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         // Group by symbol and compute 52-week high ratio
         let result = filtered
@@ -99,6 +104,41 @@ impl Factor for High52Week {
 
         Ok(result)
     }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        // Rolling maximum over the lookback window, including the current day
+        let result = data
+            .clone()
+            .filter(on_or_before(end))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            .with_column(
+                col("close")
+                    .rolling_max(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback + 1,
+                        min_periods: 1,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("max_price"),
+            )
+            .with_column((col("close") / col("max_price")).alias(self.name()))
+            .filter(between_dates(start, end))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .filter(col(self.name()).is_not_null())
+            .collect()?;
+
+        Ok(result)
+    }
 }
 
 impl ConfigurableFactor for High52Week {
@@ -3,7 +3,9 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -78,10 +80,7 @@ impl Factor for MACrossover {
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         let short_window = self.config.short_window;
         let long_window = self.config.long_window;
@@ -112,6 +111,54 @@ impl Factor for MACrossover {
 
         Ok(result)
     }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let short_window = self.config.short_window;
+        let long_window = self.config.long_window;
+
+        // Rolling SMAs; partial windows match the tail-based point-in-time path
+        let result = data
+            .clone()
+            .filter(on_or_before(end))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            .with_column(
+                col("close")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: short_window,
+                        min_periods: 1,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("sma_short"),
+            )
+            .with_column(
+                col("close")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: long_window,
+                        min_periods: 1,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("sma_long"),
+            )
+            .with_column(((col("sma_short") / col("sma_long")) - lit(1.0)).alias(self.name()))
+            .filter(between_dates(start, end))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .filter(col(self.name()).is_not_null())
+            .collect()?;
+
+        Ok(result)
+    }
 }
 
 impl ConfigurableFactor for MACrossover {
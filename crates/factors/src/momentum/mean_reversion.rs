@@ -3,7 +3,9 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -76,10 +78,7 @@ impl Factor for MeanReversion {
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         let lookback = self.config.lookback;
         let num_std_dev = self.config.num_std_dev;
@@ -118,6 +117,57 @@ impl Factor for MeanReversion {
 
         Ok(result)
     }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let lookback = self.config.lookback;
+        let num_std_dev = self.config.num_std_dev;
+
+        // Rolling SMA and standard deviation over the lookback window
+        let result = data
+            .clone()
+            .filter(on_or_before(end))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            .with_column(
+                col("close")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: lookback,
+                        min_periods: 1,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("sma"),
+            )
+            .with_column(
+                col("close")
+                    .rolling_std(RollingOptionsFixedWindow {
+                        window_size: lookback,
+                        min_periods: 1,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("std"),
+            )
+            .with_column(
+                // (P_t - SMA) / (num_std_dev * σ)
+                ((col("close") - col("sma")) / (lit(num_std_dev) * col("std"))).alias(self.name()),
+            )
+            .filter(between_dates(start, end))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .filter(col(self.name()).is_not_null())
+            .collect()?;
+
+        Ok(result)
+    }
 }
 
 impl ConfigurableFactor for MeanReversion {
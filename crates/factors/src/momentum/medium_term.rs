@@ -3,7 +3,9 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -77,10 +79,7 @@ impl Factor for MediumTermMomentum {
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         let skip_days = self.config.skip_days;
         let lookback = self.config.lookback;
@@ -119,6 +118,48 @@ impl Factor for MediumTermMomentum {
 
         Ok(result)
     }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let skip_days = self.config.skip_days;
+        let lookback = self.config.lookback;
+
+        // Shift within each symbol so every row sees its own skip-period prices
+        let result = data
+            .clone()
+            .filter(on_or_before(end))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            .with_column(
+                col("close")
+                    .shift(lit(skip_days as i64))
+                    .over([col("symbol")])
+                    .alias("current_price"),
+            )
+            .with_column(
+                col("close")
+                    .shift(lit((lookback + skip_days) as i64))
+                    .over([col("symbol")])
+                    .alias("lagged_price"),
+            )
+            .with_column(
+                ((col("current_price") / col("lagged_price")) - lit(1.0)).alias(self.name()),
+            )
+            .filter(between_dates(start, end))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .filter(col(self.name()).is_not_null())
+            .collect()?;
+
+        Ok(result)
+    }
 }
 
 impl ConfigurableFactor for MediumTermMomentum {
@@ -201,4 +242,54 @@ mod tests {
         assert_eq!(factor.frequency(), DataFrequency::Daily);
         assert_eq!(factor.required_columns(), &["symbol", "date", "close"]);
     }
+
+    #[test]
+    fn test_medium_term_momentum_typed_dates() {
+        let factor = MediumTermMomentum::default();
+
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let dates: Vec<NaiveDate> = (0..148)
+            .map(|i| start.checked_add_days(chrono::Days::new(i)).unwrap())
+            .collect();
+        let prices: Vec<f64> = (0..148).map(|i| 100.0 + i as f64 * 0.1984).collect();
+
+        let typed = df! {
+            "symbol" => vec!["AAPL"; 148],
+            "date" => dates.clone(),
+            "close" => prices.clone(),
+        }
+        .unwrap();
+        let datetimes = typed
+            .clone()
+            .lazy()
+            .with_column(col("date").cast(DataType::Datetime(TimeUnit::Milliseconds, None)));
+        let strings = df! {
+            "symbol" => vec!["AAPL"; 148],
+            "date" => dates.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            "close" => prices,
+        }
+        .unwrap();
+
+        // Date, Datetime and string columns all select the same rows
+        let date = NaiveDate::from_ymd_opt(2024, 5, 27).unwrap();
+        let expected = factor.compute_raw(&strings.lazy(), date).unwrap();
+        for data in [typed.lazy(), datetimes] {
+            let result = factor.compute_raw(&data, date).unwrap();
+            assert_eq!(result.height(), 1);
+            assert_eq!(
+                result
+                    .column("medium_term_momentum")
+                    .unwrap()
+                    .f64()
+                    .unwrap()
+                    .get(0),
+                expected
+                    .column("medium_term_momentum")
+                    .unwrap()
+                    .f64()
+                    .unwrap()
+                    .get(0)
+            );
+        }
+    }
 }
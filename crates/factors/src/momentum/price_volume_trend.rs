@@ -3,7 +3,9 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -69,10 +71,7 @@ impl Factor for PriceVolumeTrend {
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         let lookback = self.config.lookback;
 
@@ -141,6 +140,52 @@ impl Factor for PriceVolumeTrend {
 
         Ok(df)
     }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let lookback = self.config.lookback;
+
+        // Volume-weighted daily returns summed over the lookback window
+        let result = data
+            .clone()
+            .filter(on_or_before(end))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            .with_column(
+                col("close")
+                    .shift(lit(1))
+                    .over([col("symbol")])
+                    .alias("close_lag"),
+            )
+            .with_column(
+                (((col("close") - col("close_lag")) / col("close_lag")) * col("volume"))
+                    .alias("weighted_change"),
+            )
+            .with_column(
+                col("weighted_change")
+                    .rolling_sum(RollingOptionsFixedWindow {
+                        window_size: lookback,
+                        min_periods: lookback,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias(self.name()),
+            )
+            .filter(between_dates(start, end))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .filter(col(self.name()).is_not_null())
+            .collect()?;
+
+        Ok(result)
+    }
 }
 
 impl ConfigurableFactor for PriceVolumeTrend {
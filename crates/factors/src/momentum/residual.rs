@@ -2,8 +2,12 @@
 
 use crate::{
     Result,
+    features::{self, DAILY_RETURN, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -76,31 +80,20 @@ impl Factor for ResidualMomentum {
         DataFrequency::Daily
     }
 
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::DailyReturn]
+    }
+
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())));
+        let filtered = data.clone().filter(on_or_before(date));
 
         let lookback = self.config.lookback;
         let skip_days = self.config.skip_days;
 
-        // Compute stock returns using shift
-        let with_returns = filtered
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            .with_column(
-                ((col("close") - col("close_lag")) / col("close_lag")).alias("stock_return"),
-            )
-            .filter(col("stock_return").is_not_null());
+        // Daily returns, shared across factors when run through the registry
+        let with_returns = features::with_features(filtered, &self.features())?
+            .filter(col(DAILY_RETURN).is_not_null());
 
         // Compute regression statistics for each symbol using all available data
         // Then compute residuals for each day
@@ -109,11 +102,11 @@ impl Factor for ResidualMomentum {
             .group_by([col("symbol")])
             .agg([
                 // Compute mean of stock returns
-                col("stock_return").mean().alias("mean_stock"),
+                col(DAILY_RETURN).mean().alias("mean_stock"),
                 // Compute mean of market returns
                 col("market_return").mean().alias("mean_market"),
                 // Compute covariance
-                ((col("stock_return") - col("stock_return").mean())
+                ((col(DAILY_RETURN) - col(DAILY_RETURN).mean())
                     * (col("market_return") - col("market_return").mean()))
                 .sum()
                 .alias("covariance"),
@@ -138,7 +131,7 @@ impl Factor for ResidualMomentum {
             )
             // Compute residuals for each day
             .with_column(
-                (col("stock_return") - (col("alpha") + col("beta") * col("market_return")))
+                (col(DAILY_RETURN) - (col("alpha") + col("beta") * col("market_return")))
                     .alias("residual"),
             );
 
@@ -156,7 +149,110 @@ impl Factor for ResidualMomentum {
                     .sum()
                     .alias(self.name()),
             ])
-            .filter(col("date").eq(lit(date.to_string())))
+            .filter(on_date(date))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .filter(col(self.name()).is_not_null())
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let lookback = self.config.lookback;
+        let skip_days = self.config.skip_days;
+        let residual_count = lookback - skip_days;
+        let window = RollingOptionsFixedWindow {
+            window_size: residual_count,
+            min_periods: residual_count,
+            ..Default::default()
+        };
+
+        // Daily returns, shared across factors when run through the registry
+        let with_returns =
+            features::with_features(data.clone().filter(on_or_before(end)), &self.features())?
+                .filter(col(DAILY_RETURN).is_not_null());
+
+        // Expanding regression sums, so each row sees the coefficients fitted on
+        // all data up to and including that row (as the point-in-time path does)
+        let with_regression_coefs = with_returns
+            .with_columns([
+                col(DAILY_RETURN)
+                    .cum_count(false)
+                    .over([col("symbol")])
+                    .cast(DataType::Float64)
+                    .alias("n"),
+                col(DAILY_RETURN)
+                    .cum_sum(false)
+                    .over([col("symbol")])
+                    .alias("sum_stock"),
+                col("market_return")
+                    .cum_sum(false)
+                    .over([col("symbol")])
+                    .alias("sum_market"),
+                (col(DAILY_RETURN) * col("market_return"))
+                    .cum_sum(false)
+                    .over([col("symbol")])
+                    .alias("sum_cross"),
+                col("market_return")
+                    .pow(2)
+                    .cum_sum(false)
+                    .over([col("symbol")])
+                    .alias("sum_market_sq"),
+            ])
+            .with_column(
+                ((col("sum_cross") - col("sum_stock") * col("sum_market") / col("n"))
+                    / (col("sum_market_sq") - col("sum_market").pow(2) / col("n")))
+                .alias("beta"),
+            )
+            .with_column(
+                ((col("sum_stock") - col("beta") * col("sum_market")) / col("n")).alias("alpha"),
+            );
+
+        // Sum residuals from t-lookback to t-skip_days using each row's coefficients:
+        // Σ(r - α - β·m) = Σr - k·α - β·Σm. Before a full lookback is available the
+        // window is the first min(n, lookback - skip_days) returns, as in the
+        // point-in-time path.
+        let full = col("n").gt_eq(lit(lookback as f64));
+        let head = col("n").lt_eq(lit(residual_count as f64));
+        let window_sum = |name: &str| {
+            when(full.clone())
+                .then(
+                    col(name)
+                        .rolling_sum(window.clone())
+                        .shift(lit(skip_days as i64))
+                        .over([col("symbol")]),
+                )
+                .otherwise(
+                    when(head.clone())
+                        .then(col(name))
+                        .otherwise(lit(0.0))
+                        .cum_sum(false)
+                        .over([col("symbol")]),
+                )
+        };
+        let result = with_regression_coefs
+            .with_columns([
+                window_sum(DAILY_RETURN).alias("window_stock"),
+                window_sum("market_return").alias("window_market"),
+                when(head.clone())
+                    .then(col("n"))
+                    .otherwise(lit(residual_count as f64))
+                    .alias("window_count"),
+            ])
+            .with_column(
+                (col("window_stock")
+                    - col("window_count") * col("alpha")
+                    - col("beta") * col("window_market"))
+                .alias(self.name()),
+            )
+            .filter(between_dates(start, end))
             .select([col("symbol"), col("date"), col(self.name())])
             .filter(col(self.name()).is_not_null())
             .collect()?;
@@ -3,7 +3,9 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -69,10 +71,7 @@ impl Factor for RSI {
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         // Calculate RSI for each symbol by processing grouped data
         let mut results = Vec::new();
@@ -146,6 +145,77 @@ impl Factor for RSI {
 
         Ok(df)
     }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let period = self.config.period;
+
+        // Split daily price changes into gains and losses, then average over the period
+        let result = data
+            .clone()
+            .filter(on_or_before(end))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            .with_column(
+                (col("close") - col("close").shift(lit(1)))
+                    .over([col("symbol")])
+                    .alias("change"),
+            )
+            .with_column(
+                when(col("change").lt(lit(0.0)))
+                    .then(lit(0.0))
+                    .otherwise(col("change"))
+                    .alias("gain"),
+            )
+            .with_column(
+                when(col("change").gt(lit(0.0)))
+                    .then(lit(0.0))
+                    .otherwise(-col("change"))
+                    .alias("loss"),
+            )
+            .with_column(
+                col("gain")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: period,
+                        min_periods: period,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("avg_gain"),
+            )
+            .with_column(
+                col("loss")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: period,
+                        min_periods: period,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("avg_loss"),
+            )
+            .with_column(
+                when(col("avg_loss").eq(lit(0.0)))
+                    .then(lit(100.0))
+                    .otherwise(
+                        lit(100.0) - (lit(100.0) / (lit(1.0) + col("avg_gain") / col("avg_loss"))),
+                    )
+                    .alias(self.name()),
+            )
+            .filter(between_dates(start, end))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .filter(col(self.name()).is_not_null())
+            .collect()?;
+
+        Ok(result)
+    }
 }
 
 impl ConfigurableFactor for RSI {
@@ -3,7 +3,9 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -73,10 +75,7 @@ impl Factor for ShortTermMomentum {
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         let skip_days = self.config.skip_days;
         let lookback = self.config.lookback;
@@ -115,6 +114,48 @@ impl Factor for ShortTermMomentum {
 
         Ok(result)
     }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let skip_days = self.config.skip_days;
+        let lookback = self.config.lookback;
+
+        // Shift within each symbol so every row sees its own skip-period prices
+        let result = data
+            .clone()
+            .filter(on_or_before(end))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            .with_column(
+                col("close")
+                    .shift(lit(skip_days as i64))
+                    .over([col("symbol")])
+                    .alias("current_price"),
+            )
+            .with_column(
+                col("close")
+                    .shift(lit((lookback + skip_days) as i64))
+                    .over([col("symbol")])
+                    .alias("lagged_price"),
+            )
+            .with_column(
+                ((col("current_price") / col("lagged_price")) - lit(1.0)).alias(self.name()),
+            )
+            .filter(between_dates(start, end))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .filter(col(self.name()).is_not_null())
+            .collect()?;
+
+        Ok(result)
+    }
 }
 
 impl ConfigurableFactor for ShortTermMomentum {
@@ -2,8 +2,12 @@
 
 use crate::{
     Result,
+    features::{self, DAILY_RETURN, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -43,61 +47,24 @@ pub struct TimeSeriesMomentum {
     config: TimeSeriesMomentumConfig,
 }
 
-impl Factor for TimeSeriesMomentum {
-    fn name(&self) -> &str {
-        "time_series_momentum"
-    }
-
-    fn description(&self) -> &str {
-        "Sign of 252-day return scaled by inverse volatility"
-    }
-
-    fn category(&self) -> FactorCategory {
-        FactorCategory::Momentum
-    }
-
-    fn required_columns(&self) -> &[&str] {
-        &["symbol", "date", "close"]
-    }
-
-    fn lookback(&self) -> usize {
-        self.config.lookback
-    }
-
-    fn frequency(&self) -> DataFrequency {
-        DataFrequency::Daily
-    }
-
-    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+impl TimeSeriesMomentum {
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> Result<LazyFrame> {
         // Filter to dates up to and including the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())));
-
-        // Compute daily returns using shift
-        let with_returns = filtered
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            .with_column(((col("close") - col("close_lag")) / col("close_lag")).alias("return"))
-            .with_column(
-                col("close")
-                    .shift(lit(self.lookback() as i64))
-                    .over([col("symbol")])
-                    .alias("close_lagged"),
-            );
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Daily returns, shared across factors when run through the registry
+        let with_returns = features::with_features(filtered, &self.features())?.with_column(
+            col("close")
+                .shift(lit(self.lookback() as i64))
+                .over([col("symbol")])
+                .alias("close_lagged"),
+        );
 
         // Compute rolling volatility and total return
-        let result = with_returns
+        Ok(with_returns
             .with_column(
-                col("return")
+                col(DAILY_RETURN)
                     .rolling_std(RollingOptionsFixedWindow {
                         window_size: self.lookback(),
                         min_periods: self.lookback(),
@@ -123,13 +90,65 @@ impl Factor for TimeSeriesMomentum {
                     .otherwise(col("sign_return") / lit(0.0001))
                     .alias(self.name()),
             )
-            .filter(col("date").eq(lit(date.to_string())))
             .select([col("symbol"), col("date"), col(self.name())])
             .filter(
                 col(self.name())
                     .is_not_null()
                     .and(col(self.name()).is_finite()),
-            )
+            ))
+    }
+}
+
+impl Factor for TimeSeriesMomentum {
+    fn name(&self) -> &str {
+        "time_series_momentum"
+    }
+
+    fn description(&self) -> &str {
+        "Sign of 252-day return scaled by inverse volatility"
+    }
+
+    fn category(&self) -> FactorCategory {
+        FactorCategory::Momentum
+    }
+
+    fn required_columns(&self) -> &[&str] {
+        &["symbol", "date", "close"]
+    }
+
+    fn lookback(&self) -> usize {
+        self.config.lookback
+    }
+
+    fn frequency(&self) -> DataFrequency {
+        DataFrequency::Daily
+    }
+
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::DailyReturn]
+    }
+
+    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        let result = self
+            .rolling(data, date)?
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)?
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
@@ -3,7 +3,9 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -72,10 +74,7 @@ impl Factor for VolatilityBreakout {
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         // Calculate volatility breakout for each symbol by processing grouped data
         let mut results = Vec::new();
@@ -180,6 +179,80 @@ impl Factor for VolatilityBreakout {
 
         Ok(df)
     }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let lookback = self.lookback();
+
+        // True range against the previous close, averaged into ATR alongside the SMA
+        let result = data
+            .clone()
+            .filter(on_or_before(end))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            .with_column(
+                col("close")
+                    .shift(lit(1))
+                    .over([col("symbol")])
+                    .alias("prev_close"),
+            )
+            .with_column((col("high") - col("low")).alias("hl_range"))
+            .with_column((col("high") - col("prev_close")).abs().alias("hc_range"))
+            .with_column((col("low") - col("prev_close")).abs().alias("lc_range"))
+            .with_column(
+                when(col("hl_range").gt_eq(col("hc_range")))
+                    .then(col("hl_range"))
+                    .otherwise(col("hc_range"))
+                    .alias("true_range"),
+            )
+            .with_column(
+                when(col("true_range").gt_eq(col("lc_range")))
+                    .then(col("true_range"))
+                    .otherwise(col("lc_range"))
+                    .alias("true_range"),
+            )
+            .with_column(
+                col("close")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: lookback,
+                        min_periods: lookback,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("sma"),
+            )
+            .with_column(
+                col("true_range")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: lookback,
+                        min_periods: lookback,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("atr"),
+            )
+            .with_column(
+                // (P_t - SMA) / ATR, or 0 when ATR is zero
+                when(col("atr").gt(lit(0.0)))
+                    .then((col("close") - col("sma")) / col("atr"))
+                    .otherwise(lit(0.0))
+                    .alias(self.name()),
+            )
+            .filter(col("atr").is_not_null())
+            .filter(between_dates(start, end))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .collect()?;
+
+        Ok(result)
+    }
 }
 
 impl ConfigurableFactor for VolatilityBreakout {
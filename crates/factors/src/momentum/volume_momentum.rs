@@ -3,7 +3,9 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -78,10 +80,7 @@ impl Factor for VolumeMomentum {
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         let short_window = self.config.short_window;
         let long_window = self.config.long_window;
@@ -117,6 +116,59 @@ impl Factor for VolumeMomentum {
 
         Ok(result)
     }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let short_window = self.config.short_window;
+        let long_window = self.config.long_window;
+
+        // Rolling average volumes; partial windows match the tail-based point-in-time path
+        let result = data
+            .clone()
+            .filter(on_or_before(end))
+            .sort(
+                ["symbol", "date"],
+                SortMultipleOptions::default().with_order_descending_multi([false, false]),
+            )
+            .with_column(
+                col("volume")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: short_window,
+                        min_periods: 1,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("vol_short"),
+            )
+            .with_column(
+                col("volume")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: long_window,
+                        min_periods: 1,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("vol_long"),
+            )
+            .with_column(
+                when(col("vol_long").gt(lit(1.0)))
+                    .then(col("vol_short") / col("vol_long"))
+                    .otherwise(col("vol_short"))
+                    .alias(self.name()),
+            )
+            .filter(between_dates(start, end))
+            .select([col("symbol"), col("date"), col(self.name())])
+            .filter(col(self.name()).is_not_null())
+            .collect()?;
+
+        Ok(result)
+    }
 }
 
 impl ConfigurableFactor for VolumeMomentum {
@@ -0,0 +1,343 @@
+//! Cross-sectional orthogonalization of factor exposures.
+//!
+//! Style factors often overlap: residual momentum loads on beta, liquidity on
+//! size. These helpers work on wide exposure frames such as the output of
+//! [`FactorRegistry::compute_all`](crate::FactorRegistry::compute_all), one
+//! date at a time:
+//!
+//! - [`orthogonalize`] regresses one exposure on others, optionally
+//!   cap-weighted, and keeps the residual.
+//! - [`symmetric_orthogonalize`] applies Löwdin's symmetric orthogonalization,
+//!   decorrelating a set of exposures while moving each as little as possible.
+//!
+//! [`Orthogonalization`] wraps both as serializable steps.
+
+use crate::{
+    Result,
+    cross_section::{fit_residuals, float_columns, rows_by_date},
+};
+use ndarray::{Array1, Array2};
+use polars::prelude::*;
+
+/// An orthogonalization step applied to a wide exposure frame.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Orthogonalization {
+    /// Replace `target` with its residual against `against`; see [`orthogonalize`]
+    Residual {
+        /// Exposure to orthogonalize
+        target: String,
+        /// Exposures to remove from the target
+        against: Vec<String>,
+        /// Optional regression weight column, e.g. market cap
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        weight: Option<String>,
+    },
+    /// Decorrelate `columns` symmetrically; see [`symmetric_orthogonalize`]
+    Symmetric {
+        /// Exposures to orthogonalize together
+        columns: Vec<String>,
+    },
+}
+
+impl Orthogonalization {
+    /// Apply this step to `df`.
+    pub fn apply(&self, df: &DataFrame) -> Result<DataFrame> {
+        match self {
+            Self::Residual {
+                target,
+                against,
+                weight,
+            } => {
+                let against: Vec<&str> = against.iter().map(String::as_str).collect();
+                orthogonalize(df, target, &against, weight.as_deref())
+            }
+            Self::Symmetric { columns } => {
+                let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+                symmetric_orthogonalize(df, &columns)
+            }
+        }
+    }
+}
+
+/// Regression-based orthogonalization of one exposure against others.
+///
+/// On each date, regresses `target` on an intercept and the `against`
+/// columns, weighting each row's squared error by `weight` if given, and
+/// replaces `target` with the residuals. The residuals are (weighted) uncorrelated with every `against`
+/// column on every date.
+///
+/// Rows with a null target, regressor or weight, or a non-positive weight,
+/// get a null residual, as do all rows on a date with no more observations
+/// than regressors.
+///
+/// # Arguments
+///
+/// * `df` - DataFrame with columns: `date`, `target`, `against` and `weight`
+/// * `target` - Name of the column to orthogonalize
+/// * `against` - Columns to remove from the target, e.g. `market_beta`
+/// * `weight` - Optional regression weight column, e.g. raw market cap
+///
+/// # Returns
+///
+/// DataFrame with the target column replaced by residuals.
+pub fn orthogonalize(
+    df: &DataFrame,
+    target: &str,
+    against: &[&str],
+    weight: Option<&str>,
+) -> Result<DataFrame> {
+    let values = float_columns(df, &[target])?;
+    let regressors = float_columns(df, against)?;
+    let weights = weight.map(|w| float_columns(df, &[w])).transpose()?;
+
+    let rows_by_date = rows_by_date(df, |row| {
+        values[0].get(row).is_some()
+            && regressors.iter().all(|r| r.get(row).is_some())
+            && weights
+                .as_ref()
+                .is_none_or(|w| w[0].get(row).is_some_and(|w| w > 0.0))
+    })?;
+
+    let n_cols = against.len() + 1;
+    let mut residuals: Vec<Option<f64>> = vec![None; df.height()];
+    for rows in rows_by_date.values() {
+        if rows.len() <= n_cols {
+            continue;
+        }
+
+        let mut x = Array2::ones((rows.len(), n_cols));
+        let mut y = Array1::zeros(rows.len());
+        let mut w = Array1::ones(rows.len());
+        for (i, &row) in rows.iter().enumerate() {
+            for (j, regressor) in regressors.iter().enumerate() {
+                x[[i, j + 1]] = regressor.get(row).unwrap_or_default();
+            }
+            y[i] = values[0].get(row).unwrap_or_default();
+            if let Some(weights) = &weights {
+                w[i] = weights[0].get(row).unwrap_or_default().sqrt();
+            }
+        }
+
+        fit_residuals(rows, &y, &x, &w, "orthogonalization", &mut residuals)?;
+    }
+
+    let mut result = df.clone();
+    result.with_column(Column::new(target.into(), residuals))?;
+
+    Ok(result)
+}
+
+/// Symmetric (Löwdin) orthogonalization of a set of exposures.
+///
+/// On each date, demeans the `columns` to `X` and replaces them with
+/// `X · S^(-1/2)`, where `S = XᵀX / (n - 1)` is their sample covariance. The
+/// results are uncorrelated with unit variance, and of all such rotations are
+/// the closest to the originals in the least-squares sense, so no factor is
+/// privileged the way the first one is in Gram-Schmidt.
+///
+/// Rows with a null in any of `columns` get nulls, as do all rows on a date
+/// with no more observations than columns or with collinear exposures.
+///
+/// # Arguments
+///
+/// * `df` - DataFrame with columns: `date` and `columns`
+/// * `columns` - Exposures to orthogonalize together
+///
+/// # Returns
+///
+/// DataFrame with `columns` replaced by their orthogonalized values.
+pub fn symmetric_orthogonalize(df: &DataFrame, columns: &[&str]) -> Result<DataFrame> {
+    let values = float_columns(df, columns)?;
+    let rows_by_date = rows_by_date(df, |row| values.iter().all(|v| v.get(row).is_some()))?;
+
+    let k = columns.len();
+    let mut results: Vec<Vec<Option<f64>>> = vec![vec![None; df.height()]; k];
+    for rows in rows_by_date.values() {
+        let n = rows.len();
+        if n <= k {
+            continue;
+        }
+
+        let mut x = Array2::zeros((n, k));
+        for (i, &row) in rows.iter().enumerate() {
+            for (j, column) in values.iter().enumerate() {
+                x[[i, j]] = column.get(row).unwrap_or_default();
+            }
+        }
+        let means = x.mean_axis(ndarray::Axis(0)).expect("n > 0");
+        x -= &means;
+
+        let covariance = x.t().dot(&x) / (n - 1) as f64;
+        let Some(inverse_sqrt) = inverse_sqrt(&covariance) else {
+            continue;
+        };
+        let orthogonal = x.dot(&inverse_sqrt);
+        for (i, &row) in rows.iter().enumerate() {
+            for (j, result) in results.iter_mut().enumerate() {
+                result[row] = Some(orthogonal[[i, j]]);
+            }
+        }
+    }
+
+    let mut result = df.clone();
+    for (name, values) in columns.iter().zip(results) {
+        result.with_column(Column::new((*name).into(), values))?;
+    }
+
+    Ok(result)
+}
+
+/// `S^(-1/2)` of a symmetric positive definite matrix, or `None` if it is
+/// (numerically) singular.
+fn inverse_sqrt(matrix: &Array2<f64>) -> Option<Array2<f64>> {
+    let (eigenvalues, eigenvectors) = symmetric_eigen(matrix);
+    let largest = eigenvalues.iter().copied().fold(0.0, f64::max);
+    if eigenvalues.iter().any(|&l| l <= largest * 1e-12) {
+        return None;
+    }
+    let scaled = &eigenvectors * &eigenvalues.mapv(|l| 1.0 / l.sqrt());
+    Some(scaled.dot(&eigenvectors.t()))
+}
+
+/// Eigenvalues and eigenvectors (as columns) of a symmetric matrix, by cyclic
+/// Jacobi rotations.
+pub(crate) fn symmetric_eigen(matrix: &Array2<f64>) -> (Array1<f64>, Array2<f64>) {
+    let n = matrix.nrows();
+    let mut a = matrix.clone();
+    let mut v = Array2::eye(n);
+    let scale = a.iter().map(|x| x * x).sum::<f64>().max(f64::MIN_POSITIVE);
+
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..n)
+            .flat_map(|p| (0..n).filter(move |&q| q != p).map(move |q| (p, q)))
+            .map(|(p, q)| a[[p, q]] * a[[p, q]])
+            .sum();
+        if off_diagonal <= scale * 1e-30 {
+            break;
+        }
+
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[[p, q]] == 0.0 {
+                    continue;
+                }
+                // Rotation zeroing a[p, q]
+                let theta = (a[[q, q]] - a[[p, p]]) / (2.0 * a[[p, q]]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for k in 0..n {
+                    let (kp, kq) = (a[[k, p]], a[[k, q]]);
+                    a[[k, p]] = c * kp - s * kq;
+                    a[[k, q]] = s * kp + c * kq;
+                }
+                for k in 0..n {
+                    let (pk, qk) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = c * pk - s * qk;
+                    a[[q, k]] = s * pk + c * qk;
+                }
+                for k in 0..n {
+                    let (kp, kq) = (v[[k, p]], v[[k, q]]);
+                    v[[k, p]] = c * kp - s * kq;
+                    v[[k, q]] = s * kp + c * kq;
+                }
+            }
+        }
+    }
+
+    (a.diag().to_owned(), v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn exposures() -> DataFrame {
+        df![
+            "symbol" => ["A", "B", "C", "D", "E", "F", "A", "B", "C", "D"],
+            "date" => ["2024-01-02", "2024-01-02", "2024-01-02", "2024-01-02", "2024-01-02",
+                       "2024-01-02", "2024-01-03", "2024-01-03", "2024-01-03", "2024-01-03"],
+            "momentum" => [1.0, 2.5, -0.5, 0.3, -1.2, 0.8, 0.4, -0.2, 1.1, -0.9],
+            "beta" => [0.9, 1.8, -0.2, 0.1, -1.0, 0.4, 1.0, 0.2, -0.6, 0.3],
+            "size" => [Some(0.1), Some(-0.3), Some(0.7), Some(-1.1), Some(0.5), Some(0.2),
+                       Some(0.3), None, Some(-0.4), Some(0.8)],
+            "cap" => [10.0, 50.0, 20.0, 5.0, 8.0, 30.0, 10.0, 20.0, 30.0, 40.0],
+        ]
+        .unwrap()
+    }
+
+    fn values(df: &DataFrame, column: &str, rows: std::ops::Range<usize>) -> Vec<f64> {
+        let column = df.column(column).unwrap().f64().unwrap();
+        rows.map(|row| column.get(row).unwrap()).collect()
+    }
+
+    fn dot(a: &[f64], b: &[f64], w: &[f64]) -> f64 {
+        a.iter().zip(b).zip(w).map(|((a, b), w)| a * b * w).sum()
+    }
+
+    #[test]
+    fn test_orthogonalize_weighted_residuals() {
+        let df = exposures();
+        let result = orthogonalize(&df, "momentum", &["beta", "size"], Some("cap")).unwrap();
+
+        let residual = values(&result, "momentum", 0..6);
+        let beta = values(&df, "beta", 0..6);
+        let cap = values(&df, "cap", 0..6);
+        assert_relative_eq!(dot(&residual, &[1.0; 6], &cap), 0.0, epsilon = 1e-10);
+        assert_relative_eq!(dot(&residual, &beta, &cap), 0.0, epsilon = 1e-10);
+
+        // The second date has three complete rows for three regressors
+        let momentum = result.column("momentum").unwrap();
+        assert_eq!(momentum.null_count(), 4);
+        // Other columns are untouched
+        assert!(
+            result
+                .column("beta")
+                .unwrap()
+                .equals(df.column("beta").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_symmetric_orthogonalize_decorrelates() {
+        let df = exposures();
+        let step = Orthogonalization::Symmetric {
+            columns: vec!["momentum".to_string(), "beta".to_string()],
+        };
+        let result = step.apply(&df).unwrap();
+
+        for rows in [0..6, 6..10] {
+            let n = rows.len();
+            let momentum = values(&result, "momentum", rows.clone());
+            let beta = values(&result, "beta", rows);
+            let ones = vec![1.0; n];
+            let scale = (n - 1) as f64;
+            assert_relative_eq!(dot(&momentum, &ones, &ones), 0.0, epsilon = 1e-10);
+            assert_relative_eq!(dot(&momentum, &beta, &ones) / scale, 0.0, epsilon = 1e-10);
+            assert_relative_eq!(
+                dot(&momentum, &momentum, &ones) / scale,
+                1.0,
+                epsilon = 1e-10
+            );
+            assert_relative_eq!(dot(&beta, &beta, &ones) / scale, 1.0, epsilon = 1e-10);
+        }
+
+        // Correlated inputs stay close to the originals
+        let original = values(&df, "momentum", 0..6);
+        let orthogonal = values(&result, "momentum", 0..6);
+        assert!(dot(&original, &orthogonal, &[1.0; 6]) > 0.0);
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let matrix = ndarray::array![[4.0, 1.0, 0.5], [1.0, 3.0, 0.2], [0.5, 0.2, 1.0]];
+        let (values, vectors) = symmetric_eigen(&matrix);
+        let rebuilt = (&vectors * &values).dot(&vectors.t());
+        for (a, b) in rebuilt.iter().zip(matrix.iter()) {
+            assert_relative_eq!(a, b, epsilon = 1e-12);
+        }
+    }
+}
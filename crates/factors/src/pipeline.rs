@@ -0,0 +1,495 @@
+//! Composable preprocessing pipelines for factor values.
+//!
+//! A [`Pipeline`] is an ordered list of [`Transform`]s applied to raw factor
+//! values, replacing the single z-score step in [`Factor::compute`]. Pipelines
+//! are serializable, so an exposure file can record exactly how its values were
+//! processed, and can be attached to a single factor with
+//! [`Factor::with_pipeline`] or to a whole registry with
+//! [`FactorRegistry::with_pipeline`](crate::FactorRegistry::with_pipeline).
+
+use crate::{
+    Factor, Result,
+    adjustment::PriceAdjustment,
+    calendar::TradingCalendar,
+    features::Feature,
+    registry::FactorCategory,
+    spec::AliasedFactor,
+    standardize::{
+        FillStrategy, Standardization, cross_sectional_standardize, fill_missing, mad_clip,
+        rank_transform, sigma_clip, winsorize,
+    },
+    traits::DataFrequency,
+};
+use chrono::NaiveDate;
+use polars::prelude::*;
+use std::sync::Arc;
+
+/// A single preprocessing step applied cross-sectionally on each date.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Transform {
+    /// Clip to cross-sectional percentiles (see [`winsorize`])
+    Winsorize {
+        /// Lower percentile, e.g. 0.01
+        lower: f64,
+        /// Upper percentile, e.g. 0.99
+        upper: f64,
+    },
+    /// Clip to `mean ± n_sigma * std` (see [`sigma_clip`])
+    SigmaClip {
+        /// Number of standard deviations to allow
+        n_sigma: f64,
+    },
+    /// Clip to `median ± n_mad * 1.4826 * MAD` (see [`mad_clip`])
+    MadClip {
+        /// Number of scaled MADs to allow
+        n_mad: f64,
+    },
+    /// Replace values with cross-sectional percentile ranks (see [`rank_transform`])
+    Rank,
+    /// Fill missing values (see [`fill_missing`])
+    Fill {
+        /// Fill strategy
+        strategy: FillStrategy,
+    },
+    /// Standardize with the given scheme (see [`Standardization`])
+    Standardize {
+        /// Standardization scheme
+        scheme: Standardization,
+    },
+    /// Alternate z-scoring and clipping at `±n_sigma` until every z-score is
+    /// within the bound, for at most `max_iterations` rounds
+    IterativeStandardize {
+        /// Bound on absolute z-scores
+        n_sigma: f64,
+        /// Maximum number of standardize-and-clip rounds
+        max_iterations: usize,
+    },
+}
+
+impl Transform {
+    /// Input columns needed beyond `symbol`, `date` and the factor values.
+    pub fn required_columns(&self) -> Vec<&str> {
+        match self {
+            Self::Standardize { scheme } => scheme.required_columns(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Apply the transform to `value_column` of `df`.
+    pub fn apply(&self, df: &DataFrame, value_column: &str) -> Result<DataFrame> {
+        match self {
+            Self::Winsorize { lower, upper } => winsorize(df, value_column, *lower, *upper),
+            Self::SigmaClip { n_sigma } => sigma_clip(df, value_column, *n_sigma),
+            Self::MadClip { n_mad } => mad_clip(df, value_column, *n_mad),
+            Self::Rank => rank_transform(df, value_column),
+            Self::Fill { strategy } => fill_missing(df, value_column, *strategy),
+            Self::Standardize { scheme } => scheme.apply(df, value_column),
+            Self::IterativeStandardize {
+                n_sigma,
+                max_iterations,
+            } => iterative_standardize(df, value_column, *n_sigma, *max_iterations),
+        }
+    }
+}
+
+/// Alternate z-scoring and clipping until values fall within `±n_sigma`.
+///
+/// Returns the z-scores as soon as they are all within bounds; if
+/// `max_iterations` rounds are not enough, returns the last clipped scores so
+/// the bound always holds.
+fn iterative_standardize(
+    df: &DataFrame,
+    value_column: &str,
+    n_sigma: f64,
+    max_iterations: usize,
+) -> Result<DataFrame> {
+    let mut result = df.clone();
+
+    for _ in 0..max_iterations.max(1) {
+        let scores = cross_sectional_standardize(&result, value_column)?;
+        let within = scores
+            .column(value_column)?
+            .f64()?
+            .into_iter()
+            .flatten()
+            .all(|z| z.abs() <= n_sigma);
+        if within {
+            return Ok(scores);
+        }
+
+        result = scores
+            .lazy()
+            .with_column(
+                when(col(value_column).gt(lit(n_sigma)))
+                    .then(lit(n_sigma))
+                    .when(col(value_column).lt(lit(-n_sigma)))
+                    .then(lit(-n_sigma))
+                    .otherwise(col(value_column))
+                    .alias(value_column),
+            )
+            .collect()?;
+    }
+
+    Ok(result)
+}
+
+/// Ordered list of transforms applied to raw factor values.
+///
+/// The default pipeline is a single cross-sectional z-score, matching
+/// [`Factor::compute`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use factors::pipeline::{Pipeline, Transform};
+/// use factors::standardize::FillStrategy;
+///
+/// let pipeline = Pipeline::new()
+///     .then(Transform::Winsorize { lower: 0.01, upper: 0.99 })
+///     .then(Transform::Fill { strategy: FillStrategy::Median })
+///     .then(Transform::IterativeStandardize { n_sigma: 3.0, max_iterations: 5 });
+/// ```
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Pipeline {
+    transforms: Vec<Transform>,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::from(Standardization::CrossSectional)
+    }
+}
+
+impl From<Standardization> for Pipeline {
+    fn from(scheme: Standardization) -> Self {
+        Self::new().then(Transform::Standardize { scheme })
+    }
+}
+
+impl Pipeline {
+    /// Create an empty pipeline that leaves values unchanged.
+    pub const fn new() -> Self {
+        Self {
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Append a transform.
+    pub fn then(mut self, transform: Transform) -> Self {
+        self.transforms.push(transform);
+        self
+    }
+
+    /// Transforms in the order they are applied.
+    pub fn transforms(&self) -> &[Transform] {
+        &self.transforms
+    }
+
+    /// Input columns needed by any transform beyond `symbol`, `date` and the
+    /// factor values, without duplicates.
+    pub fn required_columns(&self) -> Vec<&str> {
+        let mut columns: Vec<&str> = Vec::new();
+        for column in self.transforms.iter().flat_map(Transform::required_columns) {
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+        columns
+    }
+
+    /// Apply every transform in order to `value_column` of `df`.
+    ///
+    /// `df` must contain the columns listed by [`Self::required_columns`].
+    pub fn apply(&self, df: &DataFrame, value_column: &str) -> Result<DataFrame> {
+        let mut result = df.clone();
+        for transform in &self.transforms {
+            result = transform.apply(&result, value_column)?;
+        }
+        Ok(result)
+    }
+
+    /// Process raw factor values, taking any extra columns from `data`.
+    ///
+    /// `raw` has columns `symbol`, `date` and `value_column`; `rows` selects the
+    /// rows of `data` that `raw` was computed from. Returns a DataFrame with the
+    /// same three columns, sorted by date and symbol.
+    ///
+    /// Sorting first makes cross-sectional sums run in the same order however
+    /// the raw rows arrived (group-by output order varies between runs), so
+    /// scores are bit-for-bit reproducible.
+    pub(crate) fn process(
+        &self,
+        raw: DataFrame,
+        data: &LazyFrame,
+        rows: Expr,
+        value_column: &str,
+    ) -> Result<DataFrame> {
+        let raw = raw.sort(["date", "symbol"], SortMultipleOptions::default())?;
+        let extra = self.required_columns();
+        if extra.is_empty() {
+            return self.apply(&raw, value_column);
+        }
+
+        let keys = [col("symbol"), col("date")];
+        let inputs = data.clone().filter(rows).select(
+            keys.iter()
+                .cloned()
+                .chain(extra.iter().map(|&c| col(c)))
+                .collect::<Vec<_>>(),
+        );
+        let joined = raw
+            .lazy()
+            .join(inputs, keys.clone(), keys, JoinArgs::new(JoinType::Left))
+            .collect()?;
+
+        let result = self.apply(&joined, value_column)?;
+        Ok(result.select(["symbol", "date", value_column])?)
+    }
+}
+
+/// A factor with a preprocessing pipeline attached.
+///
+/// Created with [`Factor::with_pipeline`]. Raw values come from the wrapped
+/// factor; [`Factor::compute`] and [`Factor::compute_panel`] run the pipeline
+/// instead of the default z-score. Columns needed by the pipeline are not
+/// included in [`Factor::required_columns`]; see [`Pipeline::required_columns`].
+#[derive(Debug, Clone)]
+pub struct PipelineFactor<F> {
+    factor: F,
+    pipeline: Pipeline,
+}
+
+impl<F: Factor> PipelineFactor<F> {
+    /// Attach `pipeline` to `factor`.
+    pub const fn new(factor: F, pipeline: Pipeline) -> Self {
+        Self { factor, pipeline }
+    }
+
+    /// The wrapped factor.
+    pub const fn factor(&self) -> &F {
+        &self.factor
+    }
+
+    /// The attached pipeline.
+    pub const fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+}
+
+impl<F: Factor> Factor for PipelineFactor<F> {
+    fn name(&self) -> &str {
+        self.factor.name()
+    }
+
+    fn description(&self) -> &str {
+        self.factor.description()
+    }
+
+    fn category(&self) -> FactorCategory {
+        self.factor.category()
+    }
+
+    fn required_columns(&self) -> &[&str] {
+        self.factor.required_columns()
+    }
+
+    fn lookback(&self) -> usize {
+        self.factor.lookback()
+    }
+
+    fn frequency(&self) -> DataFrequency {
+        self.factor.frequency()
+    }
+
+    fn features(&self) -> Vec<Feature> {
+        self.factor.features()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.factor.dependencies()
+    }
+
+    fn price_adjustment(&self) -> PriceAdjustment {
+        self.factor.price_adjustment()
+    }
+
+    fn on_calendar(&self, calendar: &TradingCalendar) -> Option<Arc<dyn Factor>> {
+        // Aliased under its own name, the adopted factor is simply forwarded to
+        let factor = AliasedFactor::new(self.name(), self.factor.on_calendar(calendar)?);
+        Some(Arc::new(PipelineFactor::new(factor, self.pipeline.clone())))
+    }
+
+    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        self.factor.compute_raw(data, date)
+    }
+
+    fn attached_pipeline(&self) -> Option<&Pipeline> {
+        Some(&self.pipeline)
+    }
+
+    fn compute(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        self.compute_pipeline(data, date, &self.pipeline)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        self.factor.compute_range(data, start, end)
+    }
+
+    fn compute_panel(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        self.compute_panel_pipeline(data, start, end, &self.pipeline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FactorRegistry, momentum::ShortTermMomentum, value::BookToPrice};
+    use std::sync::Arc;
+
+    fn book_data() -> LazyFrame {
+        df![
+            "symbol" => ["A", "B", "C", "D", "E"],
+            "date" => ["2024-03-31"; 5],
+            "book_equity" => [1.0, 2.0, 3.0, 4.0, 100.0],
+            "market_cap" => [1.0; 5],
+        ]
+        .unwrap()
+        .lazy()
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()
+    }
+
+    fn values(df: &DataFrame, column: &str) -> Vec<f64> {
+        df.column(column)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_default_pipeline_matches_compute() {
+        let factor = BookToPrice::default();
+        let expected = factor.compute(&book_data(), date()).unwrap();
+        let actual = factor
+            .compute_pipeline(&book_data(), date(), &Pipeline::default())
+            .unwrap();
+
+        assert_eq!(
+            values(&actual, "book_to_price"),
+            values(&expected, "book_to_price")
+        );
+    }
+
+    #[test]
+    fn test_iterative_standardize_bounds_outliers() {
+        let pipeline = Pipeline::new().then(Transform::IterativeStandardize {
+            n_sigma: 1.5,
+            max_iterations: 20,
+        });
+        let result = BookToPrice::default()
+            .with_pipeline(pipeline)
+            .compute(&book_data(), date())
+            .unwrap();
+
+        let scores = values(&result, "book_to_price");
+        assert!(scores.iter().all(|z| z.abs() <= 1.5 + 1e-9));
+        // The outlier is pulled in but still ranks highest.
+        assert!(scores[4] > scores[3]);
+        assert!(scores[0] < 0.0);
+    }
+
+    #[test]
+    fn test_pipeline_serde_roundtrip() {
+        let pipeline = Pipeline::new()
+            .then(Transform::Winsorize {
+                lower: 0.01,
+                upper: 0.99,
+            })
+            .then(Transform::MadClip { n_mad: 3.0 })
+            .then(Transform::Fill {
+                strategy: FillStrategy::Median,
+            })
+            .then(Transform::Standardize {
+                scheme: Standardization::CapWeighted {
+                    weight: "market_cap".to_string(),
+                },
+            });
+
+        let json = serde_json::to_string(&pipeline).unwrap();
+        assert!(json.contains(r#""type":"mad_clip""#));
+        let decoded: Pipeline = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, pipeline);
+        assert_eq!(decoded.required_columns(), ["market_cap"]);
+    }
+
+    #[test]
+    fn test_registry_pipeline_applies_to_all_factors() {
+        let mut registry =
+            FactorRegistry::new().with_pipeline(Pipeline::new().then(Transform::Rank));
+        registry.register(std::sync::Arc::new(BookToPrice::default()));
+
+        let result = registry.compute_all(&book_data(), date()).unwrap();
+        let mut ranks = values(&result, "book_to_price");
+        ranks.sort_by(f64::total_cmp);
+        for (actual, expected) in ranks.iter().zip([0.2, 0.4, 0.6, 0.8, 1.0]) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        // A pipeline attached to a factor reports the wrapped factor's identity.
+        let factor = ShortTermMomentum::default().with_pipeline(Pipeline::default());
+        assert_eq!(factor.name(), "short_term_momentum");
+        assert_eq!(factor.pipeline(), &Pipeline::default());
+    }
+
+    #[test]
+    fn test_factor_pipeline_takes_precedence() {
+        let rank = Pipeline::new().then(Transform::Rank);
+        let mut registry = FactorRegistry::new().with_pipeline(rank.clone());
+        registry.register(Arc::new(
+            BookToPrice::default().with_pipeline(Pipeline::new()),
+        ));
+        registry.register(Arc::new(crate::spec::AliasedFactor::new(
+            "book_rank",
+            Arc::new(BookToPrice::default()),
+        )));
+
+        let report = registry
+            .compute_all_with_coverage(&book_data(), date(), crate::JoinMode::Inner)
+            .unwrap();
+        assert_eq!(
+            values(&report.exposures, "book_to_price"),
+            [1.0, 2.0, 3.0, 4.0, 100.0]
+        );
+        let ranks = values(&report.exposures, "book_rank");
+        for (actual, expected) in ranks.iter().zip([0.2, 0.4, 0.6, 0.8, 1.0]) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        let pipelines: Vec<&Pipeline> = report.coverage.iter().map(|c| &c.pipeline).collect();
+        assert_eq!(pipelines, [&rank, &Pipeline::new()]);
+        let frame = report.coverage_frame().unwrap();
+        let recorded: Vec<&str> = frame
+            .column("pipeline")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(recorded, [r#"[{"type":"rank"}]"#, "[]"]);
+    }
+}
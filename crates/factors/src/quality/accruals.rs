@@ -7,13 +7,13 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
 
 /// Configuration for Accruals Quality factor.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct AccrualsQualityConfig {}
 
 /// Accruals Quality factor.
@@ -69,7 +69,7 @@ impl Factor for AccrualsQuality {
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         let result = data
             .clone()
-            .filter(col("date").eq(lit(date.to_string())))
+            .filter(on_date(date))
             .select([
                 col("symbol"),
                 col("date"),
@@ -6,13 +6,14 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    schema::derive_fields,
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
 
 /// Configuration for Altman Z-Score factor.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct AltmanZConfig {}
 
 /// Altman Z-Score factor.
@@ -73,9 +74,8 @@ impl Factor for AltmanZ {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let result = data
-            .clone()
-            .filter(col("date").eq(lit(date.to_string())))
+        let result = derive_fields(data.clone(), self.required_columns())?
+            .filter(on_date(date))
             .select([
                 col("symbol"),
                 col("date"),
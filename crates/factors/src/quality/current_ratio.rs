@@ -6,13 +6,13 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
 
 /// Configuration for Current Ratio factor.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct CurrentRatioConfig {}
 
 /// Current Ratio factor.
@@ -58,7 +58,7 @@ impl Factor for CurrentRatio {
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         let result = data
             .clone()
-            .filter(col("date").eq(lit(date.to_string())))
+            .filter(on_date(date))
             .select([
                 col("symbol"),
                 col("date"),
@@ -6,7 +6,7 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date, on_or_before},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -76,7 +76,7 @@ impl Factor for EarningsPersistence {
 
         let result = data
             .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())))
+            .filter(on_or_before(date))
             .sort(["symbol", "date"], Default::default())
             .group_by([col("symbol")])
             .agg([
@@ -86,7 +86,7 @@ impl Factor for EarningsPersistence {
                 col("eps").mean().alias("eps_mean"),
                 col("eps").std(0).alias("eps_std"),
             ])
-            .filter(col("date").eq(lit(date.to_string())))
+            .filter(on_date(date))
             .select([
                 col("symbol"),
                 col("date"),
@@ -6,7 +6,7 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date, on_or_before},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -74,7 +74,7 @@ impl Factor for EarningsSmoothness {
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         let result = data
             .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())))
+            .filter(on_or_before(date))
             .sort(["symbol", "date"], Default::default())
             .group_by([col("symbol")])
             .agg([
@@ -85,7 +85,7 @@ impl Factor for EarningsSmoothness {
                 // Calculate standard deviation of operating cash flow
                 col("operating_cash_flow").std(0).alias("ocf_std"),
             ])
-            .filter(col("date").eq(lit(date.to_string())))
+            .filter(on_date(date))
             .select([
                 col("symbol"),
                 col("date"),
@@ -6,13 +6,14 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    schema::derive_fields,
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
 
 /// Configuration for Piotroski F-Score factor.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct PiotroskiConfig {}
 
 /// Piotroski F-Score factor.
@@ -35,6 +36,10 @@ pub struct PiotroskiConfig {}
 /// - Increase in asset turnover (1 point)
 ///
 /// Higher scores (7-9) indicate strong fundamentals, while lower scores (0-2) suggest weakness.
+///
+/// Ratios and prior-period values missing from `data` are derived from base
+/// statement lines as in [`crate::schema`]. On daily as-of data, prior periods
+/// need a [`REPORT_DATE`](crate::schema::REPORT_DATE) column.
 #[derive(Debug, Clone, Default)]
 pub struct Piotroski {
     config: PiotroskiConfig,
@@ -83,9 +88,8 @@ impl Factor for Piotroski {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let result = data
-            .clone()
-            .filter(col("date").eq(lit(date.to_string())))
+        let result = derive_fields(data.clone(), self.required_columns())?
+            .filter(on_date(date))
             .select([
                 col("symbol"),
                 col("date"),
@@ -3,11 +3,25 @@
 //! The registry provides a centralized way to discover, instantiate, and
 //! query factors. It supports grouping by category and bulk computation.
 
-use crate::{Factor, Result, traits::DataFrequency};
+use crate::{
+    Factor, FactorError, Result,
+    adjustment::{CorporateActions, PriceAdjustment},
+    calendar::TradingCalendar,
+    coverage::{self, Exposures, JoinMode},
+    features::{self, Feature},
+    mapping::ColumnMapping,
+    pipeline::Pipeline,
+    schema::{derive_fields, derive_shared_fields},
+    spec::{self, FactorSpec, RegistrySpec},
+    standardize::Standardization,
+    traits::{DataFrequency, between_dates, check_date_range, date_column, on_date},
+    validation::{self, FactorValidation},
+};
 use chrono::NaiveDate;
 use derive_more::Display;
 use polars::prelude::*;
-use std::collections::HashMap;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 /// Factor category for grouping related factors.
@@ -40,18 +54,128 @@ pub struct FactorInfo {
     pub description: String,
     /// Factor category
     pub category: FactorCategory,
-    /// Required input columns
-    pub required_columns: Vec<String>,
+    /// Required input columns, with the source columns they are read from
+    pub required_columns: Vec<RequiredColumn>,
     /// Lookback period
     pub lookback: usize,
     /// Data frequency
     pub frequency: DataFrequency,
+    /// Factors whose scores this factor reads
+    pub dependencies: Vec<String>,
+}
+
+/// A factor input column and the input column it is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequiredColumn {
+    /// Canonical column name the factor reads
+    pub name: String,
+    /// Input column mapped to `name`, equal to it when unmapped
+    pub source: String,
+}
+
+impl RequiredColumn {
+    /// Whether the column is read from a differently named input column.
+    pub fn is_mapped(&self) -> bool {
+        self.name != self.source
+    }
+}
+
+impl std::fmt::Display for RequiredColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_mapped() {
+            write!(f, "{} (from {})", self.name, self.source)
+        } else {
+            f.write_str(&self.name)
+        }
+    }
+}
+
+/// Named selection of factors for [`FactorRegistry::with_set`].
+#[derive(
+    Debug, Display, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum FactorSet {
+    /// Barra-style risk model style factors: size, beta, momentum, residual
+    /// volatility, value, earnings yield, growth, leverage, liquidity and
+    /// dividend yield
+    #[display("risk_core")]
+    RiskCore,
+    /// Every built-in factor
+    #[display("alpha_research")]
+    AlphaResearch,
+}
+
+impl FactorSet {
+    /// Every named set.
+    pub const ALL: [Self; 2] = [Self::RiskCore, Self::AlphaResearch];
+
+    /// Names of the factors in the set, or `None` if it includes every
+    /// built-in factor.
+    pub const fn factor_names(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Self::RiskCore => Some(&[
+                "log_market_cap",
+                "market_beta",
+                "long_term_momentum",
+                "idiosyncratic_volatility",
+                "book_to_price",
+                "earnings_yield",
+                "earnings_growth",
+                "sales_growth",
+                "leverage",
+                "turnover_ratio",
+                "dividend_yield",
+            ]),
+            Self::AlphaResearch => None,
+        }
+    }
+}
+
+impl std::str::FromStr for FactorSet {
+    type Err = FactorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|set| set.to_string() == s)
+            .ok_or_else(|| FactorError::NotFound(format!("factor set {s}")))
+    }
+}
+
+/// How [`FactorRegistry`] evaluates its factors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Execution {
+    /// One factor at a time on the calling thread
+    #[default]
+    Sequential,
+    /// Factors concurrently on a thread pool, sharing one collected copy of
+    /// the input
+    Parallel {
+        /// Number of worker threads; `0` uses one per available core
+        threads: usize,
+    },
 }
 
 /// Registry for factor discovery and instantiation.
 #[derive(Debug, Default)]
 pub struct FactorRegistry {
     factors: HashMap<String, Arc<dyn Factor>>,
+    specs: HashMap<String, FactorSpec>,
+    pipeline: Option<Pipeline>,
+    columns: ColumnMapping,
+    actions: Option<CorporateActions>,
+    calendar: Option<TradingCalendar>,
+    execution: Execution,
+    pool: Option<Arc<rayon::ThreadPool>>,
+}
+
+/// Which prepared input a factor reads in [`FactorRegistry::evaluate`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+struct InputKey {
+    adjustment: PriceAdjustment,
+    aligned: bool,
 }
 
 impl FactorRegistry {
@@ -59,62 +183,205 @@ impl FactorRegistry {
     pub fn new() -> Self {
         Self {
             factors: HashMap::new(),
+            specs: HashMap::new(),
+            pipeline: None,
+            columns: ColumnMapping::new(),
+            actions: None,
+            calendar: None,
+            execution: Execution::Sequential,
+            pool: None,
         }
     }
 
-    /// Register all standard factors.
+    /// Register every built-in factor with its default configuration.
     pub fn with_defaults() -> Self {
         let mut registry = Self::new();
+        for factor in spec::builtins() {
+            let name = factor.name().to_string();
+            registry.specs.insert(name.clone(), FactorSpec::new(&name));
+            registry.factors.insert(name, factor);
+        }
+        registry
+    }
 
-        // Momentum factors
-        registry.register(Arc::new(crate::momentum::ShortTermMomentum::default()));
-        registry.register(Arc::new(crate::momentum::MediumTermMomentum::default()));
-        registry.register(Arc::new(crate::momentum::LongTermMomentum::default()));
-
-        // Value factors
-        registry.register(Arc::new(crate::value::BookToPrice::default()));
-        registry.register(Arc::new(crate::value::EarningsYield::default()));
-        registry.register(Arc::new(crate::value::FcfYield::default()));
-
-        // Quality factors
-        registry.register(Arc::new(crate::quality::Roe::default()));
-        registry.register(Arc::new(crate::quality::Roa::default()));
-        registry.register(Arc::new(crate::quality::ProfitMargin::default()));
-        registry.register(Arc::new(crate::quality::Leverage::default()));
-        registry.register(Arc::new(crate::quality::GrossProfitability::default()));
-
-        // Size factors
-        registry.register(Arc::new(crate::size::LogMarketCap::default()));
-
-        // Volatility factors
-        registry.register(Arc::new(crate::volatility::MarketBeta::default()));
-        registry.register(Arc::new(crate::volatility::HistoricalVolatility::default()));
-
-        // Growth factors
-        registry.register(Arc::new(crate::growth::EarningsGrowth::default()));
-        registry.register(Arc::new(crate::growth::SalesGrowth::default()));
-
-        // Liquidity factors
-        registry.register(Arc::new(crate::liquidity::TurnoverRatio::default()));
-        registry.register(Arc::new(crate::liquidity::AmihudIlliquidity::default()));
-        registry.register(Arc::new(crate::liquidity::DollarVolume::default()));
-        registry.register(Arc::new(crate::liquidity::BidAskSpread::default()));
-        registry.register(Arc::new(crate::liquidity::RollMeasure::default()));
-        registry.register(Arc::new(crate::liquidity::CorwinSchultz::default()));
-        registry.register(Arc::new(crate::liquidity::ShortInterestRatio::default()));
-        registry.register(Arc::new(crate::liquidity::DaysToCover::default()));
-        registry.register(Arc::new(crate::liquidity::RelativeVolume::default()));
-        registry.register(Arc::new(crate::liquidity::KyleLambda::default()));
-
-        // Sentiment factors
-        registry.register(Arc::new(crate::sentiment::AnalystRevisions::default()));
+    /// Register the built-in factors in a named set.
+    pub fn with_set(set: FactorSet) -> Self {
+        let registry = Self::with_defaults();
+        match set.factor_names() {
+            Some(names) => registry.filter(|factor| names.contains(&factor.name())),
+            None => registry,
+        }
+    }
 
-        registry
+    /// Keep only factors in one of `categories`.
+    pub fn with_categories(self, categories: &[FactorCategory]) -> Self {
+        self.filter(|factor| categories.contains(&factor.category()))
+    }
+
+    /// Keep only factors computed from data of `frequency`.
+    pub fn with_frequency(self, frequency: DataFrequency) -> Self {
+        self.filter(|factor| factor.frequency() == frequency)
+    }
+
+    /// Keep only factors for which `keep` returns `true`.
+    pub fn filter(mut self, keep: impl Fn(&dyn Factor) -> bool) -> Self {
+        self.factors.retain(|_, factor| keep(factor.as_ref()));
+        self.specs.retain(|name, _| self.factors.contains_key(name));
+        self
+    }
+
+    /// Attach a preprocessing pipeline applied to every factor.
+    ///
+    /// When set, [`FactorRegistry::compute_all`] and
+    /// [`FactorRegistry::compute_all_panel`] run each factor's raw values
+    /// through `pipeline` instead of the default z-score. A pipeline attached
+    /// to a factor itself with [`Factor::with_pipeline`] takes precedence.
+    /// The pipeline each factor went through is reported in
+    /// [`FactorCoverage::pipeline`](crate::FactorCoverage::pipeline).
+    pub fn with_pipeline(mut self, pipeline: Pipeline) -> Self {
+        self.pipeline = Some(pipeline);
+        self
+    }
+
+    /// Attach a column mapping applied to the input of
+    /// [`FactorRegistry::validate`] and the `compute_all*` methods, so vendor
+    /// column names are read as the canonical names factors expect.
+    pub fn with_column_mapping(mut self, columns: ColumnMapping) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// The input column mapping, empty unless one is attached.
+    pub const fn column_mapping(&self) -> &ColumnMapping {
+        &self.columns
+    }
+
+    /// Adjust price and volume inputs for corporate actions before computing.
+    ///
+    /// Each factor receives data adjusted as its
+    /// [`Factor::price_adjustment`] asks, for the events up to the date it is
+    /// computed on (the end date for panels); market capitalization is
+    /// derived from unadjusted prices. See [`crate::adjustment`].
+    pub fn with_corporate_actions(mut self, actions: CorporateActions) -> Self {
+        self.actions = Some(actions);
+        self
+    }
+
+    /// Count daily lookbacks in trading days of `calendar`.
+    ///
+    /// Row-based daily factors see one row per session for each symbol, with
+    /// missing sessions as null rows, so lookbacks and skip periods count
+    /// sessions; input rows on closed days are an error. Factors with
+    /// date-window lookbacks count them on `calendar` via
+    /// [`Factor::on_calendar`] and read the input as given, as do quarterly
+    /// factors. Every input's `date` becomes a `Date`, so results dated by
+    /// `Datetime` input join with aligned ones. See [`TradingCalendar::align`].
+    pub fn with_calendar(mut self, calendar: TradingCalendar) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    /// The trading calendar daily inputs are aligned to, if one is attached.
+    pub const fn calendar(&self) -> Option<&TradingCalendar> {
+        self.calendar.as_ref()
+    }
+
+    /// Set how factors are evaluated by the `compute_all*` methods.
+    ///
+    /// [`Execution::Parallel`] builds the registry's thread pool here, once,
+    /// and fails if the pool cannot be created.
+    pub fn with_execution(mut self, execution: Execution) -> Result<Self> {
+        self.pool = match execution {
+            Execution::Sequential => None,
+            Execution::Parallel { threads } => Some(Arc::new(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .map_err(|e| FactorError::Computation(format!("thread pool: {e}")))?,
+            )),
+        };
+        self.execution = execution;
+        Ok(self)
+    }
+
+    /// How factors are evaluated by the `compute_all*` methods.
+    pub const fn execution(&self) -> Execution {
+        self.execution
+    }
+
+    /// The registry-wide pipeline, if one is attached.
+    pub const fn pipeline(&self) -> Option<&Pipeline> {
+        self.pipeline.as_ref()
     }
 
     /// Register a factor in the registry.
+    ///
+    /// The factor has no spec, so [`FactorRegistry::to_spec`] fails while it
+    /// is registered; use [`FactorRegistry::register_spec`] for factors that
+    /// should be exportable.
     pub fn register(&mut self, factor: Arc<dyn Factor>) {
-        self.factors.insert(factor.name().to_string(), factor);
+        let name = factor.name().to_string();
+        self.specs.remove(&name);
+        self.factors.insert(name, factor);
+    }
+
+    /// Build a built-in factor from a spec and register it.
+    pub fn register_spec(&mut self, spec: FactorSpec) -> Result<()> {
+        let factor = spec.build()?;
+        let name = factor.name().to_string();
+        self.factors.insert(name.clone(), factor);
+        self.specs.insert(name, spec);
+        Ok(())
+    }
+
+    /// Build a registry from a spec.
+    ///
+    /// Registers each listed factor under its alias (or its own name) with the
+    /// given parameter overrides, and attaches the spec's pipeline, column
+    /// mapping and calendar. Fails if two entries resolve to the same name.
+    pub fn from_spec(spec: &RegistrySpec) -> Result<Self> {
+        let mut registry = Self::new();
+        registry.pipeline = spec.pipeline.clone();
+        registry.columns = spec.columns.clone();
+        registry.calendar = spec.calendar.clone();
+
+        for factor in &spec.factors {
+            if registry.factors.contains_key(factor.name()) {
+                return Err(FactorError::InvalidSpec(format!(
+                    "duplicate factor name: {}",
+                    factor.name()
+                )));
+            }
+            registry.register_spec(factor.clone())?;
+        }
+
+        Ok(registry)
+    }
+
+    /// Export the registry as a spec, with factors sorted by name.
+    ///
+    /// Passing the result to [`FactorRegistry::from_spec`] rebuilds an
+    /// equivalent registry. Fails if any factor was added with
+    /// [`FactorRegistry::register`] rather than from a spec, since its
+    /// configuration cannot be recovered.
+    pub fn to_spec(&self) -> Result<RegistrySpec> {
+        let mut names = self.names();
+        names.sort_unstable();
+        let factors = names
+            .into_iter()
+            .map(|name| {
+                self.specs.get(name).cloned().ok_or_else(|| {
+                    FactorError::InvalidSpec(format!("factor {name} was registered without a spec"))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RegistrySpec {
+            pipeline: self.pipeline.clone(),
+            columns: self.columns.clone(),
+            calendar: self.calendar.clone(),
+            factors,
+        })
     }
 
     /// Get a factor by name.
@@ -132,6 +399,9 @@ impl FactorRegistry {
     }
 
     /// Get all factor metadata.
+    ///
+    /// Required columns report the input column each is read from under the
+    /// registry's column mapping.
     pub fn all_info(&self) -> Vec<FactorInfo> {
         self.factors
             .values()
@@ -139,9 +409,17 @@ impl FactorRegistry {
                 name: f.name().to_string(),
                 description: f.description().to_string(),
                 category: f.category(),
-                required_columns: f.required_columns().iter().map(|s| s.to_string()).collect(),
+                required_columns: f
+                    .required_columns()
+                    .iter()
+                    .map(|name| RequiredColumn {
+                        name: name.to_string(),
+                        source: self.columns.source(name).to_string(),
+                    })
+                    .collect(),
                 lookback: f.lookback(),
                 frequency: f.frequency(),
+                dependencies: f.dependencies(),
             })
             .collect()
     }
@@ -151,30 +429,514 @@ impl FactorRegistry {
         self.factors.keys().map(|s| s.as_str()).collect()
     }
 
+    /// Derived inputs read by the registered factors, deduplicated.
+    ///
+    /// The `compute_all*` methods compute these once per run and share them.
+    pub fn features(&self) -> Vec<Feature> {
+        let mut features: Vec<Feature> = self.factors.values().flat_map(|f| f.features()).collect();
+        features.sort();
+        features.dedup();
+        features
+    }
+
+    /// Validate a dataset against every registered factor.
+    ///
+    /// Returns one entry per factor, sorted by name, reporting whether the
+    /// factor can run on `data` and, if not, why. The schema and per-symbol
+    /// history length are computed once and shared across factors. Derivable
+    /// columns count as present, and missing columns are reported with the
+    /// source columns they are mapped from.
+    pub fn validate(&self, data: &LazyFrame) -> Result<Vec<FactorValidation>> {
+        let data = &self.prepare(data, InputKey::default(), None)?;
+        let schema = data.clone().collect_schema()?;
+        let mut history: Option<usize> = None;
+
+        let mut reports = Vec::with_capacity(self.factors.len());
+        for factor in self.sorted() {
+            let result = match validation::validate_schema(factor, &schema) {
+                Ok(()) => {
+                    let available = match history {
+                        Some(available) => available,
+                        None => *history.insert(validation::longest_history(data)?),
+                    };
+                    validation::validate_history(factor, available)
+                }
+                Err(e) => Err(self.columns.describe_error(e)),
+            };
+
+            reports.push(FactorValidation {
+                name: factor.name().to_string(),
+                result,
+            });
+        }
+
+        Ok(reports)
+    }
+
     /// Compute all factors for a given date.
     ///
-    /// Returns a DataFrame with columns: `symbol`, `date`, and one column per factor.
+    /// Returns a DataFrame with columns: `symbol`, `date`, and one column per
+    /// factor in name order. Factors are inner-joined, so only rows every
+    /// factor produced are kept; see
+    /// [`FactorRegistry::compute_all_with_coverage`] for other join modes.
     pub fn compute_all(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let mut result: Option<DataFrame> = None;
+        let results = self.evaluate(
+            data,
+            date,
+            |factor, data| self.compute_one(factor, data, date),
+            |factor, data| factor.compute(data, date),
+        )?;
+        coverage::join_exposures(results.into_iter().map(|(_, result)| result))
+    }
+
+    /// Compute all factors for a given date with per-factor standardization.
+    ///
+    /// `schemes` maps factor names to the [`Standardization`] to apply; factors
+    /// not listed use [`Standardization::CrossSectional`], matching
+    /// [`FactorRegistry::compute_all`].
+    pub fn compute_all_with(
+        &self,
+        data: &LazyFrame,
+        date: NaiveDate,
+        schemes: &HashMap<String, Standardization>,
+    ) -> Result<DataFrame> {
+        let default = Standardization::default();
+        let results = self.evaluate(
+            data,
+            date,
+            |factor, data| {
+                let scheme = schemes.get(factor.name()).unwrap_or(&default);
+                factor.compute_with(data, date, scheme)
+            },
+            |factor, data| factor.compute(data, date),
+        )?;
+        coverage::join_exposures(results.into_iter().map(|(_, result)| result))
+    }
+
+    /// Compute all factors for a given date, joined with `mode`, and report
+    /// per-factor coverage.
+    ///
+    /// Unlike [`FactorRegistry::compute_all`], a factor that fails is left out
+    /// of the exposures and its error recorded in the coverage report.
+    pub fn compute_all_with_coverage(
+        &self,
+        data: &LazyFrame,
+        date: NaiveDate,
+        mode: JoinMode,
+    ) -> Result<Exposures> {
+        let results = self.evaluate(
+            data,
+            date,
+            |factor, data| self.compute_one(factor, data, date),
+            |factor, data| factor.compute(data, date),
+        )?;
+        coverage::collect_exposures(
+            self.with_pipelines(results),
+            mode,
+            self.universe(data, on_date(date))?,
+        )
+    }
+
+    /// Compute all factors for every date in `[start, end]`.
+    ///
+    /// Panel counterpart of [`FactorRegistry::compute_all`]. Returns a long
+    /// DataFrame with columns: `symbol`, `date`, and one column per factor.
+    pub fn compute_all_panel(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        let results = self.evaluate(
+            data,
+            end,
+            |factor, data| self.compute_panel_one(factor, data, start, end),
+            |factor, data| factor.compute_panel(data, start, end),
+        )?;
+        coverage::join_exposures(results.into_iter().map(|(_, result)| result))
+    }
+
+    /// Panel counterpart of [`FactorRegistry::compute_all_with_coverage`].
+    pub fn compute_all_panel_with_coverage(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+        mode: JoinMode,
+    ) -> Result<Exposures> {
+        check_date_range(start, end)?;
+        let results = self.evaluate(
+            data,
+            end,
+            |factor, data| self.compute_panel_one(factor, data, start, end),
+            |factor, data| factor.compute_panel(data, start, end),
+        )?;
+        coverage::collect_exposures(
+            self.with_pipelines(results),
+            mode,
+            self.universe(data, between_dates(start, end))?,
+        )
+    }
+
+    /// Run `compute` for every factor, returning results in name order.
+    ///
+    /// `data` is first mapped, adjusted as of `as_of`, aligned and given any
+    /// derivable columns the factors need, once for each distinct input in
+    /// use. Shared
+    /// [`Feature`]s are then computed once per input, skipping any whose
+    /// inputs `data` lacks so the factors needing them fail on their own.
+    /// Factors then run in dependency order (see [`FactorRegistry::schedule`]),
+    /// each seeing its registered dependencies' scores as extra columns.
+    /// Those are the dependencies' own scores from `own` (e.g.
+    /// [`Factor::compute`]), not the registry's, so a factor reads the same
+    /// values as when it computes its dependencies itself. Under
+    /// [`Execution::Parallel`] each prepared input is collected once and the
+    /// factors of each stage run concurrently on the registry's thread pool.
+    fn evaluate<F, G>(
+        &self,
+        data: &LazyFrame,
+        as_of: NaiveDate,
+        compute: F,
+        own: G,
+    ) -> Result<Vec<(String, Result<DataFrame>)>>
+    where
+        F: Fn(&dyn Factor, &LazyFrame) -> Result<DataFrame> + Sync,
+        G: Fn(&dyn Factor, &LazyFrame) -> Result<DataFrame> + Sync,
+    {
+        let stages = self.schedule()?;
+        let factors: HashMap<&str, Arc<dyn Factor>> = self
+            .factors
+            .iter()
+            .map(|(name, factor)| (name.as_str(), self.on_calendar(factor)))
+            .collect();
+        let depended_on: Vec<String> = self
+            .factors
+            .values()
+            .flat_map(|factor| self.registered_dependencies(factor.as_ref()))
+            .collect();
 
+        let mut inputs: HashMap<InputKey, LazyFrame> = HashMap::new();
         for factor in self.factors.values() {
-            let factor_df = factor.compute(data, date)?;
-
-            result = Some(match result {
-                Some(df) => df
-                    .lazy()
-                    .join(
-                        factor_df.lazy(),
-                        [col("symbol"), col("date")],
-                        [col("symbol"), col("date")],
-                        JoinArgs::new(JoinType::Inner),
-                    )
-                    .collect()?,
-                None => factor_df,
-            });
+            let key = self.input_key(factor.as_ref());
+            if let std::collections::hash_map::Entry::Vacant(entry) = inputs.entry(key) {
+                entry.insert(self.shared_input(data, key, as_of)?);
+            }
+        }
+
+        let mut results: BTreeMap<String, Result<DataFrame>> = BTreeMap::new();
+        let mut scores: BTreeMap<String, Result<DataFrame>> = BTreeMap::new();
+        for stage in stages {
+            let factors: Vec<&dyn Factor> = stage
+                .iter()
+                .map(|name| factors[name.as_str()].as_ref())
+                .collect();
+            let run = |factor: &dyn Factor| {
+                let name = factor.name().to_string();
+                let needed = depended_on.contains(&name);
+                match self.with_dependencies(factor, &inputs[&self.input_key(factor)], &scores) {
+                    Ok(input) => {
+                        let own = needed.then(|| own(factor, &input));
+                        (name, compute(factor, &input), own)
+                    }
+                    Err(e) => {
+                        let own = needed.then(|| Err(FactorError::Computation(e.to_string())));
+                        (name, Err(e), own)
+                    }
+                }
+            };
+            let outputs: Vec<_> = match &self.pool {
+                None => factors.into_iter().map(run).collect(),
+                Some(pool) => pool.install(|| factors.into_par_iter().map(run).collect()),
+            };
+            for (name, result, own) in outputs {
+                if let Some(own) = own {
+                    scores.insert(name.clone(), own);
+                }
+                results.insert(name, result);
+            }
+        }
+        Ok(results.into_iter().collect())
+    }
+
+    /// `data` mapped, adjusted and aligned as `key` asks, with every column
+    /// the factors, their shared features and the pipeline read derived where
+    /// it is missing (see [`crate::schema`]). Previous-period values that
+    /// cannot be derived from `data`'s rows are left to the factors reading
+    /// them, which report why.
+    ///
+    /// Corporate actions are applied as of `as_of` when given, and otherwise
+    /// as of each symbol's latest row. Market capitalization is derived before
+    /// adjusting, so it reflects reported prices.
+    fn prepare(
+        &self,
+        data: &LazyFrame,
+        key: InputKey,
+        as_of: Option<NaiveDate>,
+    ) -> Result<LazyFrame> {
+        let features = self.features();
+        let mut columns: Vec<&str> = self
+            .factors
+            .values()
+            .flat_map(|factor| factor.required_columns().iter().copied())
+            .chain(
+                features
+                    .iter()
+                    .flat_map(|f| f.required_columns().iter().copied()),
+            )
+            .chain(self.pipeline.iter().flat_map(Pipeline::required_columns))
+            .chain(
+                self.factors
+                    .values()
+                    .filter_map(|factor| factor.attached_pipeline())
+                    .flat_map(Pipeline::required_columns),
+            )
+            .collect();
+        columns.sort_unstable();
+        columns.dedup();
+
+        let mapped = self.mapped(data)?;
+        let adjusted = match &self.actions {
+            Some(actions) => {
+                let reported = derive_fields(mapped, &["market_cap"])?;
+                match as_of {
+                    Some(date) => actions.adjust_as_of(&reported, key.adjustment, date)?,
+                    None => actions.adjust(&reported, key.adjustment)?,
+                }
+            }
+            None => mapped,
+        };
+        let aligned = match &self.calendar {
+            Some(calendar) if key.aligned => calendar.align(&adjusted)?,
+            _ => adjusted,
+        };
+        derive_shared_fields(aligned, &columns)
+    }
+
+    /// Prepared input with the registry's shared [`Feature`]s added.
+    ///
+    /// Collected when features were added or factors run in parallel, so the
+    /// work is done once rather than by every factor.
+    fn shared_input(&self, data: &LazyFrame, key: InputKey, as_of: NaiveDate) -> Result<LazyFrame> {
+        let data = self.prepare(data, key, Some(as_of))?;
+        let schema = data.clone().collect_schema()?;
+        let shared: Vec<Feature> = self
+            .features()
+            .into_iter()
+            .filter(|f| f.required_columns().iter().all(|c| schema.contains(c)))
+            .collect();
+        Ok(match (shared.is_empty(), self.execution) {
+            (true, Execution::Sequential) => data,
+            (true, Execution::Parallel { .. }) => data.collect()?.lazy(),
+            (false, _) => features::with_features(data, &shared)?.collect()?.lazy(),
+        })
+    }
+
+    /// Input `factor` is computed on: adjusted as it prefers when corporate
+    /// actions are set, and calendar-aligned when a calendar is set and it is
+    /// daily and counts rows. Otherwise every factor shares the default input.
+    fn input_key(&self, factor: &dyn Factor) -> InputKey {
+        InputKey {
+            adjustment: match self.actions {
+                Some(_) => factor.price_adjustment(),
+                None => PriceAdjustment::default(),
+            },
+            aligned: self.calendar.as_ref().is_some_and(|calendar| {
+                factor.frequency() == DataFrequency::Daily && factor.on_calendar(calendar).is_none()
+            }),
         }
+    }
+
+    /// `factor` as computed: counting its date windows on the registry's
+    /// calendar when it has both (see [`Factor::on_calendar`]).
+    fn on_calendar(&self, factor: &Arc<dyn Factor>) -> Arc<dyn Factor> {
+        self.calendar
+            .as_ref()
+            .and_then(|calendar| factor.on_calendar(calendar))
+            .unwrap_or_else(|| Arc::clone(factor))
+    }
+
+    /// `data` with the `scores` of `factor`'s registered dependencies joined
+    /// on as columns named after them, replacing any input columns of that
+    /// name.
+    fn with_dependencies(
+        &self,
+        factor: &dyn Factor,
+        data: &LazyFrame,
+        scores: &BTreeMap<String, Result<DataFrame>>,
+    ) -> Result<LazyFrame> {
+        let dependencies = self.registered_dependencies(factor);
+        if dependencies.is_empty() {
+            return Ok(data.clone());
+        }
+
+        let keys = [col("symbol"), col("date")];
+        let mut input = data.clone().select([all().exclude(&dependencies)]);
+        for name in &dependencies {
+            let scores = match &scores[name] {
+                Ok(scores) => scores.clone().lazy(),
+                Err(e) => {
+                    return Err(FactorError::Computation(format!(
+                        "Dependency '{name}' failed: {e}"
+                    )));
+                }
+            };
+            input = input.join(
+                scores.select([col("symbol"), col("date"), col(name.as_str())]),
+                keys.clone(),
+                keys.clone(),
+                JoinArgs::new(JoinType::Left),
+            );
+        }
+        Ok(input)
+    }
+
+    /// `factor`'s dependencies that are registered, and so computed for it.
+    fn registered_dependencies(&self, factor: &dyn Factor) -> Vec<String> {
+        factor
+            .dependencies()
+            .into_iter()
+            .filter(|name| self.factors.contains_key(name))
+            .collect()
+    }
+
+    /// Registered factor names grouped into stages that can run in order.
+    ///
+    /// Every factor comes after the registered factors it depends on, and
+    /// factors within a stage are independent of each other and sorted by
+    /// name. Dependencies that are not registered are left to the factor.
+    /// Returns [`FactorError::DependencyCycle`] if the dependencies loop.
+    pub fn schedule(&self) -> Result<Vec<Vec<String>>> {
+        let mut pending: BTreeMap<&str, Vec<String>> = self
+            .factors
+            .iter()
+            .map(|(name, factor)| (name.as_str(), self.registered_dependencies(factor.as_ref())))
+            .collect();
+
+        let mut stages = Vec::new();
+        while !pending.is_empty() {
+            let ready: Vec<&str> = pending
+                .iter()
+                .filter(|(_, dependencies)| {
+                    dependencies
+                        .iter()
+                        .all(|d| !pending.contains_key(d.as_str()))
+                })
+                .map(|(name, _)| *name)
+                .collect();
+            if ready.is_empty() {
+                return Err(FactorError::DependencyCycle(Self::find_cycle(&pending)));
+            }
+            for name in &ready {
+                pending.remove(name);
+            }
+            stages.push(ready.into_iter().map(String::from).collect());
+        }
+        Ok(stages)
+    }
+
+    /// A cycle among `pending` factors, none of which can be scheduled.
+    fn find_cycle(pending: &BTreeMap<&str, Vec<String>>) -> Vec<String> {
+        // Every pending factor waits on another pending factor, so walking
+        // those edges from any of them must revisit a factor
+        let mut path: Vec<&str> = Vec::new();
+        let mut current = *pending.keys().next().expect("pending is non-empty");
+        while !path.contains(&current) {
+            path.push(current);
+            current = pending[current]
+                .iter()
+                .find(|d| pending.contains_key(d.as_str()))
+                .expect("pending factors wait on a pending dependency");
+        }
+        let start = path.iter().position(|name| *name == current).unwrap_or(0);
+        let mut cycle: Vec<String> = path[start..].iter().map(|s| s.to_string()).collect();
+        cycle.push(current.to_string());
+        cycle
+    }
 
-        result.ok_or_else(|| crate::FactorError::Computation("No factors registered".to_string()))
+    /// Registered factors in name order.
+    fn sorted(&self) -> impl Iterator<Item = &dyn Factor> {
+        let mut factors: Vec<&dyn Factor> = self.factors.values().map(|f| f.as_ref()).collect();
+        factors.sort_by(|a, b| a.name().cmp(b.name()));
+        factors.into_iter()
+    }
+
+    /// Pipeline `factor`'s raw values go through: its own if attached, else
+    /// the registry's, else `None` for the default z-score.
+    fn pipeline_for<'a>(&'a self, factor: &'a dyn Factor) -> Option<&'a Pipeline> {
+        factor.attached_pipeline().or(self.pipeline.as_ref())
+    }
+
+    /// Pipeline reported for `factor`'s scores, the default z-score when none
+    /// is set.
+    fn applied_pipeline(&self, name: &str) -> Pipeline {
+        self.factors
+            .get(name)
+            .and_then(|factor| self.pipeline_for(factor.as_ref()).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Scores for one factor on `date`, using the pipeline from
+    /// [`FactorRegistry::pipeline_for`].
+    fn compute_one(
+        &self,
+        factor: &dyn Factor,
+        data: &LazyFrame,
+        date: NaiveDate,
+    ) -> Result<DataFrame> {
+        self.pipeline_for(factor).map_or_else(
+            || factor.compute(data, date),
+            |pipeline| factor.compute_pipeline(data, date, pipeline),
+        )
+    }
+
+    /// Scores for one factor over `[start, end]`, using the pipeline from
+    /// [`FactorRegistry::pipeline_for`].
+    fn compute_panel_one(
+        &self,
+        factor: &dyn Factor,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        self.pipeline_for(factor).map_or_else(
+            || factor.compute_panel(data, start, end),
+            |pipeline| factor.compute_panel_pipeline(data, start, end, pipeline),
+        )
+    }
+
+    /// `results` with the pipeline each factor's scores went through.
+    fn with_pipelines(
+        &self,
+        results: Vec<(String, Result<DataFrame>)>,
+    ) -> Vec<(String, Pipeline, Result<DataFrame>)> {
+        results
+            .into_iter()
+            .map(|(name, result)| {
+                let pipeline = self.applied_pipeline(&name);
+                (name, pipeline, result)
+            })
+            .collect()
+    }
+
+    /// `data` under the column mapping. With a calendar, `date` is converted
+    /// to a `Date` as [`TradingCalendar::align`] does, so aligned and
+    /// unaligned results join on the same key type.
+    fn mapped(&self, data: &LazyFrame) -> Result<LazyFrame> {
+        let mapped = self.columns.apply(data.clone())?;
+        Ok(match self.calendar {
+            Some(_) => mapped.with_column(date_column()),
+            None => mapped,
+        })
+    }
+
+    /// Distinct `symbol`/`date` rows of the mapped `data` selected by `rows`.
+    fn universe(&self, data: &LazyFrame, rows: Expr) -> Result<LazyFrame> {
+        Ok(self
+            .mapped(data)?
+            .filter(rows)
+            .select([col("symbol"), col("date")])
+            .unique(None, UniqueKeepStrategy::Any)
+            .sort(["symbol", "date"], Default::default()))
     }
 
     /// Number of registered factors.
@@ -187,3 +949,467 @@ impl FactorRegistry {
         self.factors.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ConfigurableFactor,
+        momentum::{ShortTermMomentum, ShortTermMomentumConfig},
+    };
+
+    #[test]
+    fn test_with_defaults_registers_every_builtin() {
+        let registry = FactorRegistry::with_defaults();
+        assert_eq!(registry.len(), spec::builtins().len());
+        for name in [
+            "residual_momentum",
+            "rsi",
+            "piotroski_f_score",
+            "altman_z_score",
+            "market_cap",
+        ] {
+            assert!(registry.get(name).is_some(), "{name} not registered");
+        }
+    }
+
+    #[test]
+    fn test_with_set() {
+        let risk = FactorRegistry::with_set(FactorSet::RiskCore);
+        let names = FactorSet::RiskCore.factor_names().unwrap();
+        assert_eq!(risk.len(), names.len());
+        assert!(names.iter().all(|name| risk.get(name).is_some()));
+
+        let alpha = FactorRegistry::with_set(FactorSet::AlphaResearch);
+        assert_eq!(alpha.len(), FactorRegistry::with_defaults().len());
+
+        for set in FactorSet::ALL {
+            assert_eq!(set.to_string().parse::<FactorSet>().unwrap(), set);
+        }
+        assert!("no_such_set".parse::<FactorSet>().is_err());
+    }
+
+    #[test]
+    fn test_category_and_frequency_filters() {
+        let value = FactorRegistry::with_defaults().with_categories(&[FactorCategory::Value]);
+        assert!(!value.is_empty());
+        assert!(
+            value
+                .all_info()
+                .iter()
+                .all(|info| info.category == FactorCategory::Value)
+        );
+
+        let daily = FactorRegistry::with_defaults().with_frequency(DataFrequency::Daily);
+        assert!(
+            daily
+                .all_info()
+                .iter()
+                .all(|info| info.frequency == DataFrequency::Daily)
+        );
+        assert_eq!(daily.to_spec().unwrap().factors.len(), daily.len());
+    }
+
+    #[test]
+    fn test_compute_all_with_coverage() {
+        let data = df![
+            "symbol" => ["A", "B", "C"],
+            "date" => ["2024-03-31"; 3],
+            "book_equity" => [1.0, 2.0, 3.0],
+            "net_income" => [Some(1.0), None, Some(2.0)],
+            "market_cap" => [10.0, 10.0, 10.0],
+        ]
+        .unwrap()
+        .lazy();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let registry = FactorRegistry::with_defaults().with_categories(&[FactorCategory::Value]);
+
+        let report = registry
+            .compute_all_with_coverage(&data, date, JoinMode::Universe)
+            .unwrap();
+        let columns = report.exposures.get_column_names();
+        assert_eq!(
+            columns,
+            ["symbol", "date", "book_to_price", "earnings_yield"]
+        );
+        assert_eq!(report.exposures.height(), 3);
+
+        let names: Vec<&str> = report.coverage.iter().map(|c| c.name.as_str()).collect();
+        let mut sorted = names.clone();
+        sorted.sort_unstable();
+        assert_eq!(names, sorted);
+
+        let earnings = report
+            .coverage
+            .iter()
+            .find(|c| c.name == "earnings_yield")
+            .unwrap();
+        assert_eq!(earnings.null_count, 1);
+        let dividend = report
+            .coverage
+            .iter()
+            .find(|c| c.name == "dividend_yield")
+            .unwrap();
+        assert!(dividend.dropped.is_some());
+    }
+
+    #[test]
+    fn test_column_mapping() {
+        let data = df![
+            "ticker" => ["A", "B", "C"],
+            "date" => ["2024-03-31"; 3],
+            "total_equity" => [1.0, 2.0, 3.0],
+            "px" => [5.0, 5.0, 10.0],
+            "shares_outstanding" => [2.0, 2.0, 2.0],
+        ]
+        .unwrap()
+        .lazy();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let columns = ColumnMapping::new()
+            .with_alias("symbol", "ticker")
+            .with_alias("book_equity", "total_equity")
+            .with_alias("close", "px")
+            .with_alias("net_income", "ni");
+        let registry = FactorRegistry::with_defaults()
+            .filter(|f| ["book_to_price", "earnings_yield"].contains(&f.name()))
+            .with_pipeline(Pipeline::new())
+            .with_column_mapping(columns.clone());
+
+        let info = registry.all_info();
+        let book = info.iter().find(|i| i.name == "book_to_price").unwrap();
+        let sources: Vec<String> = book
+            .required_columns
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert!(sources.contains(&"book_equity (from total_equity)".to_string()));
+        assert!(sources.contains(&"date".to_string()));
+
+        let validation = registry.validate(&data).unwrap();
+        assert!(validation[0].is_ok());
+        let earnings = validation[1].result.as_ref().unwrap_err().to_string();
+        assert!(earnings.contains("net_income (from ni)"), "{earnings}");
+
+        let exposures = registry
+            .filter(|f| f.name() == "book_to_price")
+            .compute_all(&data, date)
+            .unwrap();
+        let scores: Vec<f64> = exposures
+            .column("book_to_price")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(scores, [0.1, 0.2, 0.15]);
+
+        let spec = FactorRegistry::with_defaults()
+            .with_column_mapping(columns.clone())
+            .to_spec()
+            .unwrap();
+        let rebuilt =
+            FactorRegistry::from_spec(&RegistrySpec::from_toml(&spec.to_toml().unwrap()).unwrap());
+        assert_eq!(rebuilt.unwrap().column_mapping(), &columns);
+    }
+
+    #[test]
+    fn test_calendar_aligns_daily_lookbacks() {
+        use crate::momentum::{MediumTermMomentum, MediumTermMomentumConfig};
+
+        // 2024-01-12 is missing and 2024-01-15 (MLK Day) closed
+        let data = df![
+            "symbol" => ["A"; 3],
+            "date" => ["2024-01-10", "2024-01-11", "2024-01-16"],
+            "close" => [1.0, 2.0, 8.0],
+        ]
+        .unwrap()
+        .lazy();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 16).unwrap();
+        let short = ShortTermMomentum::with_config(ShortTermMomentumConfig {
+            lookback: 2,
+            skip_days: 0,
+        });
+        // Shifts rows rather than slicing them
+        let medium = MediumTermMomentum::with_config(MediumTermMomentumConfig {
+            lookback: 2,
+            skip_days: 0,
+        });
+        let scores = |registry: &FactorRegistry| {
+            let exposures = registry.compute_all(&data, date).unwrap();
+            [short.name(), medium.name()]
+                .map(|name| exposures.column(name).unwrap().f64().unwrap().get(0))
+        };
+
+        let mut rows = FactorRegistry::new().with_pipeline(Pipeline::new());
+        rows.register(Arc::new(short.clone()));
+        rows.register(Arc::new(medium.clone()));
+        assert_eq!(scores(&rows), [Some(7.0); 2]);
+
+        let mut sessions = FactorRegistry::new()
+            .with_pipeline(Pipeline::new())
+            .with_calendar(TradingCalendar::nyse());
+        for name in [short.name(), medium.name()] {
+            sessions
+                .register_spec(
+                    FactorSpec::new(name)
+                        .with_params(serde_json::json!({ "lookback": 2, "skip_days": 0 })),
+                )
+                .unwrap();
+        }
+        assert_eq!(sessions.calendar(), Some(&TradingCalendar::nyse()));
+        // The missing session counts as one of the two
+        assert_eq!(scores(&sessions), [Some(3.0); 2]);
+
+        let saturday = df!["symbol" => ["A"], "date" => ["2024-01-13"], "close" => [4.0]]
+            .unwrap()
+            .lazy();
+        let stray = concat([data.clone(), saturday], Default::default()).unwrap();
+        assert!(sessions.compute_all(&stray, date).is_err());
+
+        let rebuilt = FactorRegistry::from_spec(
+            &RegistrySpec::from_toml(&sessions.to_spec().unwrap().to_toml().unwrap()).unwrap(),
+        );
+        assert_eq!(rebuilt.unwrap().calendar(), Some(&TradingCalendar::nyse()));
+
+        // Datetime dates join aligned and unaligned inputs, and the universe
+        let stamped = data.clone().with_columns([
+            col("date")
+                .strict_cast(DataType::Date)
+                .cast(DataType::Datetime(TimeUnit::Milliseconds, None)),
+            lit(4.0).alias("book_equity"),
+            lit(2.0).alias("market_cap"),
+        ]);
+        sessions.register(Arc::new(crate::value::BookToPrice::default()));
+        for mode in [JoinMode::Inner, JoinMode::Universe] {
+            let exposures = sessions
+                .compute_all_with_coverage(&stamped, date, mode)
+                .unwrap()
+                .exposures;
+            let score = |name| exposures.column(name).unwrap().f64().unwrap().get(0);
+            assert_eq!(score(short.name()), Some(3.0));
+            assert_eq!(score("book_to_price"), Some(2.0));
+        }
+    }
+
+    #[test]
+    fn test_calendar_counts_date_windows() {
+        use crate::sentiment::InsiderTrading;
+
+        // A buy 60 sessions (86 calendar days) before a Saturday sell
+        let data = df![
+            "symbol" => ["A", "A"],
+            "date" => ["2024-01-02", "2024-03-23"],
+            "insider_buys" => [1.0, 0.0],
+            "insider_sells" => [0.0, 1.0],
+        ]
+        .unwrap()
+        .lazy();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 28).unwrap();
+        let score = |registry: FactorRegistry| {
+            let mut registry = registry.with_pipeline(Pipeline::new());
+            registry.register(Arc::new(InsiderTrading::default()));
+            let exposures = registry.compute_all(&data, date).unwrap();
+            exposures
+                .column("insider_net_buying")
+                .unwrap()
+                .f64()
+                .unwrap()
+                .get(0)
+        };
+
+        // 63 calendar days by default, as standalone
+        assert_eq!(score(FactorRegistry::new()), Some(-1.0));
+        // 63 sessions of the registry's calendar, with the weekend filing kept
+        let nyse = FactorRegistry::new().with_calendar(TradingCalendar::nyse());
+        assert_eq!(score(nyse), Some(0.0));
+    }
+
+    #[test]
+    fn test_shared_features() {
+        use crate::volatility::{HistoricalVolatility, MarketBeta};
+
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut symbols = Vec::new();
+        let mut dates = Vec::new();
+        let mut closes = Vec::new();
+        // Rows arrive unsorted; shared features sort them once
+        for i in (0..60).rev() {
+            for (s, symbol) in ["A", "B", "C"].into_iter().enumerate() {
+                symbols.push(symbol);
+                dates.push(start + chrono::Duration::days(i));
+                closes.push(100.0 + ((i as usize * (s + 2)) % 9) as f64);
+            }
+        }
+        let data = df![
+            "symbol" => symbols,
+            "date" => dates,
+            "close" => closes,
+        ]
+        .unwrap()
+        .lazy();
+        let date = start + chrono::Duration::days(59);
+
+        let volatility = HistoricalVolatility::with_lookback(20);
+        let mut registry = FactorRegistry::new();
+        registry.register(Arc::new(volatility.clone()));
+        registry.register(Arc::new(MarketBeta::with_lookback(20)));
+        assert_eq!(
+            registry.features(),
+            [
+                Feature::DailyReturn,
+                Feature::MarketModel {
+                    window: 20,
+                    min_periods: 20
+                }
+            ]
+        );
+
+        // Without `market_return` only the market model is skipped
+        let report = registry
+            .compute_all_with_coverage(&data, date, JoinMode::Outer)
+            .unwrap();
+        assert!(report.coverage[1].dropped.is_some());
+
+        let standalone = volatility.compute(&data, date).unwrap();
+        let shared = report.exposures.column("historical_volatility").unwrap();
+        let standalone = standalone.column("historical_volatility").unwrap();
+        for (a, e) in shared
+            .f64()
+            .unwrap()
+            .into_iter()
+            .zip(standalone.f64().unwrap())
+        {
+            assert!((a.unwrap() - e.unwrap()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_dependencies_run_first() {
+        use crate::{
+            CompositeFactor,
+            value::{BookToPrice, EarningsYield},
+        };
+
+        let data = df![
+            "symbol" => ["A", "B", "C"],
+            "date" => ["2024-03-31"; 3],
+            "book_equity" => [1.0, 2.0, 4.0],
+            "net_income" => [3.0, 1.0, 2.0],
+            "market_cap" => [10.0, 10.0, 10.0],
+        ]
+        .unwrap()
+        .lazy();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let composite = CompositeFactor::new(
+            "value_composite",
+            FactorCategory::Value,
+            vec![
+                Arc::new(BookToPrice::default()),
+                Arc::new(EarningsYield::default()),
+            ],
+        );
+
+        let mut registry = FactorRegistry::new();
+        registry.register(Arc::new(composite.clone()));
+        registry.register(Arc::new(BookToPrice::default()));
+        registry.register(Arc::new(EarningsYield::default()));
+        assert_eq!(
+            registry.schedule().unwrap(),
+            [
+                vec!["book_to_price", "earnings_yield"],
+                vec!["value_composite"]
+            ]
+        );
+
+        let exposures = registry.compute_all(&data, date).unwrap();
+        let standalone = composite.compute(&data, date).unwrap();
+        assert!(
+            exposures
+                .select(["symbol", "value_composite"])
+                .unwrap()
+                .equals(&standalone.select(["symbol", "value_composite"]).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_dependency_cycle() {
+        use crate::{
+            CompositeFactor,
+            value::{BookToPrice, EarningsYield},
+        };
+
+        // Each composite takes the other's name, so they wait on each other
+        let mut registry = FactorRegistry::new();
+        registry.register(Arc::new(CompositeFactor::new(
+            "book_to_price",
+            FactorCategory::Value,
+            vec![Arc::new(EarningsYield::default())],
+        )));
+        registry.register(Arc::new(CompositeFactor::new(
+            "earnings_yield",
+            FactorCategory::Value,
+            vec![Arc::new(BookToPrice::default())],
+        )));
+        registry.register(Arc::new(crate::size::MarketCap::default()));
+
+        let err = registry.schedule().unwrap_err();
+        let FactorError::DependencyCycle(cycle) = &err else {
+            panic!("expected a dependency cycle, got {err}");
+        };
+        assert_eq!(cycle, &["book_to_price", "earnings_yield", "book_to_price"]);
+        assert!(err.to_string().contains("book_to_price -> earnings_yield"));
+
+        let data = df!["symbol" => ["A"], "date" => ["2024-03-31"]]
+            .unwrap()
+            .lazy();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        assert!(matches!(
+            registry.compute_all(&data, date),
+            Err(FactorError::DependencyCycle(_))
+        ));
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let n = 300;
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+        let mut symbols = Vec::new();
+        let mut dates = Vec::new();
+        let mut closes = Vec::new();
+        let mut volumes = Vec::new();
+        for (s, symbol) in ["A", "B", "C", "D"].into_iter().enumerate() {
+            for i in 0..n {
+                symbols.push(symbol);
+                dates.push(start + chrono::Duration::days(i as i64));
+                closes.push(100.0 + (s + 1) as f64 * i as f64 * 0.1 + ((i * (s + 3)) % 7) as f64);
+                volumes.push(1_000.0 + ((i * (s + 1)) % 11) as f64 * 100.0);
+            }
+        }
+        let data = df![
+            "symbol" => symbols,
+            "date" => dates,
+            "close" => closes,
+            "volume" => volumes,
+        ]
+        .unwrap()
+        .lazy();
+        let date = start + chrono::Duration::days(n as i64 - 1);
+
+        let sequential =
+            FactorRegistry::with_defaults().with_categories(&[FactorCategory::Momentum]);
+        let parallel = FactorRegistry::with_defaults()
+            .with_categories(&[FactorCategory::Momentum])
+            .with_execution(Execution::Parallel { threads: 4 })
+            .unwrap();
+
+        let expected = sequential
+            .compute_all_with_coverage(&data, date, JoinMode::Outer)
+            .unwrap();
+        let actual = parallel
+            .compute_all_with_coverage(&data, date, JoinMode::Outer)
+            .unwrap();
+        assert!(expected.exposures.width() > 3);
+        assert_eq!(actual.exposures, expected.exposures);
+        assert_eq!(actual.coverage, expected.coverage);
+    }
+}
@@ -0,0 +1,599 @@
+//! Cross-sectional regression of returns on factor exposures.
+//!
+//! [`cross_sectional_regression`] estimates factor returns one date at a time,
+//! the way a fundamental risk model does: each stock's forward return is
+//! regressed on a market intercept, optional industry dummies and the style
+//! exposures from [`FactorRegistry::compute_all`](crate::FactorRegistry::compute_all),
+//! weighted by the square root of market cap. The residuals are the stocks'
+//! specific returns.
+//!
+//! [`fama_macbeth`] then averages each factor's return series over time, with
+//! Newey-West standard errors for the autocorrelation that overlapping or
+//! persistent returns introduce.
+
+use crate::{
+    FactorError, Result,
+    cross_section::{float_columns, rows_by_date},
+    evaluation::factor_columns,
+};
+use ndarray::{Array1, Array2};
+use polars::prelude::*;
+use std::collections::BTreeSet;
+
+/// Name of the intercept factor in [`FactorReturns::factor_returns`].
+pub const MARKET_FACTOR: &str = "market";
+
+/// Prefix of industry factor names in [`FactorReturns::factor_returns`].
+pub const INDUSTRY_PREFIX: &str = "industry_";
+
+/// Configuration for [`cross_sectional_regression`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CrossSectionConfig {
+    /// Column holding each row's forward return
+    pub return_column: String,
+    /// Market cap column; rows are weighted by its square root. `None`
+    /// weights every row equally
+    pub weight_column: Option<String>,
+    /// Industry column, expanded into one dummy per industry present on
+    /// each date
+    pub industry_column: Option<String>,
+}
+
+impl Default for CrossSectionConfig {
+    fn default() -> Self {
+        Self {
+            return_column: "forward_return_1".to_string(),
+            weight_column: Some("market_cap".to_string()),
+            industry_column: None,
+        }
+    }
+}
+
+impl CrossSectionConfig {
+    /// Set the forward return column.
+    pub fn with_return_column(mut self, column: impl Into<String>) -> Self {
+        self.return_column = column.into();
+        self
+    }
+
+    /// Set the market cap column used for weighting.
+    pub fn with_weight_column(mut self, column: impl Into<String>) -> Self {
+        self.weight_column = Some(column.into());
+        self
+    }
+
+    /// Weight every row equally.
+    pub fn unweighted(mut self) -> Self {
+        self.weight_column = None;
+        self
+    }
+
+    /// Set the industry column.
+    pub fn with_industry_column(mut self, column: impl Into<String>) -> Self {
+        self.industry_column = Some(column.into());
+        self
+    }
+}
+
+/// Output of [`cross_sectional_regression`].
+#[derive(Debug, Clone)]
+pub struct FactorReturns {
+    /// Columns `factor`, `date`, `factor_return`: the `market` intercept,
+    /// each industry (prefixed `industry_`) and each style factor
+    pub factor_returns: DataFrame,
+    /// Columns `symbol`, `date`, `specific_return`: regression residuals
+    pub specific_returns: DataFrame,
+    /// Columns `date`, `count`, `r_squared`: per-date fit, with the weighted
+    /// R²
+    pub fits: DataFrame,
+}
+
+/// Estimate factor and specific returns by per-date weighted regression.
+///
+/// On each date, regresses forward returns on a unit market exposure, the
+/// style exposures and, if configured, industry dummies. Industry returns are
+/// constrained to sum to zero so the market return stays identifiable. Rows
+/// are weighted by √cap, which down-weights small caps' noisy returns without
+/// letting the largest names dominate.
+///
+/// Rows with a null return, exposure, weight or industry, or a non-positive
+/// weight, are left out. Dates with no more rows than parameters are skipped.
+///
+/// # Arguments
+///
+/// * `exposures` - Wide exposures: `symbol`, `date`, one column per style
+///   factor
+/// * `data` - Panel with `symbol`, `date`, the return column and any weight
+///   and industry columns
+/// * `config` - Return, weight and industry columns
+///
+/// # Errors
+///
+/// Returns [`FactorError::Computation`] if a date's exposures are collinear.
+pub fn cross_sectional_regression(
+    exposures: &DataFrame,
+    data: &DataFrame,
+    config: &CrossSectionConfig,
+) -> Result<FactorReturns> {
+    let styles = factor_columns(exposures);
+    let keys = [col("symbol"), col("date")];
+    let mut columns = vec![
+        col("symbol"),
+        col("date"),
+        col(config.return_column.as_str())
+            .cast(DataType::Float64)
+            .alias("return"),
+    ];
+    if let Some(weight) = &config.weight_column {
+        columns.push(col(weight.as_str()).cast(DataType::Float64).alias("weight"));
+    }
+    if let Some(industry) = &config.industry_column {
+        columns.push(
+            col(industry.as_str())
+                .cast(DataType::String)
+                .alias("industry"),
+        );
+    }
+    let panel = exposures
+        .clone()
+        .lazy()
+        .join(
+            data.clone().lazy().select(columns),
+            keys.clone(),
+            keys,
+            JoinArgs::new(JoinType::Inner),
+        )
+        .sort(["date", "symbol"], Default::default())
+        .collect()?;
+
+    let style_names: Vec<&str> = styles.iter().map(String::as_str).collect();
+    let returns = float_columns(&panel, &["return"])?;
+    let exposure_values = float_columns(&panel, &style_names)?;
+    let weights = config
+        .weight_column
+        .as_ref()
+        .map(|_| float_columns(&panel, &["weight"]))
+        .transpose()?;
+    let industries = config
+        .industry_column
+        .as_ref()
+        .map(|_| panel.column("industry").map(|c| c.str().cloned()))
+        .transpose()?
+        .transpose()?;
+
+    let rows_by_date = rows_by_date(&panel, |row| {
+        returns[0].get(row).is_some_and(f64::is_finite)
+            && exposure_values
+                .iter()
+                .all(|e| e.get(row).is_some_and(f64::is_finite))
+            && weights
+                .as_ref()
+                .is_none_or(|w| w[0].get(row).is_some_and(|w| w > 0.0))
+            && industries.as_ref().is_none_or(|i| i.get(row).is_some())
+    })?;
+    let mut dates: Vec<_> = rows_by_date.into_iter().collect();
+    dates.sort();
+
+    let mut residuals: Vec<Option<f64>> = vec![None; panel.height()];
+    let mut return_rows: Vec<IdxSize> = Vec::new();
+    let mut return_factors: Vec<String> = Vec::new();
+    let mut return_values: Vec<f64> = Vec::new();
+    let mut fit_rows: Vec<IdxSize> = Vec::new();
+    let mut fit_counts: Vec<u64> = Vec::new();
+    let mut fit_r_squared: Vec<f64> = Vec::new();
+
+    for (date, rows) in dates {
+        let n = rows.len();
+        let industry_names: Vec<&str> = industries.as_ref().map_or_else(Vec::new, |i| {
+            rows.iter()
+                .filter_map(|&row| i.get(row))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        });
+        let parameters = 1 + styles.len() + industry_names.len().saturating_sub(1);
+        if n <= parameters {
+            continue;
+        }
+
+        let mut y = Array1::zeros(n);
+        let mut style_matrix = Array2::zeros((n, styles.len()));
+        let mut sector_matrix = Array2::zeros((n, industry_names.len()));
+        // √cap regression weights; toraniko-math scales rows by the weight
+        // before squaring, so it gets their square root
+        let mut regression_weights = Array1::ones(n);
+        for (i, &row) in rows.iter().enumerate() {
+            y[i] = returns[0].get(row).unwrap_or_default();
+            for (j, exposure) in exposure_values.iter().enumerate() {
+                style_matrix[[i, j]] = exposure.get(row).unwrap_or_default();
+            }
+            if let Some(weights) = &weights {
+                regression_weights[i] = weights[0].get(row).unwrap_or_default().sqrt();
+            }
+            if let Some(industry) = industries.as_ref().and_then(|i| i.get(row)) {
+                let j = industry_names
+                    .binary_search(&industry)
+                    .expect("collected above");
+                sector_matrix[[i, j]] = 1.0;
+            }
+        }
+        let row_weights = regression_weights.mapv(f64::sqrt);
+        let failed = |e: toraniko_math::MathError| {
+            FactorError::Computation(format!("regression failed on {date}: {e}"))
+        };
+
+        let (market, industry_returns, style_returns, fitted_residuals) =
+            if industry_names.is_empty() {
+                let mut x = Array2::ones((n, styles.len() + 1));
+                for ((i, j), &exposure) in style_matrix.indexed_iter() {
+                    x[[i, j + 1]] = exposure;
+                }
+                let fit =
+                    toraniko_math::weighted_least_squares(&y, &x, &row_weights).map_err(failed)?;
+                (
+                    fit.coefficients[0],
+                    Array1::zeros(0),
+                    fit.coefficients.iter().skip(1).copied().collect(),
+                    fit.residuals,
+                )
+            } else {
+                let fit =
+                    toraniko_math::constrained_wls(&y, &row_weights, &sector_matrix, &style_matrix)
+                        .map_err(failed)?;
+                (
+                    fit.market_return,
+                    fit.sector_returns,
+                    fit.style_returns,
+                    fit.residuals,
+                )
+            };
+
+        let first = rows[0] as IdxSize;
+        let estimates = std::iter::once((MARKET_FACTOR.to_string(), market))
+            .chain(
+                industry_names
+                    .iter()
+                    .zip(industry_returns.iter())
+                    .map(|(name, &r)| (format!("{INDUSTRY_PREFIX}{name}"), r)),
+            )
+            .chain(styles.iter().cloned().zip(style_returns.iter().copied()));
+        for (factor, value) in estimates {
+            return_rows.push(first);
+            return_factors.push(factor);
+            return_values.push(value);
+        }
+
+        let total_weight = regression_weights.sum();
+        let mean = (&regression_weights * &y).sum() / total_weight;
+        let residual_ss = (&regression_weights * &fitted_residuals.mapv(|e| e * e)).sum();
+        let total_ss = (&regression_weights * &y.mapv(|v| (v - mean).powi(2))).sum();
+        fit_rows.push(first);
+        fit_counts.push(n as u64);
+        fit_r_squared.push(if total_ss > 0.0 {
+            1.0 - residual_ss / total_ss
+        } else {
+            0.0
+        });
+
+        for (&row, &residual) in rows.iter().zip(fitted_residuals.iter()) {
+            residuals[row] = Some(residual);
+        }
+    }
+
+    let dates_at = |rows: Vec<IdxSize>| {
+        panel
+            .column("date")?
+            .take(&IdxCa::from_vec("".into(), rows))
+    };
+
+    let factor_returns = DataFrame::new(vec![
+        Column::new("factor".into(), return_factors),
+        dates_at(return_rows)?,
+        Column::new("factor_return".into(), return_values),
+    ])?;
+    let fits = DataFrame::new(vec![
+        dates_at(fit_rows)?,
+        Column::new("count".into(), fit_counts),
+        Column::new("r_squared".into(), fit_r_squared),
+    ])?;
+
+    let mut specific_returns = panel.select(["symbol", "date"])?;
+    specific_returns.with_column(Column::new("specific_return".into(), residuals))?;
+    let specific_returns = specific_returns
+        .lazy()
+        .filter(col("specific_return").is_not_null())
+        .sort(["symbol", "date"], Default::default())
+        .collect()?;
+
+    Ok(FactorReturns {
+        factor_returns: factor_returns
+            .lazy()
+            .sort(["factor", "date"], Default::default())
+            .collect()?,
+        specific_returns,
+        fits,
+    })
+}
+
+/// Fama-MacBeth statistics of per-date factor returns.
+///
+/// For each factor, reports the mean return over dates, its standard
+/// deviation, and a t-statistic whose standard error is Newey-West adjusted
+/// with Bartlett weights over `lags` lags. With `lags` of `None`, uses
+/// `⌊4 (T / 100)^(2/9)⌋` for `T` dates.
+///
+/// # Arguments
+///
+/// * `factor_returns` - [`FactorReturns::factor_returns`]: `factor`, `date`,
+///   `factor_return`
+/// * `lags` - Newey-West lag count
+///
+/// # Returns
+///
+/// DataFrame with columns: `factor`, `periods`, `mean_return`, `std`,
+/// `newey_west_se`, `t_stat`, sorted by factor.
+pub fn fama_macbeth(factor_returns: &DataFrame, lags: Option<usize>) -> Result<DataFrame> {
+    let sorted = factor_returns
+        .clone()
+        .lazy()
+        .filter(col("factor_return").is_not_null())
+        .sort(["factor", "date"], Default::default())
+        .group_by_stable([col("factor")])
+        .agg([col("factor_return")])
+        .collect()?;
+
+    let factors = sorted.column("factor")?.str()?;
+    let series = sorted.column("factor_return")?.list()?;
+
+    let mut names = Vec::with_capacity(sorted.height());
+    let mut periods = Vec::with_capacity(sorted.height());
+    let mut means = Vec::with_capacity(sorted.height());
+    let mut stds = Vec::with_capacity(sorted.height());
+    let mut errors = Vec::with_capacity(sorted.height());
+    let mut t_stats = Vec::with_capacity(sorted.height());
+    for (name, values) in factors.into_iter().zip(series) {
+        let (Some(name), Some(values)) = (name, values) else {
+            continue;
+        };
+        let values: Vec<f64> = values.f64()?.into_no_null_iter().collect();
+        let t = values.len();
+        let mean = values.iter().sum::<f64>() / t as f64;
+        let std = (t > 1).then(|| {
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (t - 1) as f64).sqrt()
+        });
+        let lags = lags.unwrap_or_else(|| default_lags(t));
+        let se = (newey_west_variance(&values, lags) / t as f64).sqrt();
+
+        names.push(name.to_string());
+        periods.push(t as u64);
+        means.push(mean);
+        stds.push(std);
+        errors.push(se);
+        t_stats.push((se > 0.0).then(|| mean / se));
+    }
+
+    Ok(DataFrame::new(vec![
+        Column::new("factor".into(), names),
+        Column::new("periods".into(), periods),
+        Column::new("mean_return".into(), means),
+        Column::new("std".into(), stds),
+        Column::new("newey_west_se".into(), errors),
+        Column::new("t_stat".into(), t_stats),
+    ])?)
+}
+
+/// Newey-West (1994) automatic lag count for `periods` observations.
+pub(crate) fn default_lags(periods: usize) -> usize {
+    (4.0 * (periods as f64 / 100.0).powf(2.0 / 9.0)).floor() as usize
+}
+
+/// Long-run variance of a series: [`newey_west_covariance`] with equal
+/// weights.
+pub(crate) fn newey_west_variance(values: &[f64], lags: usize) -> f64 {
+    let t = values.len();
+    if t == 0 {
+        return f64::NAN;
+    }
+    let series = Array2::from_shape_fn((t, 1), |(i, _)| values[i]);
+    newey_west_covariance(&series, &Array1::from_elem(t, 1.0 / t as f64), lags)[[0, 0]]
+}
+
+/// Long-run covariance of the columns of `returns` (oldest row first).
+///
+/// Rows are demeaned and weighted by `weights`, which sum to one, and the
+/// lagged covariances up to `lags` are added with Bartlett weights
+/// `1 - j / (lags + 1)`.
+pub(crate) fn newey_west_covariance(
+    returns: &Array2<f64>,
+    weights: &Array1<f64>,
+    lags: usize,
+) -> Array2<f64> {
+    let t = returns.nrows();
+    let mean = weights.dot(returns);
+    let demeaned = returns - &mean;
+    let lagged = |lag: usize| {
+        let mut covariance = Array2::zeros((returns.ncols(), returns.ncols()));
+        for s in lag..t {
+            let current = demeaned.row(s);
+            let previous = demeaned.row(s - lag);
+            for i in 0..returns.ncols() {
+                for j in 0..returns.ncols() {
+                    covariance[[i, j]] += weights[s] * current[i] * previous[j];
+                }
+            }
+        }
+        covariance
+    };
+
+    let mut covariance = lagged(0);
+    for lag in 1..=lags.min(t.saturating_sub(1)) {
+        let gamma = lagged(lag);
+        let bartlett = 1.0 - lag as f64 / (lags + 1) as f64;
+        covariance = covariance + (&gamma + &gamma.t()) * bartlett;
+    }
+    covariance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Eight stocks in two industries over three dates. Returns are an exact
+    /// linear function of the exposures except for stock H's noise.
+    fn panel() -> (DataFrame, DataFrame) {
+        let mut symbols = Vec::new();
+        let mut dates = Vec::new();
+        let mut size = Vec::new();
+        let mut momentum = Vec::new();
+        let mut returns = Vec::new();
+        let mut caps = Vec::new();
+        let mut sectors = Vec::new();
+        for (d, date) in ["2024-01-02", "2024-01-03", "2024-01-04"]
+            .into_iter()
+            .enumerate()
+        {
+            for s in 0..8 {
+                let x1 = s as f64 - 3.5;
+                let x2 = ((s * 5 + d) % 8) as f64 / 4.0 - 1.0;
+                let tech = s % 2 == 0;
+                let noise = if s == 7 { 0.002 } else { 0.0 };
+                symbols.push(format!("S{s}"));
+                dates.push(date);
+                size.push(x1);
+                momentum.push(x2);
+                returns.push(
+                    0.001 + 0.01 * x1 - 0.005 * x2 + if tech { 0.002 } else { -0.002 } + noise,
+                );
+                caps.push(100.0 * (s + 1) as f64);
+                sectors.push(if tech { "tech" } else { "energy" });
+            }
+        }
+        let exposures = df![
+            "symbol" => symbols.clone(),
+            "date" => dates.clone(),
+            "size" => size,
+            "momentum" => momentum,
+        ]
+        .unwrap();
+        let data = df![
+            "symbol" => symbols,
+            "date" => dates,
+            "forward_return_1" => returns,
+            "market_cap" => caps,
+            "sector" => sectors,
+        ]
+        .unwrap();
+        (exposures, data)
+    }
+
+    fn factor_return(result: &FactorReturns, factor: &str) -> Vec<f64> {
+        result
+            .factor_returns
+            .clone()
+            .lazy()
+            .filter(col("factor").eq(lit(factor)))
+            .collect()
+            .unwrap()
+            .column("factor_return")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_cross_sectional_regression_with_industries() {
+        let (exposures, mut data) = panel();
+        // Drop H's noise so the fit is exact
+        let exact: Vec<f64> = data
+            .column("forward_return_1")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .enumerate()
+            .map(|(i, r)| if i % 8 == 7 { r - 0.002 } else { r })
+            .collect();
+        data.with_column(Column::new("forward_return_1".into(), exact))
+            .unwrap();
+
+        let config = CrossSectionConfig::default().with_industry_column("sector");
+        let result = cross_sectional_regression(&exposures, &data, &config).unwrap();
+
+        // market, two industries and two styles on each of three dates
+        assert_eq!(result.factor_returns.height(), 15);
+        for r in factor_return(&result, "size") {
+            assert_relative_eq!(r, 0.01, epsilon = 1e-10);
+        }
+        for r in factor_return(&result, "industry_tech") {
+            assert_relative_eq!(r, 0.002, epsilon = 1e-10);
+        }
+        for r in factor_return(&result, MARKET_FACTOR) {
+            assert_relative_eq!(r, 0.001, epsilon = 1e-10);
+        }
+        let residuals = result.specific_returns.column("specific_return").unwrap();
+        assert_eq!(residuals.len(), 24);
+        assert!(
+            residuals
+                .f64()
+                .unwrap()
+                .into_no_null_iter()
+                .all(|e| e.abs() < 1e-10)
+        );
+        let r_squared = result.fits.column("r_squared").unwrap().f64().unwrap();
+        assert_relative_eq!(r_squared.get(0).unwrap(), 1.0, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_cross_sectional_regression_weights_residuals() {
+        let (exposures, data) = panel();
+        let config = CrossSectionConfig::default();
+        let result = cross_sectional_regression(&exposures, &data, &config).unwrap();
+        assert_eq!(result.factor_returns.height(), 9);
+
+        // √cap-weighted residuals are orthogonal to the intercept
+        let first = result
+            .specific_returns
+            .lazy()
+            .join(
+                data.lazy(),
+                [col("symbol"), col("date")],
+                [col("symbol"), col("date")],
+                JoinArgs::new(JoinType::Inner),
+            )
+            .filter(col("date").eq(lit("2024-01-02")))
+            .select([(col("specific_return") * col("market_cap").sqrt()).sum()])
+            .collect()
+            .unwrap();
+        let weighted = first.column("specific_return").unwrap().f64().unwrap();
+        assert_relative_eq!(weighted.get(0).unwrap(), 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_fama_macbeth() {
+        let factor_returns = df![
+            "factor" => ["a", "a", "a", "a", "b", "b", "b", "b"],
+            "date" => ["1", "2", "3", "4", "1", "2", "3", "4"],
+            "factor_return" => [0.01, 0.03, 0.01, 0.03, 0.02, -0.02, 0.02, -0.02],
+        ]
+        .unwrap();
+
+        let iid = fama_macbeth(&factor_returns, Some(0)).unwrap();
+        let mean = iid.column("mean_return").unwrap().f64().unwrap();
+        assert_relative_eq!(mean.get(0).unwrap(), 0.02);
+        assert_relative_eq!(mean.get(1).unwrap(), 0.0);
+        // With no lags the standard error is the population std over √T
+        let se = iid.column("newey_west_se").unwrap().f64().unwrap();
+        assert_relative_eq!(se.get(0).unwrap(), 0.01 / 2.0, epsilon = 1e-12);
+
+        // Alternating returns have negative autocorrelation, shrinking the
+        // long-run variance
+        let adjusted = fama_macbeth(&factor_returns, Some(1)).unwrap();
+        let adjusted_se = adjusted.column("newey_west_se").unwrap().f64().unwrap();
+        assert!(adjusted_se.get(0).unwrap() < se.get(0).unwrap());
+
+        assert_eq!(default_lags(100), 4);
+    }
+}
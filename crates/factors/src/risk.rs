@@ -0,0 +1,643 @@
+//! Factor covariance and specific risk forecasts.
+//!
+//! Built on the per-date estimates from
+//! [`cross_sectional_regression`](crate::cross_sectional_regression):
+//!
+//! - [`factor_covariance`] combines exponentially weighted volatilities with
+//!   exponentially weighted correlations, each with its own half-life and a
+//!   Newey-West adjustment for serial correlation. A short volatility
+//!   half-life tracks changing risk levels; a long correlation half-life keeps
+//!   the matrix stable and well conditioned.
+//! - [`specific_risk`] forecasts each stock's residual volatility the same way,
+//!   then shrinks it toward the cap-weighted mean of its size bucket, pulling
+//!   noisy estimates in harder.
+//! - [`RiskForecast`] bundles both and turns exposures into total risk.
+//!
+//! All forecasts are per period of the input returns, e.g. daily for daily
+//! returns.
+
+use crate::{
+    FactorError, Result,
+    regression::{FactorReturns, INDUSTRY_PREFIX, MARKET_FACTOR, newey_west_covariance},
+};
+use ndarray::{Array1, Array2};
+use polars::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Configuration for [`factor_covariance`] and [`specific_risk`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RiskConfig {
+    /// Half-life, in periods, of the factor volatility weights
+    pub volatility_half_life: usize,
+    /// Half-life, in periods, of the factor correlation weights
+    pub correlation_half_life: usize,
+    /// Half-life, in periods, of the specific volatility weights
+    pub specific_half_life: usize,
+    /// Lags in the Newey-West serial correlation adjustment
+    pub newey_west_lags: usize,
+    /// Bayesian shrinkage intensity `q` toward size bucket means
+    pub shrinkage: f64,
+    /// Number of equal-count market cap buckets for shrinkage
+    pub size_buckets: usize,
+    /// Minimum specific return observations for a stock's own estimate
+    pub min_periods: usize,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            volatility_half_life: 84,
+            correlation_half_life: 504,
+            specific_half_life: 84,
+            newey_west_lags: 2,
+            shrinkage: 0.1,
+            size_buckets: 10,
+            min_periods: 20,
+        }
+    }
+}
+
+impl RiskConfig {
+    /// Set the factor volatility and correlation half-lives.
+    pub const fn with_half_lives(mut self, volatility: usize, correlation: usize) -> Self {
+        self.volatility_half_life = volatility;
+        self.correlation_half_life = correlation;
+        self
+    }
+
+    /// Set the specific volatility half-life.
+    pub const fn with_specific_half_life(mut self, half_life: usize) -> Self {
+        self.specific_half_life = half_life;
+        self
+    }
+
+    /// Set the Newey-West lag count.
+    pub const fn with_newey_west_lags(mut self, lags: usize) -> Self {
+        self.newey_west_lags = lags;
+        self
+    }
+
+    /// Set the shrinkage intensity and number of size buckets.
+    pub const fn with_shrinkage(mut self, intensity: f64, size_buckets: usize) -> Self {
+        self.shrinkage = intensity;
+        self.size_buckets = size_buckets;
+        self
+    }
+
+    /// Set the minimum observations for a stock's own specific risk.
+    pub const fn with_min_periods(mut self, min_periods: usize) -> Self {
+        self.min_periods = min_periods;
+        self
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.volatility_half_life == 0
+            || self.correlation_half_life == 0
+            || self.specific_half_life == 0
+        {
+            return Err(FactorError::InvalidSpec(
+                "risk half-lives must be at least 1".to_string(),
+            ));
+        }
+        if self.size_buckets == 0 || self.shrinkage.is_nan() || self.shrinkage < 0.0 {
+            return Err(FactorError::InvalidSpec(
+                "shrinkage needs a non-negative intensity and at least one size bucket".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Factor covariance matrix from per-date factor returns.
+///
+/// Each factor's variance uses exponential weights with the volatility
+/// half-life, and the correlations use weights with the correlation
+/// half-life; both add Bartlett-weighted autocovariances up to the
+/// Newey-West lag count. A factor missing on a date, e.g. an industry with no
+/// members, counts as a zero return.
+///
+/// # Arguments
+///
+/// * `factor_returns` - [`FactorReturns::factor_returns`]: `factor`, `date`,
+///   `factor_return`
+/// * `config` - Half-lives and lag count
+///
+/// # Returns
+///
+/// Square DataFrame with a `factor` column followed by one column per factor,
+/// both in name order.
+pub fn factor_covariance(factor_returns: &DataFrame, config: &RiskConfig) -> Result<DataFrame> {
+    config.check()?;
+    let (factors, returns) = factor_matrix(factor_returns)?;
+    if returns.nrows() < 2 {
+        return Err(FactorError::InsufficientData {
+            required: 2,
+            available: returns.nrows(),
+        });
+    }
+
+    let volatility = ewma_covariance(
+        &returns,
+        config.volatility_half_life,
+        config.newey_west_lags,
+    );
+    let correlation = ewma_covariance(
+        &returns,
+        config.correlation_half_life,
+        config.newey_west_lags,
+    );
+
+    let k = factors.len();
+    let mut columns = vec![Column::new("factor".into(), factors.clone())];
+    for (j, name) in factors.iter().enumerate() {
+        let values: Vec<f64> = (0..k)
+            .map(|i| {
+                let scale = (correlation[[i, i]] * correlation[[j, j]]).sqrt();
+                let rho = if i == j {
+                    1.0
+                } else if scale > 0.0 {
+                    correlation[[i, j]] / scale
+                } else {
+                    0.0
+                };
+                rho * (volatility[[i, i]] * volatility[[j, j]]).sqrt()
+            })
+            .collect();
+        columns.push(Column::new(name.as_str().into(), values));
+    }
+
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Specific volatility forecasts with Bayesian shrinkage toward size buckets.
+///
+/// Each stock's raw forecast is the Newey-West adjusted, exponentially
+/// weighted volatility of its specific returns. Stocks are then sorted into
+/// equal-count market cap buckets, and each forecast is shrunk toward the
+/// cap-weighted mean raw forecast `s̄` of its bucket:
+///
+/// `σ = v s̄ + (1 - v) σ_raw`, with `v = q |σ_raw - s̄| / (Δ + q |σ_raw - s̄|)`
+///
+/// where `Δ` is the bucket's standard deviation of raw forecasts around `s̄`
+/// and `q` the shrinkage intensity, so outliers are pulled in the most.
+/// Stocks with fewer than `min_periods` specific returns take the bucket
+/// mean.
+///
+/// # Arguments
+///
+/// * `specific_returns` - [`FactorReturns::specific_returns`]: `symbol`,
+///   `date`, `specific_return`
+/// * `market_caps` - `symbol`, `date`, `market_cap`; each symbol's latest
+///   row is used
+/// * `config` - Specific half-life, lag count and shrinkage settings
+///
+/// # Returns
+///
+/// DataFrame with columns: `symbol`, `market_cap`, `size_bucket`, `periods`,
+/// `raw_specific_risk`, `specific_risk`, sorted by symbol. Symbols without a
+/// market cap are dropped.
+pub fn specific_risk(
+    specific_returns: &DataFrame,
+    market_caps: &DataFrame,
+    config: &RiskConfig,
+) -> Result<DataFrame> {
+    config.check()?;
+    let series = specific_returns
+        .clone()
+        .lazy()
+        .filter(col("specific_return").is_not_null())
+        .sort(["symbol", "date"], Default::default())
+        .group_by([col("symbol")])
+        .agg([col("specific_return").cast(DataType::Float64)])
+        .collect()?;
+    let caps = market_caps
+        .clone()
+        .lazy()
+        .select([
+            col("symbol"),
+            col("date"),
+            col("market_cap").cast(DataType::Float64),
+        ])
+        .filter(col("market_cap").gt(lit(0.0)))
+        .sort(["symbol", "date"], Default::default())
+        .group_by([col("symbol")])
+        .agg([col("market_cap").last()])
+        .join(
+            series.lazy(),
+            [col("symbol")],
+            [col("symbol")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .sort(["market_cap", "symbol"], Default::default())
+        .collect()?;
+
+    let symbols = caps.column("symbol")?.str()?;
+    let cap_values = caps.column("market_cap")?.f64()?;
+    let returns = caps.column("specific_return")?.list()?;
+
+    let n = caps.height();
+    let mut periods = Vec::with_capacity(n);
+    let mut raw: Vec<Option<f64>> = Vec::with_capacity(n);
+    for history in returns {
+        let history: Vec<f64> = match history {
+            Some(history) => history.f64()?.into_no_null_iter().collect(),
+            None => Vec::new(),
+        };
+        periods.push(history.len() as u64);
+        raw.push((history.len() >= config.min_periods.max(2)).then(|| {
+            let column = Array2::from_shape_vec((history.len(), 1), history)
+                .expect("one column per observation");
+            ewma_covariance(&column, config.specific_half_life, config.newey_west_lags)[[0, 0]]
+                .sqrt()
+        }));
+    }
+
+    // Rows are in market cap order, so buckets are contiguous runs
+    let buckets = config.size_buckets.min(n).max(1);
+    let bucket_of = |row: usize| row * buckets / n.max(1);
+    let mut shrunk: Vec<Option<f64>> = vec![None; n];
+    for bucket in 0..buckets {
+        let rows: Vec<usize> = (0..n).filter(|&row| bucket_of(row) == bucket).collect();
+        let estimated: Vec<(f64, f64)> = rows
+            .iter()
+            .filter_map(|&row| Some((raw[row]?, cap_values.get(row)?)))
+            .collect();
+        let total_cap: f64 = estimated.iter().map(|(_, cap)| cap).sum();
+        if estimated.is_empty() || total_cap <= 0.0 {
+            continue;
+        }
+        let mean = estimated.iter().map(|(s, cap)| s * cap).sum::<f64>() / total_cap;
+        let dispersion = (estimated
+            .iter()
+            .map(|(s, _)| (s - mean).powi(2))
+            .sum::<f64>()
+            / estimated.len() as f64)
+            .sqrt();
+
+        for &row in &rows {
+            shrunk[row] = Some(raw[row].map_or(mean, |sigma| {
+                let gap = config.shrinkage * (sigma - mean).abs();
+                let intensity = if gap > 0.0 {
+                    gap / (dispersion + gap)
+                } else {
+                    0.0
+                };
+                intensity * mean + (1.0 - intensity) * sigma
+            }));
+        }
+    }
+
+    let result = DataFrame::new(vec![
+        Column::new(
+            "symbol".into(),
+            symbols.into_iter().collect::<Vec<Option<&str>>>(),
+        ),
+        Column::new("market_cap".into(), cap_values.clone()),
+        Column::new(
+            "size_bucket".into(),
+            (0..n)
+                .map(|row| bucket_of(row) as i64 + 1)
+                .collect::<Vec<_>>(),
+        ),
+        Column::new("periods".into(), periods),
+        Column::new("raw_specific_risk".into(), raw),
+        Column::new("specific_risk".into(), shrunk),
+    ])?
+    .lazy()
+    .sort(["symbol"], Default::default())
+    .collect()?;
+
+    Ok(result)
+}
+
+/// A full risk forecast: factor covariance plus specific risk.
+#[derive(Debug, Clone)]
+pub struct RiskForecast {
+    /// Output of [`factor_covariance`]
+    pub factor_covariance: DataFrame,
+    /// Output of [`specific_risk`]
+    pub specific_risk: DataFrame,
+}
+
+impl RiskForecast {
+    /// Estimate both parts of the forecast from regression output.
+    ///
+    /// # Arguments
+    ///
+    /// * `estimates` - Output of
+    ///   [`cross_sectional_regression`](crate::cross_sectional_regression)
+    /// * `market_caps` - `symbol`, `date`, `market_cap`
+    /// * `config` - Half-lives, lags and shrinkage settings
+    pub fn estimate(
+        estimates: &FactorReturns,
+        market_caps: &DataFrame,
+        config: &RiskConfig,
+    ) -> Result<Self> {
+        Ok(Self {
+            factor_covariance: factor_covariance(&estimates.factor_returns, config)?,
+            specific_risk: specific_risk(&estimates.specific_returns, market_caps, config)?,
+        })
+    }
+
+    /// Forecast volatility of each row's stock.
+    ///
+    /// Every stock has a unit `market` exposure. Style exposures are read
+    /// from columns named after covariance factors; with `industry_column`,
+    /// each row is also exposed to `industry_{value}`. Factors without a
+    /// column count as zero exposure.
+    ///
+    /// # Arguments
+    ///
+    /// * `exposures` - `symbol`, `date`, style exposures and the industry
+    ///   column, if any
+    /// * `industry_column` - Column holding each stock's industry
+    ///
+    /// # Returns
+    ///
+    /// DataFrame with columns: `symbol`, `date`, `factor_risk`,
+    /// `specific_risk`, `total_risk`. Rows with a null exposure get a null
+    /// factor risk, and stocks without a specific forecast a null specific
+    /// risk; either makes the total null.
+    pub fn asset_risk(
+        &self,
+        exposures: &DataFrame,
+        industry_column: Option<&str>,
+    ) -> Result<DataFrame> {
+        let factors: Vec<String> = self
+            .factor_covariance
+            .column("factor")?
+            .str()?
+            .into_no_null_iter()
+            .map(str::to_string)
+            .collect();
+        let covariance = self.covariance_matrix(&factors)?;
+        let index: HashMap<&str, usize> = factors
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), i))
+            .collect();
+
+        let mut styles = Vec::new();
+        for (i, name) in factors.iter().enumerate() {
+            if exposures.schema().contains(name) && Some(name.as_str()) != industry_column {
+                styles.push((i, exposures.column(name)?.cast(&DataType::Float64)?));
+            }
+        }
+        let industries = industry_column
+            .map(|name| exposures.column(name)?.cast(&DataType::String))
+            .transpose()?;
+        let market = index.get(MARKET_FACTOR).copied();
+
+        let mut factor_risk: Vec<Option<f64>> = Vec::with_capacity(exposures.height());
+        for row in 0..exposures.height() {
+            let mut x = Array1::<f64>::zeros(factors.len());
+            if let Some(market) = market {
+                x[market] = 1.0;
+            }
+            let mut complete = true;
+            for (i, column) in &styles {
+                match column.f64()?.get(row) {
+                    Some(value) => x[*i] = value,
+                    None => complete = false,
+                }
+            }
+            if let Some(industries) = &industries {
+                match industries.str()?.get(row) {
+                    Some(industry) => {
+                        if let Some(&i) = index.get(format!("{INDUSTRY_PREFIX}{industry}").as_str())
+                        {
+                            x[i] = 1.0;
+                        }
+                    }
+                    None => complete = false,
+                }
+            }
+            factor_risk.push(complete.then(|| x.dot(&covariance.dot(&x)).max(0.0).sqrt()));
+        }
+
+        let mut result = exposures.select(["symbol", "date"])?;
+        result.with_column(Column::new("factor_risk".into(), factor_risk))?;
+        let specific = self
+            .specific_risk
+            .clone()
+            .lazy()
+            .select([col("symbol"), col("specific_risk")]);
+
+        Ok(result
+            .lazy()
+            .join(
+                specific,
+                [col("symbol")],
+                [col("symbol")],
+                JoinArgs::new(JoinType::Left),
+            )
+            .with_column(
+                (col("factor_risk").pow(2) + col("specific_risk").pow(2))
+                    .sqrt()
+                    .alias("total_risk"),
+            )
+            .collect()?)
+    }
+
+    /// The covariance frame as a matrix, rows and columns in `factors` order.
+    fn covariance_matrix(&self, factors: &[String]) -> Result<Array2<f64>> {
+        let k = factors.len();
+        let mut matrix = Array2::zeros((k, k));
+        for (j, name) in factors.iter().enumerate() {
+            let column = self.factor_covariance.column(name)?.f64()?;
+            for (i, value) in column.into_iter().enumerate().take(k) {
+                matrix[[i, j]] = value.unwrap_or_default();
+            }
+        }
+        Ok(matrix)
+    }
+}
+
+/// Factor names in order and a dates × factors matrix of returns, oldest
+/// date first, with zeros where a factor has no return.
+fn factor_matrix(factor_returns: &DataFrame) -> Result<(Vec<String>, Array2<f64>)> {
+    let factors = factor_returns.column("factor")?.str()?;
+    let dates = factor_returns.column("date")?.cast(&DataType::String)?;
+    let dates = dates.str()?;
+    let values = factor_returns
+        .column("factor_return")?
+        .cast(&DataType::Float64)?;
+    let values = values.f64()?;
+
+    let mut names = BTreeSet::new();
+    let mut by_date: BTreeMap<&str, Vec<(&str, f64)>> = BTreeMap::new();
+    for row in 0..factor_returns.height() {
+        if let (Some(factor), Some(date), Some(value)) =
+            (factors.get(row), dates.get(row), values.get(row))
+        {
+            names.insert(factor);
+            by_date.entry(date).or_default().push((factor, value));
+        }
+    }
+
+    let names: Vec<String> = names.into_iter().map(str::to_string).collect();
+    let index: HashMap<&str, usize> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.as_str(), i))
+        .collect();
+    let mut matrix = Array2::zeros((by_date.len(), names.len()));
+    for (t, observations) in by_date.values().enumerate() {
+        for (factor, value) in observations {
+            matrix[[t, index[factor]]] = *value;
+        }
+    }
+    Ok((names, matrix))
+}
+
+/// Exponentially weighted [`newey_west_covariance`] of the columns of
+/// `returns` (oldest row first).
+fn ewma_covariance(returns: &Array2<f64>, half_life: usize, lags: usize) -> Array2<f64> {
+    // Most recent first from toraniko-math; rows here are oldest first
+    let mut weights = toraniko_math::exp_weights(returns.nrows(), half_life);
+    weights.invert_axis(ndarray::Axis(0));
+    newey_west_covariance(returns, &weights, lags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn factor_returns(a: &[f64], b: &[f64]) -> DataFrame {
+        let dates: Vec<String> = (0..a.len())
+            .map(|t| format!("2024-01-{:02}", t + 1))
+            .collect();
+        df![
+            "factor" => [vec!["a"; a.len()], vec!["b"; b.len()]].concat(),
+            "date" => [dates.clone(), dates].concat(),
+            "factor_return" => [a, b].concat(),
+        ]
+        .unwrap()
+    }
+
+    #[test]
+    fn test_factor_covariance_matches_population_without_decay() {
+        let a = [0.01, -0.02, 0.015, 0.0, -0.005, 0.02];
+        let b = [0.005, -0.01, 0.0, 0.01, -0.01, 0.015];
+        // Half-lives far beyond the sample give equal weights, so the result
+        // is the population (1/T) covariance
+        let config = RiskConfig::default()
+            .with_half_lives(1_000_000, 1_000_000)
+            .with_newey_west_lags(0);
+        let covariance = factor_covariance(&factor_returns(&a, &b), &config).unwrap();
+
+        let mean = |x: &[f64]| x.iter().sum::<f64>() / x.len() as f64;
+        let (ma, mb) = (mean(&a), mean(&b));
+        let cross = a
+            .iter()
+            .zip(&b)
+            .map(|(x, y)| (x - ma) * (y - mb))
+            .sum::<f64>()
+            / a.len() as f64;
+        let variance = a.iter().map(|x| (x - ma).powi(2)).sum::<f64>() / a.len() as f64;
+
+        let column_a = covariance.column("a").unwrap().f64().unwrap();
+        assert_relative_eq!(column_a.get(0).unwrap(), variance, epsilon = 1e-9);
+        assert_relative_eq!(column_a.get(1).unwrap(), cross, epsilon = 1e-9);
+        let column_b = covariance.column("b").unwrap().f64().unwrap();
+        assert_relative_eq!(column_b.get(0).unwrap(), cross, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_newey_west_scales_with_autocorrelation() {
+        let trending: Vec<f64> = (0..40).map(|t| if t < 20 { 0.01 } else { -0.01 }).collect();
+        let alternating: Vec<f64> = (0..40)
+            .map(|t| if t % 2 == 0 { 0.01 } else { -0.01 })
+            .collect();
+        let config = RiskConfig::default().with_half_lives(1_000_000, 1_000_000);
+        let variances = |lags: usize| {
+            let covariance = factor_covariance(
+                &factor_returns(&trending, &alternating),
+                &config.clone().with_newey_west_lags(lags),
+            )
+            .unwrap();
+            let a = covariance
+                .column("a")
+                .unwrap()
+                .f64()
+                .unwrap()
+                .get(0)
+                .unwrap();
+            let b = covariance
+                .column("b")
+                .unwrap()
+                .f64()
+                .unwrap()
+                .get(1)
+                .unwrap();
+            (a, b)
+        };
+
+        let (trending_iid, alternating_iid) = variances(0);
+        let (trending_nw, alternating_nw) = variances(3);
+        assert!(trending_nw > trending_iid);
+        assert!(alternating_nw < alternating_iid);
+    }
+
+    #[test]
+    fn test_specific_risk_shrinks_toward_size_bucket() {
+        let mut symbols = Vec::new();
+        let mut dates = Vec::new();
+        let mut residuals = Vec::new();
+        for (s, scale) in [0.01, 0.01, 0.01, 0.05].into_iter().enumerate() {
+            for t in 0..30 {
+                symbols.push(format!("S{s}"));
+                dates.push(format!("2024-03-{:02}", t + 1));
+                residuals.push(if t % 2 == 0 { scale } else { -scale });
+            }
+        }
+        symbols.push("NEW".to_string());
+        dates.push("2024-03-31".to_string());
+        residuals.push(0.02);
+        let specific = df![
+            "symbol" => symbols,
+            "date" => dates,
+            "specific_return" => residuals,
+        ]
+        .unwrap();
+        let caps = df![
+            "symbol" => ["S0", "S1", "S2", "S3", "NEW"],
+            "date" => ["2024-03-31"; 5],
+            "market_cap" => [100.0, 100.0, 100.0, 100.0, 100.0],
+        ]
+        .unwrap();
+
+        let config = RiskConfig::default()
+            .with_newey_west_lags(0)
+            .with_shrinkage(1.0, 1);
+        let risk = specific_risk(&specific, &caps, &config).unwrap();
+        let raw = risk.column("raw_specific_risk").unwrap().f64().unwrap();
+        let shrunk = risk.column("specific_risk").unwrap().f64().unwrap();
+
+        // Rows by symbol: NEW, S0, S1, S2, S3
+        assert!(raw.get(0).is_none());
+        let bucket_mean = (3.0 * 0.01 + 0.05) / 4.0;
+        assert_relative_eq!(shrunk.get(0).unwrap(), bucket_mean, epsilon = 1e-6);
+        assert_relative_eq!(raw.get(4).unwrap(), 0.05, epsilon = 1e-6);
+        let outlier = shrunk.get(4).unwrap();
+        assert!(outlier < 0.05 && outlier > bucket_mean);
+
+        let forecast = RiskForecast {
+            factor_covariance: df![
+                "factor" => ["market"],
+                "market" => [0.0004],
+            ]
+            .unwrap(),
+            specific_risk: risk,
+        };
+        let exposures = df!["symbol" => ["S3"], "date" => ["2024-03-31"]].unwrap();
+        let total = forecast.asset_risk(&exposures, None).unwrap();
+        let total = total.column("total_risk").unwrap().f64().unwrap();
+        assert_relative_eq!(
+            total.get(0).unwrap(),
+            (0.0004 + outlier * outlier).sqrt(),
+            epsilon = 1e-12
+        );
+    }
+}
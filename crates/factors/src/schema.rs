@@ -0,0 +1,632 @@
+//! Canonical input schema and derived fields.
+//!
+//! [`FIELDS`] lists the standard input columns factors read, grouped into
+//! prices, fundamentals, estimates, ownership and market-level series. Some of
+//! them can be computed from others: `market_cap` from `close` and
+//! `shares_outstanding`, ratios and margins from statement lines, any
+//! `<field>_prior` from the previous reporting period of `<field>`, and
+//! `market_return` as a cap-weighted index of the input universe. Each such
+//! rule is a [`Derivation`].
+//!
+//! [`derive_fields`] fills in the requested columns a dataset lacks, deriving
+//! their inputs first where needed, so data can carry base fields only.
+//! Columns already present are never overwritten. Factors list base fields in
+//! [`Factor::required_columns`](crate::Factor::required_columns) and derive
+//! the rest themselves; [`FactorRegistry`](crate::FactorRegistry) also
+//! derives every column its features and pipeline require.
+
+use crate::{FactorError, Result, traits::date_column};
+use FieldGroup::{Estimate, Fundamental, Identifier, Market, Ownership, Price};
+use derive_more::Display;
+use polars::prelude::*;
+
+/// Kind of data a canonical field holds.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldGroup {
+    /// Row keys: `symbol` and `date`
+    Identifier,
+    /// Daily prices, volumes and quotes
+    Price,
+    /// Financial statement items and ratios built from them
+    Fundamental,
+    /// Analyst estimates and surprises
+    Estimate,
+    /// Share counts and ownership
+    Ownership,
+    /// Market-wide series shared by every symbol on a date
+    Market,
+}
+
+/// A canonical input column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanonicalField {
+    /// Column name
+    pub name: &'static str,
+    /// Kind of data
+    pub group: FieldGroup,
+    /// What the column holds
+    pub description: &'static str,
+}
+
+const fn field(name: &'static str, group: FieldGroup, description: &'static str) -> CanonicalField {
+    CanonicalField {
+        name,
+        group,
+        description,
+    }
+}
+
+/// Standard input columns, by group.
+///
+/// `<field>_prior` columns are not listed; any of them can be derived with
+/// [`Derivation::Prior`].
+pub const FIELDS: &[CanonicalField] = &[
+    field("symbol", Identifier, "Security identifier"),
+    field("date", Identifier, "Observation date"),
+    field("close", Price, "Closing price"),
+    field("high", Price, "Intraday high price"),
+    field("low", Price, "Intraday low price"),
+    field("volume", Price, "Shares traded"),
+    field("bid", Price, "Closing bid quote"),
+    field("ask", Price, "Closing ask quote"),
+    field("implied_volatility", Price, "Option-implied volatility"),
+    field("market_cap", Price, "close × shares_outstanding"),
+    field("enterprise_value", Price, "market_cap + total_debt - cash"),
+    field(
+        REPORT_DATE,
+        Fundamental,
+        "Date the period's fundamentals were reported",
+    ),
+    field("revenue", Fundamental, "Total revenue"),
+    field("cogs", Fundamental, "Cost of goods sold"),
+    field("operating_income", Fundamental, "Operating income"),
+    field("ebit", Fundamental, "Earnings before interest and taxes"),
+    field(
+        "ebitda",
+        Fundamental,
+        "EBIT plus depreciation and amortization",
+    ),
+    field("net_income", Fundamental, "Net income"),
+    field("interest_expense", Fundamental, "Interest expense"),
+    field("tax_rate", Fundamental, "Effective tax rate"),
+    field("eps", Fundamental, "Reported earnings per share"),
+    field(
+        "dividends_per_share",
+        Fundamental,
+        "Trailing dividends per share",
+    ),
+    field(
+        "operating_cash_flow",
+        Fundamental,
+        "Cash flow from operations",
+    ),
+    field(
+        "free_cash_flow",
+        Fundamental,
+        "Operating cash flow less capex",
+    ),
+    field("total_assets", Fundamental, "Total assets"),
+    field("current_assets", Fundamental, "Current assets"),
+    field("cash", Fundamental, "Cash and equivalents"),
+    field("inventory", Fundamental, "Inventory"),
+    field("total_liabilities", Fundamental, "Total liabilities"),
+    field("current_liabilities", Fundamental, "Current liabilities"),
+    field("total_debt", Fundamental, "Short- plus long-term debt"),
+    field("long_term_debt", Fundamental, "Long-term debt"),
+    field("book_equity", Fundamental, "Book value of common equity"),
+    field(
+        "shareholders_equity",
+        Fundamental,
+        "Total shareholders' equity",
+    ),
+    field("retained_earnings", Fundamental, "Retained earnings"),
+    field(
+        "invested_capital",
+        Fundamental,
+        "Debt plus equity capital invested",
+    ),
+    field("employees", Fundamental, "Employee headcount"),
+    field(
+        "working_capital",
+        Fundamental,
+        "current_assets - current_liabilities",
+    ),
+    field("gross_margin", Fundamental, "(revenue - cogs) / revenue"),
+    field(
+        "operating_margin",
+        Fundamental,
+        "operating_income / revenue",
+    ),
+    field("net_margin", Fundamental, "net_income / revenue"),
+    field("roa", Fundamental, "net_income / total_assets"),
+    field(
+        "current_ratio",
+        Fundamental,
+        "current_assets / current_liabilities",
+    ),
+    field("asset_turnover", Fundamental, "revenue / total_assets"),
+    field("eps_estimate", Estimate, "Consensus EPS estimate"),
+    field("eps_expected", Estimate, "Consensus EPS before the report"),
+    field("eps_actual", Estimate, "Reported EPS"),
+    field("surprise_std", Estimate, "Dispersion of past EPS surprises"),
+    field("shares_outstanding", Ownership, "Shares outstanding"),
+    field("float_shares", Ownership, "Shares available to trade"),
+    field("shares_short", Ownership, "Shares sold short"),
+    field(
+        "institutional_ownership",
+        Ownership,
+        "Fraction of shares held by institutions",
+    ),
+    field("insider_buys", Ownership, "Insider purchase transactions"),
+    field("insider_sells", Ownership, "Insider sale transactions"),
+    field("market_return", Market, "Daily return of the market index"),
+];
+
+/// The canonical field named `name`, if any.
+pub fn lookup(name: &str) -> Option<&'static CanonicalField> {
+    FIELDS.iter().find(|field| field.name == name)
+}
+
+/// Suffix of columns holding a field's previous-period value.
+pub const PRIOR_SUFFIX: &str = "_prior";
+
+/// Column keying the reporting period of fundamentals in an as-of panel.
+pub const REPORT_DATE: &str = "report_date";
+
+/// Fewest days between rows that can be distinct reporting periods.
+///
+/// Without a [`REPORT_DATE`] column rows are taken to be periods, which data
+/// with closer rows (e.g. a daily as-of panel) cannot be.
+const MIN_PERIOD_DAYS: i64 = 28;
+
+/// Rule computing one column from others.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Derivation {
+    /// `market_cap = close × shares_outstanding`
+    MarketCap,
+    /// `enterprise_value = market_cap + total_debt - cash`
+    EnterpriseValue,
+    /// `working_capital = current_assets - current_liabilities`
+    WorkingCapital,
+    /// `gross_margin = (revenue - cogs) / revenue`
+    GrossMargin,
+    /// `operating_margin = operating_income / revenue`
+    OperatingMargin,
+    /// `net_margin = net_income / revenue`
+    NetMargin,
+    /// `roa = net_income / total_assets`
+    ReturnOnAssets,
+    /// `current_ratio = current_assets / current_liabilities`
+    CurrentRatio,
+    /// `asset_turnover = revenue / total_assets`
+    AssetTurnover,
+    /// `<field>_prior`: the field's value in the symbol's previous reporting
+    /// period.
+    ///
+    /// With a [`REPORT_DATE`] column, periods are its distinct values, so a
+    /// daily as-of panel repeating each report gets the previous report's
+    /// value, taken from its last row. Without one every row is a period, and
+    /// [`derive_fields`] refuses data whose rows are too close together to be
+    /// periods.
+    Prior(String),
+    /// `market_return`: cap-weighted return of every symbol in the data.
+    ///
+    /// Each symbol's close-to-close return is weighted by its previous
+    /// row's `market_cap`; dates without any weighted return are null.
+    MarketReturn,
+}
+
+impl Derivation {
+    /// Derivation producing `column`, if it is derivable.
+    pub fn of(column: &str) -> Option<Self> {
+        match column {
+            "market_cap" => Some(Self::MarketCap),
+            "enterprise_value" => Some(Self::EnterpriseValue),
+            "working_capital" => Some(Self::WorkingCapital),
+            "gross_margin" => Some(Self::GrossMargin),
+            "operating_margin" => Some(Self::OperatingMargin),
+            "net_margin" => Some(Self::NetMargin),
+            "roa" => Some(Self::ReturnOnAssets),
+            "current_ratio" => Some(Self::CurrentRatio),
+            "asset_turnover" => Some(Self::AssetTurnover),
+            "market_return" => Some(Self::MarketReturn),
+            _ => column
+                .strip_suffix(PRIOR_SUFFIX)
+                .filter(|base| !base.is_empty() && !matches!(*base, "symbol" | "date"))
+                .map(|base| Self::Prior(base.to_string())),
+        }
+    }
+
+    /// Column this derivation adds.
+    pub fn column(&self) -> String {
+        match self {
+            Self::MarketCap => "market_cap".to_string(),
+            Self::EnterpriseValue => "enterprise_value".to_string(),
+            Self::WorkingCapital => "working_capital".to_string(),
+            Self::GrossMargin => "gross_margin".to_string(),
+            Self::OperatingMargin => "operating_margin".to_string(),
+            Self::NetMargin => "net_margin".to_string(),
+            Self::ReturnOnAssets => "roa".to_string(),
+            Self::CurrentRatio => "current_ratio".to_string(),
+            Self::AssetTurnover => "asset_turnover".to_string(),
+            Self::Prior(base) => format!("{base}{PRIOR_SUFFIX}"),
+            Self::MarketReturn => "market_return".to_string(),
+        }
+    }
+
+    /// Columns this derivation reads.
+    pub fn inputs(&self) -> Vec<&str> {
+        match self {
+            Self::MarketCap => vec!["close", "shares_outstanding"],
+            Self::EnterpriseValue => vec!["market_cap", "total_debt", "cash"],
+            Self::WorkingCapital | Self::CurrentRatio => {
+                vec!["current_assets", "current_liabilities"]
+            }
+            Self::GrossMargin => vec!["revenue", "cogs"],
+            Self::OperatingMargin => vec!["operating_income", "revenue"],
+            Self::NetMargin => vec!["net_income", "revenue"],
+            Self::ReturnOnAssets => vec!["net_income", "total_assets"],
+            Self::AssetTurnover => vec!["revenue", "total_assets"],
+            Self::Prior(base) => vec!["symbol", "date", base.as_str()],
+            Self::MarketReturn => vec!["symbol", "date", "close", "market_cap"],
+        }
+    }
+
+    /// Whether the derivation reads other rows, so needs data sorted by
+    /// `symbol` and `date`.
+    const fn is_sequential(&self) -> bool {
+        matches!(self, Self::Prior(_) | Self::MarketReturn)
+    }
+
+    /// `data` with this derivation's column added.
+    ///
+    /// `data` must be sorted by `symbol` and `date` for sequential
+    /// derivations, and `schema` is its schema before any derivation.
+    fn add(&self, data: LazyFrame, schema: &Schema) -> LazyFrame {
+        let name = self.column();
+        let ratio = |numerator: &str, denominator: &str| col(numerator) / col(denominator);
+        let expr = match self {
+            Self::MarketCap => col("close") * col("shares_outstanding"),
+            Self::EnterpriseValue => col("market_cap") + col("total_debt") - col("cash"),
+            Self::WorkingCapital => col("current_assets") - col("current_liabilities"),
+            Self::GrossMargin => (col("revenue") - col("cogs")) / col("revenue"),
+            Self::OperatingMargin => ratio("operating_income", "revenue"),
+            Self::NetMargin => ratio("net_income", "revenue"),
+            Self::ReturnOnAssets => ratio("net_income", "total_assets"),
+            Self::CurrentRatio => ratio("current_assets", "current_liabilities"),
+            Self::AssetTurnover => ratio("revenue", "total_assets"),
+            Self::Prior(base) if schema.contains(REPORT_DATE) => {
+                return Self::add_reported_prior(data, base, &name);
+            }
+            Self::Prior(base) => col(base.as_str()).shift(lit(1)).over([col("symbol")]),
+            Self::MarketReturn => return Self::add_market_return(data, &name),
+        };
+        data.with_column(expr.alias(name))
+    }
+
+    /// `data` with `base`'s value in the previous distinct [`REPORT_DATE`] of
+    /// each symbol added as `name`.
+    fn add_reported_prior(data: LazyFrame, base: &str, name: &str) -> LazyFrame {
+        let keys = [col("symbol"), col(REPORT_DATE)];
+        let periods = data
+            .clone()
+            .filter(col(REPORT_DATE).is_not_null())
+            .group_by_stable(keys.clone())
+            .agg([col(base).last()])
+            .sort(["symbol", REPORT_DATE], Default::default())
+            .select([
+                col("symbol"),
+                col(REPORT_DATE),
+                col(base).shift(lit(1)).over([col("symbol")]).alias(name),
+            ]);
+        data.join(periods, keys.clone(), keys, JoinArgs::new(JoinType::Left))
+            .sort(["symbol", "date"], Default::default())
+    }
+
+    /// `data` with the cap-weighted return of its universe added as `name`.
+    fn add_market_return(data: LazyFrame, name: &str) -> LazyFrame {
+        let previous = |column: &str| col(column).shift(lit(1)).over([col("symbol")]);
+        let total = col("__index_weight").sum().over([col("date")]);
+        data.with_columns([
+            (col("close") / previous("close") - lit(1.0)).alias("__index_return"),
+            previous("market_cap").alias("__index_weight"),
+        ])
+        .with_column(
+            when(col("__index_return").is_not_null())
+                .then(col("__index_weight"))
+                .otherwise(lit(NULL).cast(DataType::Float64))
+                .alias("__index_weight"),
+        )
+        .with_column(
+            when(total.clone().gt(lit(0.0)))
+                .then(
+                    (col("__index_return") * col("__index_weight"))
+                        .sum()
+                        .over([col("date")])
+                        / total,
+                )
+                .otherwise(lit(NULL).cast(DataType::Float64))
+                .alias(name),
+        )
+        .drop(["__index_return", "__index_weight"])
+    }
+}
+
+/// Derivations that fill the `columns` missing from `schema`, in the order
+/// they must run.
+///
+/// Missing inputs of a derivation are derived in turn where possible. Columns
+/// that are present, or that cannot be derived from what is, are skipped.
+pub fn plan_derivations(schema: &Schema, columns: &[&str]) -> Vec<Derivation> {
+    let mut plan = Vec::new();
+    for column in columns {
+        resolve(column, schema, &mut plan, &mut Vec::new());
+    }
+    plan
+}
+
+/// Whether `column` is present or derivable, appending the derivations it
+/// needs to `plan`.
+fn resolve(
+    column: &str,
+    schema: &Schema,
+    plan: &mut Vec<Derivation>,
+    path: &mut Vec<String>,
+) -> bool {
+    if schema.contains(column) || plan.iter().any(|d| d.column() == column) {
+        return true;
+    }
+    if path.iter().any(|c| c == column) {
+        return false;
+    }
+    let Some(derivation) = Derivation::of(column) else {
+        return false;
+    };
+
+    path.push(column.to_string());
+    let planned = plan.len();
+    let resolved = derivation
+        .inputs()
+        .into_iter()
+        .all(|input| resolve(input, schema, plan, path));
+    path.pop();
+
+    if resolved {
+        plan.push(derivation);
+    } else {
+        plan.truncate(planned);
+    }
+    resolved
+}
+
+/// `data` with every derivable column of `columns` it lacks filled in.
+///
+/// Existing columns are left untouched. When a previous-period value or
+/// `market_return` is derived the result is sorted by `symbol` and `date`.
+/// Deriving a previous-period value without a [`REPORT_DATE`] column fails if
+/// a symbol has rows fewer than 28 days apart.
+pub fn derive_fields(data: LazyFrame, columns: &[&str]) -> Result<LazyFrame> {
+    derive(data, columns, true)
+}
+
+/// [`derive_fields`] for input shared by several factors.
+///
+/// Previous-period values that [`derive_fields`] would refuse to derive are
+/// left missing instead, so only the factors reading them fail.
+pub(crate) fn derive_shared_fields(data: LazyFrame, columns: &[&str]) -> Result<LazyFrame> {
+    derive(data, columns, false)
+}
+
+/// `data` with the derivable `columns` it lacks filled in. Previous-period
+/// values over rows that are not reporting periods are an error when
+/// `strict`, and skipped otherwise.
+fn derive(mut data: LazyFrame, columns: &[&str], strict: bool) -> Result<LazyFrame> {
+    let schema = data.collect_schema()?;
+    let mut plan = plan_derivations(&schema, columns);
+    if plan.iter().any(Derivation::is_sequential) {
+        data = data.sort(["symbol", "date"], Default::default());
+    }
+    if !schema.contains(REPORT_DATE)
+        && let Some(prior) = plan.iter().find(|d| matches!(d, Derivation::Prior(_)))
+        && let Some(gap) = shortest_gap(&data)?
+        && gap < MIN_PERIOD_DAYS
+    {
+        if strict {
+            return Err(FactorError::Computation(format!(
+                "cannot derive {}: rows {gap} days apart are not reporting periods; \
+                 add a {REPORT_DATE} column",
+                prior.column()
+            )));
+        }
+        plan.retain(|d| !matches!(d, Derivation::Prior(_)));
+    }
+    Ok(plan
+        .iter()
+        .fold(data, |data, derivation| derivation.add(data, &schema)))
+}
+
+/// Fewest days between consecutive rows of any symbol in `data`, sorted by
+/// `symbol` and `date`, or `None` if no symbol has two rows.
+fn shortest_gap(data: &LazyFrame) -> Result<Option<i64>> {
+    let gaps = data
+        .clone()
+        .select([(date_column() - date_column().shift(lit(1)))
+            .over([col("symbol")])
+            .dt()
+            .total_days()
+            .min()
+            .alias("gap")])
+        .collect()?;
+    Ok(gaps.column("gap")?.i64()?.get(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FactorRegistry;
+    use approx::assert_relative_eq;
+    use chrono::NaiveDate;
+
+    fn values(df: &DataFrame, column: &str) -> Vec<Option<f64>> {
+        df.column(column)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_derives_inputs_first() {
+        let schema = Schema::from_iter([
+            Field::new("close".into(), DataType::Float64),
+            Field::new("shares_outstanding".into(), DataType::Float64),
+            Field::new("total_debt".into(), DataType::Float64),
+            Field::new("cash".into(), DataType::Float64),
+            Field::new("revenue".into(), DataType::Float64),
+        ]);
+        let plan = plan_derivations(
+            &schema,
+            &[
+                "enterprise_value",
+                "close",
+                "gross_margin",
+                "roa_prior",
+                "market_cap",
+            ],
+        );
+        assert_eq!(plan, [Derivation::MarketCap, Derivation::EnterpriseValue]);
+
+        assert_eq!(
+            Derivation::of("current_ratio_prior"),
+            Some(Derivation::Prior("current_ratio".to_string()))
+        );
+        assert_eq!(Derivation::of("date_prior"), None);
+        assert_eq!(
+            lookup("book_equity").unwrap().group,
+            FieldGroup::Fundamental
+        );
+    }
+
+    #[test]
+    fn test_derive_fields() {
+        let data = df![
+            "symbol" => ["B", "A", "B", "A"],
+            "date" => ["2024-01-03", "2024-01-03", "2024-01-02", "2024-01-02"],
+            "report_date" => ["2023-12-31", "2023-12-31", "2023-09-30", "2023-09-30"],
+            "close" => [22.0, 11.0, 20.0, 10.0],
+            "shares_outstanding" => [1.0, 3.0, 1.0, 3.0],
+            "revenue" => [100.0, 50.0, 80.0, 40.0],
+            "cogs" => [60.0, 20.0, 40.0, 30.0],
+            "gross_margin" => [9.0, 9.0, 9.0, 9.0],
+        ]
+        .unwrap()
+        .lazy();
+
+        let derived = derive_fields(
+            data,
+            &[
+                "market_return",
+                "gross_margin",
+                "gross_margin_prior",
+                "net_margin",
+            ],
+        )
+        .unwrap()
+        .collect()
+        .unwrap();
+
+        assert!(derived.column("net_margin").is_err());
+        assert_eq!(
+            values(&derived, "market_cap"),
+            [Some(30.0), Some(33.0), Some(20.0), Some(22.0)]
+        );
+        // Present columns are kept rather than derived from revenue and cogs
+        assert_eq!(
+            values(&derived, "gross_margin_prior"),
+            [None, Some(9.0), None, Some(9.0)]
+        );
+
+        // Day two: A returns 10% on a 30 cap, B returns 10% on a 20 cap
+        let market = values(&derived, "market_return");
+        assert_eq!(market[0], None);
+        assert_relative_eq!(market[1].unwrap(), 0.1, epsilon = 1e-12);
+        assert_relative_eq!(market[3].unwrap(), 0.1, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_prior_keys_on_report_date() {
+        let panel = df![
+            "symbol" => ["A", "A", "B", "A", "A"],
+            "date" => ["2024-03-29", "2024-04-01", "2024-04-02", "2024-04-02", "2024-04-03"],
+            "report_date" => ["2023-12-31", "2023-12-31", "2024-03-31", "2024-03-31", "2024-03-31"],
+            "roa" => [0.1, 0.1, 0.5, 0.2, 0.2],
+        ]
+        .unwrap()
+        .lazy();
+
+        let derived = derive_fields(panel.clone(), &["roa_prior"])
+            .unwrap()
+            .collect()
+            .unwrap();
+        // Daily rows repeating a report see the previous report, not yesterday
+        assert_eq!(
+            values(&derived, "roa_prior"),
+            [None, None, Some(0.1), Some(0.1), None]
+        );
+
+        let unkeyed = panel.drop(["report_date"]);
+        let Err(err) = derive_fields(unkeyed.clone(), &["roa_prior"]) else {
+            panic!("daily rows should not be taken as periods");
+        };
+        assert!(err.to_string().contains("report_date"));
+
+        // Shared input leaves the prior to the factors reading it
+        let shared = derive_shared_fields(unkeyed, &["roa_prior"])
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert!(shared.column("roa_prior").is_err());
+    }
+
+    #[test]
+    fn test_registry_derives_required_columns() {
+        let data = df![
+            "symbol" => ["A", "B", "A", "B"],
+            "date" => ["2023-12-31", "2023-12-31", "2024-03-31", "2024-03-31"],
+            "close" => [10.0, 20.0, 12.0, 18.0],
+            "shares_outstanding" => [100.0, 50.0, 100.0, 55.0],
+            "book_equity" => [500.0, 400.0, 600.0, 400.0],
+            "net_income" => [10.0, 5.0, 12.0, -2.0],
+            "operating_cash_flow" => [15.0, 4.0, 20.0, -5.0],
+            "total_assets" => [1000.0, 800.0, 1000.0, 800.0],
+            "long_term_debt" => [300.0, 200.0, 250.0, 260.0],
+            "current_assets" => [200.0, 150.0, 240.0, 140.0],
+            "current_liabilities" => [100.0, 100.0, 100.0, 100.0],
+            "revenue" => [400.0, 300.0, 450.0, 280.0],
+            "cogs" => [240.0, 180.0, 250.0, 190.0],
+        ]
+        .unwrap()
+        .lazy();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let registry = FactorRegistry::with_defaults()
+            .filter(|f| ["book_to_price", "piotroski_f_score"].contains(&f.name()))
+            .with_pipeline(crate::Pipeline::new());
+
+        assert!(registry.validate(&data).unwrap().iter().all(|v| v.is_ok()));
+        let exposures = registry.compute_all(&data, date).unwrap();
+
+        assert_eq!(
+            values(&exposures, "book_to_price"),
+            [Some(0.5), Some(400.0 / 990.0)]
+        );
+        let scores = exposures
+            .column("piotroski_f_score")
+            .unwrap()
+            .cast(&DataType::Int32)
+            .unwrap();
+        let scores: Vec<Option<i32>> = scores.i32().unwrap().into_iter().collect();
+        // A improves on every signal, B on none
+        assert_eq!(scores, [Some(9), Some(0)]);
+    }
+}
@@ -12,12 +12,14 @@
 
 use crate::{
     Result,
+    calendar::{DayCount, TradingCalendar},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{ConfigurableFactor, DataFrequency, Factor, after, on_or_before},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// Configuration for the Insider Trading factor.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,11 +27,20 @@ pub struct InsiderTradingConfig {
     /// Number of days to look back for insider trading activity.
     /// Default is 63 (approximately 3 months). Use 21 for 1-month, 126 for 6-month windows.
     pub lookback_days: usize,
+    /// How `lookback_days` is counted. Unset, it counts sessions of the
+    /// calendar attached with
+    /// [`FactorRegistry::with_calendar`](crate::FactorRegistry::with_calendar),
+    /// and calendar days without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day_count: Option<DayCount>,
 }
 
 impl Default for InsiderTradingConfig {
     fn default() -> Self {
-        Self { lookback_days: 63 }
+        Self {
+            lookback_days: 63,
+            day_count: None,
+        }
     }
 }
 
@@ -54,7 +65,9 @@ impl Default for InsiderTradingConfig {
 /// - `insider_sells`: Number or value of insider sell transactions
 ///
 /// # Lookback Period
-/// Configurable via `lookback_days` (default: 63 trading days)
+/// Configurable via `lookback_days` (default: 63 calendar days, or 63 trading
+/// days of a registry's calendar). Filings are sparse, so the window is
+/// measured in dates counted by `day_count` rather than in rows.
 ///
 /// # Usage Notes
 /// - Insider buys are stronger signals than sells
@@ -91,17 +104,32 @@ impl Factor for InsiderTrading {
         DataFrequency::Daily
     }
 
+    fn on_calendar(&self, calendar: &TradingCalendar) -> Option<Arc<dyn Factor>> {
+        let day_count = self
+            .config
+            .day_count
+            .clone()
+            .unwrap_or_else(|| DayCount::Trading(calendar.clone()));
+        Some(Arc::new(Self::with_config(InsiderTradingConfig {
+            day_count: Some(day_count),
+            ..self.config.clone()
+        })))
+    }
+
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        // Calculate the lookback date
-        let lookback_date = date
-            .checked_sub_days(chrono::Days::new(self.config.lookback_days as u64))
-            .unwrap_or(date);
+        // Filings after this date fall inside the window
+        let lookback_date = self
+            .config
+            .day_count
+            .clone()
+            .unwrap_or(DayCount::Calendar)
+            .window_start(date, self.config.lookback_days);
 
         // Filter data for the lookback window
         let filtered = data
             .clone()
-            .filter(col("date").gt(lit(lookback_date.format("%Y-%m-%d").to_string())))
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
+            .filter(after(lookback_date))
+            .filter(on_or_before(date))
             .collect()?;
 
         // Group by symbol and sum buys/sells over the period
@@ -386,4 +414,40 @@ mod tests {
         // Should return empty due to no activity (filtered out)
         assert_eq!(result.height(), 0);
     }
+
+    #[test]
+    fn test_insider_trading_typed_dates() {
+        let factor = InsiderTrading::default();
+
+        let dates: Vec<NaiveDate> = (0..10)
+            .map(|i| {
+                NaiveDate::from_ymd_opt(2024, 1, 1)
+                    .unwrap()
+                    .checked_add_days(chrono::Days::new(i * 3))
+                    .unwrap()
+            })
+            .collect();
+
+        let df = df! {
+            "symbol" => vec!["AAPL"; 10],
+            "date" => dates,
+            "insider_buys" => vec![1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0],
+            "insider_sells" => vec![0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        }
+        .unwrap();
+
+        let result = factor
+            .compute_raw(&df.lazy(), NaiveDate::from_ymd_opt(2024, 1, 31).unwrap())
+            .unwrap();
+
+        assert_eq!(result.height(), 1);
+        let net_buying = result
+            .column("insider_net_buying")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap();
+        assert!((net_buying - 0.6).abs() < 0.01);
+    }
 }
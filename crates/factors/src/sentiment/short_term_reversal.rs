@@ -12,8 +12,9 @@
 
 use crate::{
     Result,
+    adjustment::PriceAdjustment,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_or_before},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -87,12 +88,14 @@ impl Factor for ShortTermReversal {
         DataFrequency::Daily
     }
 
+    /// Measures returns, like the momentum factors.
+    fn price_adjustment(&self) -> PriceAdjustment {
+        PriceAdjustment::TotalReturn
+    }
+
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
         // Filter data up to the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.format("%Y-%m-%d").to_string())))
-            .collect()?;
+        let filtered = data.clone().filter(on_or_before(date)).collect()?;
 
         // Group by symbol and compute reversal signal
         let result = filtered
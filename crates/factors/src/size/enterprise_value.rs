@@ -6,13 +6,14 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    schema::derive_fields,
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
 
 /// Configuration for enterprise value factor.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct EnterpriseValueConfig {
     // Empty for now - size factors typically don't need configuration
     // This struct exists for consistency and future extensibility
@@ -85,11 +86,8 @@ impl Factor for EnterpriseValue {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let date_str = date.format("%Y-%m-%d").to_string();
-
-        let result = data
-            .clone()
-            .filter(col("date").eq(lit(date_str)))
+        let result = derive_fields(data.clone(), self.required_columns())?
+            .filter(on_date(date))
             .select([
                 col("symbol"),
                 col("date"),
@@ -6,13 +6,13 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
 
 /// Configuration for market capitalization factor.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct MarketCapConfig {
     // Empty for now - size factors typically don't need configuration
     // This struct exists for consistency and future extensibility
@@ -83,11 +83,9 @@ impl Factor for MarketCap {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let date_str = date.format("%Y-%m-%d").to_string();
-
         let result = data
             .clone()
-            .filter(col("date").eq(lit(date_str)))
+            .filter(on_date(date))
             .select([
                 col("symbol"),
                 col("date"),
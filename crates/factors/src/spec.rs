@@ -0,0 +1,530 @@
+//! Serializable registry specifications.
+//!
+//! A [`RegistrySpec`] lists built-in factors by name with optional parameter
+//! overrides and aliases, plus an optional registry-wide [`Pipeline`], input
+//! [`ColumnMapping`] and [`TradingCalendar`]. Specs round-trip through TOML or JSON so research
+//! configurations can be versioned alongside models:
+//!
+//! ```toml
+//! [columns]
+//! book_equity = "total_equity"
+//!
+//! [calendar]
+//! calendar = "nyse"
+//! holidays = ["2025-01-09"]
+//!
+//! [[factors]]
+//! factor = "medium_term_momentum"
+//!
+//! [[factors]]
+//! factor = "medium_term_momentum"
+//! alias = "momentum_3m"
+//! params = { lookback = 63 }
+//! ```
+//!
+//! Build a registry with
+//! [`FactorRegistry::from_spec`](crate::FactorRegistry::from_spec) and recover
+//! the spec with [`FactorRegistry::to_spec`](crate::FactorRegistry::to_spec).
+
+use crate::{
+    ConfigurableFactor, Factor, FactorError, Result, adjustment::PriceAdjustment,
+    calendar::TradingCalendar, features::Feature, growth, liquidity, mapping::ColumnMapping,
+    momentum, pipeline::Pipeline, quality, registry::FactorCategory, sentiment, size,
+    traits::DataFrequency, value, volatility,
+};
+use chrono::NaiveDate;
+use polars::prelude::*;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Specification of a factor registry.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RegistrySpec {
+    /// Pipeline applied to every factor, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<Pipeline>,
+    /// Input column mapping applied before any factor runs
+    #[serde(default, skip_serializing_if = "ColumnMapping::is_empty")]
+    pub columns: ColumnMapping,
+    /// Trading calendar daily inputs are aligned to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar: Option<TradingCalendar>,
+    /// Factors to register, in order
+    #[serde(default)]
+    pub factors: Vec<FactorSpec>,
+}
+
+impl RegistrySpec {
+    /// Parse a spec from TOML.
+    pub fn from_toml(input: &str) -> Result<Self> {
+        toml::from_str(input).map_err(|e| FactorError::InvalidSpec(e.to_string()))
+    }
+
+    /// Serialize the spec to TOML.
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).map_err(|e| FactorError::InvalidSpec(e.to_string()))
+    }
+
+    /// Parse a spec from JSON.
+    pub fn from_json(input: &str) -> Result<Self> {
+        serde_json::from_str(input).map_err(|e| FactorError::InvalidSpec(e.to_string()))
+    }
+
+    /// Serialize the spec to pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| FactorError::InvalidSpec(e.to_string()))
+    }
+}
+
+/// Specification of a single registered factor.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FactorSpec {
+    /// Built-in factor name, e.g. `medium_term_momentum`
+    pub factor: String,
+    /// Name to register the factor under, if different from `factor`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Overrides for fields of the factor's config; unset fields keep defaults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl FactorSpec {
+    /// Spec for a built-in factor with default parameters.
+    pub fn new(factor: impl Into<String>) -> Self {
+        Self {
+            factor: factor.into(),
+            alias: None,
+            params: None,
+        }
+    }
+
+    /// Register the factor under `alias`.
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Override config fields with `params`.
+    pub fn with_params(mut self, params: Value) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// Name the factor is registered under.
+    pub fn name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.factor)
+    }
+
+    /// Build the factor described by this spec.
+    pub fn build(&self) -> Result<Arc<dyn Factor>> {
+        let factor = builtin(&self.factor, self.params.as_ref())?;
+        Ok(match &self.alias {
+            Some(alias) if alias != &self.factor => Arc::new(AliasedFactor::new(alias, factor)),
+            _ => factor,
+        })
+    }
+}
+
+/// A factor registered under a different name.
+///
+/// Output columns are renamed from the wrapped factor's name to the alias, so
+/// several instances of the same factor can coexist in one registry.
+#[derive(Debug, Clone)]
+pub struct AliasedFactor {
+    alias: String,
+    factor: Arc<dyn Factor>,
+}
+
+impl AliasedFactor {
+    /// Register `factor` under `alias`.
+    pub fn new(alias: impl Into<String>, factor: Arc<dyn Factor>) -> Self {
+        Self {
+            alias: alias.into(),
+            factor,
+        }
+    }
+
+    /// The wrapped factor.
+    pub fn factor(&self) -> &dyn Factor {
+        self.factor.as_ref()
+    }
+
+    /// Rename the wrapped factor's output column to the alias.
+    fn rename(&self, mut df: DataFrame) -> Result<DataFrame> {
+        df.rename(self.factor.name(), self.alias.as_str().into())?;
+        // `rename` leaves the cached schema under the old name, which lazy
+        // queries on the result would otherwise resolve against
+        df.clear_schema();
+        Ok(df)
+    }
+}
+
+impl Factor for AliasedFactor {
+    fn name(&self) -> &str {
+        &self.alias
+    }
+
+    fn description(&self) -> &str {
+        self.factor.description()
+    }
+
+    fn category(&self) -> FactorCategory {
+        self.factor.category()
+    }
+
+    fn required_columns(&self) -> &[&str] {
+        self.factor.required_columns()
+    }
+
+    fn lookback(&self) -> usize {
+        self.factor.lookback()
+    }
+
+    fn frequency(&self) -> DataFrequency {
+        self.factor.frequency()
+    }
+
+    fn features(&self) -> Vec<Feature> {
+        self.factor.features()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.factor.dependencies()
+    }
+
+    fn attached_pipeline(&self) -> Option<&Pipeline> {
+        self.factor.attached_pipeline()
+    }
+
+    fn price_adjustment(&self) -> PriceAdjustment {
+        self.factor.price_adjustment()
+    }
+
+    fn on_calendar(&self, calendar: &TradingCalendar) -> Option<Arc<dyn Factor>> {
+        let factor = self.factor.on_calendar(calendar)?;
+        Some(Arc::new(Self::new(self.alias.clone(), factor)))
+    }
+
+    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        self.rename(self.factor.compute_raw(data, date)?)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        self.rename(self.factor.compute_range(data, start, end)?)
+    }
+}
+
+/// Constructor for a built-in factor from optional config overrides.
+type Builder = fn(Option<&Value>) -> Result<Arc<dyn Factor>>;
+
+macro_rules! builders {
+    ($($factor:ty),* $(,)?) => {
+        &[$(build::<$factor> as Builder),*]
+    };
+}
+
+/// Every built-in factor.
+const BUILDERS: &[Builder] = builders![
+    momentum::ShortTermMomentum,
+    momentum::MediumTermMomentum,
+    momentum::LongTermMomentum,
+    momentum::MomentumAcceleration,
+    momentum::High52Week,
+    momentum::MACrossover,
+    momentum::MeanReversion,
+    momentum::PriceVolumeTrend,
+    momentum::ResidualMomentum,
+    momentum::RSI,
+    momentum::TimeSeriesMomentum,
+    momentum::VolatilityBreakout,
+    momentum::VolumeMomentum,
+    value::BookToPrice,
+    value::DividendYield,
+    value::EarningsYield,
+    value::EbitdaToEv,
+    value::EnterpriseYield,
+    value::FcfYield,
+    value::SalesToPrice,
+    quality::AccrualsQuality,
+    quality::AltmanZ,
+    quality::AssetTurnover,
+    quality::CashflowQuality,
+    quality::CurrentRatio,
+    quality::EarningsPersistence,
+    quality::EarningsSmoothness,
+    quality::GrossProfitability,
+    quality::InterestCoverage,
+    quality::Leverage,
+    quality::ProfitMargin,
+    quality::Piotroski,
+    quality::QuickRatio,
+    quality::Roa,
+    quality::Roe,
+    quality::Roic,
+    size::EnterpriseValue,
+    size::LogMarketCap,
+    size::MarketCap,
+    volatility::MarketBeta,
+    volatility::DownsideBeta,
+    volatility::HistoricalVolatility,
+    volatility::IdiosyncraticVolatility,
+    volatility::IvRvSpread,
+    volatility::ReturnKurtosis,
+    volatility::MaxDrawdown,
+    volatility::ParkinsonVolatility,
+    volatility::ReturnSkewness,
+    volatility::ValueAtRisk,
+    growth::AssetGrowth,
+    growth::BookEquityGrowth,
+    growth::EarningsGrowth,
+    growth::EmployeeGrowth,
+    growth::SalesGrowth,
+    liquidity::AmihudIlliquidity,
+    liquidity::BidAskSpread,
+    liquidity::CorwinSchultz,
+    liquidity::DaysToCover,
+    liquidity::DollarVolume,
+    liquidity::KyleLambda,
+    liquidity::RelativeVolume,
+    liquidity::RollMeasure,
+    liquidity::ShortInterestRatio,
+    liquidity::TurnoverRatio,
+    sentiment::AnalystRevisions,
+    sentiment::EarningsSurprise,
+    sentiment::InsiderTrading,
+    sentiment::InstitutionalOwnership,
+    sentiment::ShortTermReversal,
+];
+
+/// Every built-in factor with its default configuration.
+pub fn builtins() -> Vec<Arc<dyn Factor>> {
+    BUILDERS
+        .iter()
+        .filter_map(|builder| builder(None).ok())
+        .collect()
+}
+
+/// Build the built-in factor named `factor`, overriding config fields with
+/// `params`.
+///
+/// Returns [`FactorError::NotFound`] for an unknown name and
+/// [`FactorError::InvalidSpec`] if `params` names a field the factor's config
+/// does not have or has a value of the wrong type.
+pub fn builtin(factor: &str, params: Option<&Value>) -> Result<Arc<dyn Factor>> {
+    let builder = BUILDERS
+        .iter()
+        .find(|builder| builder(None).is_ok_and(|f| f.name() == factor))
+        .ok_or_else(|| FactorError::NotFound(factor.to_string()))?;
+
+    builder(params).map_err(|e| match e {
+        FactorError::InvalidSpec(msg) => FactorError::InvalidSpec(format!("{factor}: {msg}")),
+        other => other,
+    })
+}
+
+/// Construct `F` from its default config overlaid with `params`.
+fn build<F>(params: Option<&Value>) -> Result<Arc<dyn Factor>>
+where
+    F: ConfigurableFactor + 'static,
+    F::Config: Serialize + DeserializeOwned,
+{
+    let invalid = |e: serde_json::Error| FactorError::InvalidSpec(e.to_string());
+
+    let config = match params {
+        None => F::Config::default(),
+        Some(params) => {
+            let mut config = serde_json::to_value(F::Config::default()).map_err(invalid)?;
+            overlay(&mut config, params)?;
+            serde_json::from_value(config).map_err(invalid)?
+        }
+    };
+
+    Ok(Arc::new(F::with_config(config)))
+}
+
+/// Overlay the fields of `params` onto a serialized default config.
+fn overlay(config: &mut Value, params: &Value) -> Result<()> {
+    let Value::Object(overrides) = params else {
+        return Err(FactorError::InvalidSpec(format!(
+            "params must be a table, got {params}"
+        )));
+    };
+
+    for (key, value) in overrides {
+        match config
+            .as_object_mut()
+            .and_then(|fields| fields.get_mut(key))
+        {
+            Some(field) => *field = value.clone(),
+            None => {
+                return Err(FactorError::InvalidSpec(format!(
+                    "unknown parameter `{key}`"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FactorRegistry, pipeline::Transform};
+
+    const SPEC: &str = r#"
+[pipeline]
+transforms = [
+    { type = "winsorize", lower = 0.01, upper = 0.99 },
+    { type = "standardize", scheme = { method = "cross_sectional" } },
+]
+
+[[factors]]
+factor = "medium_term_momentum"
+
+[[factors]]
+factor = "medium_term_momentum"
+alias = "momentum_3m"
+params = { lookback = 63 }
+
+[[factors]]
+factor = "book_to_price"
+"#;
+
+    #[test]
+    fn test_builtins_have_unique_names() {
+        let mut names: Vec<String> = builtins().iter().map(|f| f.name().to_string()).collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+        assert_eq!(count, BUILDERS.len());
+    }
+
+    #[test]
+    fn test_from_spec_with_alias_and_params() {
+        let spec = RegistrySpec::from_toml(SPEC).unwrap();
+        let registry = FactorRegistry::from_spec(&spec).unwrap();
+
+        assert_eq!(registry.len(), 3);
+        assert_eq!(
+            registry.get("medium_term_momentum").unwrap().lookback(),
+            126
+        );
+        let alias = registry.get("momentum_3m").unwrap();
+        assert_eq!(alias.lookback(), 63);
+        assert_eq!(alias.category(), FactorCategory::Momentum);
+        assert!(registry.pipeline().is_some());
+    }
+
+    #[test]
+    fn test_alias_runs_through_pipelines() {
+        let data = df![
+            "symbol" => ["A", "B", "C"],
+            "date" => ["2024-03-31"; 3],
+            "book_equity" => [1.0, 3.0, 2.0],
+            "market_cap" => [1.0; 3],
+        ]
+        .unwrap()
+        .lazy();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let alias = AliasedFactor::new("value", Arc::new(value::BookToPrice::default()));
+
+        let ranked = alias
+            .compute_pipeline(&data, date, &Pipeline::new().then(Transform::Rank))
+            .unwrap();
+        let ranks: Vec<f64> = ranked
+            .column("value")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(ranks, [1.0 / 3.0, 1.0, 2.0 / 3.0]);
+    }
+
+    #[test]
+    fn test_spec_round_trip() {
+        let spec = RegistrySpec::from_toml(SPEC).unwrap();
+        let registry = FactorRegistry::from_spec(&spec).unwrap();
+        let exported = registry.to_spec().unwrap();
+
+        assert_eq!(exported.factors.len(), 3);
+        assert_eq!(exported.pipeline, spec.pipeline);
+        assert_eq!(
+            RegistrySpec::from_toml(&exported.to_toml().unwrap()).unwrap(),
+            exported
+        );
+        assert_eq!(
+            RegistrySpec::from_json(&exported.to_json().unwrap()).unwrap(),
+            exported
+        );
+
+        let rebuilt = FactorRegistry::from_spec(&exported).unwrap();
+        let mut names = rebuilt.names();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            ["book_to_price", "medium_term_momentum", "momentum_3m"]
+        );
+        assert_eq!(rebuilt.get("momentum_3m").unwrap().lookback(), 63);
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        let unknown = RegistrySpec {
+            pipeline: None,
+            columns: ColumnMapping::new(),
+            calendar: None,
+            factors: vec![FactorSpec::new("no_such_factor")],
+        };
+        assert!(matches!(
+            FactorRegistry::from_spec(&unknown),
+            Err(FactorError::NotFound(_))
+        ));
+
+        let typo = RegistrySpec {
+            pipeline: None,
+            columns: ColumnMapping::new(),
+            calendar: None,
+            factors: vec![
+                FactorSpec::new("medium_term_momentum")
+                    .with_params(serde_json::json!({ "lookbak": 63 })),
+            ],
+        };
+        assert!(matches!(
+            FactorRegistry::from_spec(&typo),
+            Err(FactorError::InvalidSpec(_))
+        ));
+
+        let duplicate = RegistrySpec {
+            pipeline: None,
+            columns: ColumnMapping::new(),
+            calendar: None,
+            factors: vec![FactorSpec::new("roe"), FactorSpec::new("roe")],
+        };
+        assert!(matches!(
+            FactorRegistry::from_spec(&duplicate),
+            Err(FactorError::InvalidSpec(_))
+        ));
+
+        // A factor registered directly has no recoverable configuration
+        let mut registry =
+            FactorRegistry::from_spec(&RegistrySpec::from_toml(SPEC).unwrap()).unwrap();
+        registry.register(Arc::new(AliasedFactor::new(
+            "momentum_3m",
+            Arc::new(momentum::MediumTermMomentum::default()),
+        )));
+        assert!(matches!(
+            registry.to_spec(),
+            Err(FactorError::InvalidSpec(_))
+        ));
+    }
+}
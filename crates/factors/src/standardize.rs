@@ -3,8 +3,76 @@
 //! Cross-sectional standardization is essential for comparing factor exposures
 //! across different securities and time periods.
 
-use crate::Result;
+use crate::{
+    Result,
+    cross_section::{fit_residuals, float_columns, rows_by_date},
+};
+use ndarray::{Array1, Array2};
 use polars::prelude::*;
+use std::collections::BTreeMap;
+
+/// Standardization scheme applied to raw factor values.
+///
+/// Selected per factor via [`Factor::compute_with`](crate::Factor::compute_with).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Standardization {
+    /// Z-score across all securities on each date (see [`cross_sectional_standardize`])
+    #[default]
+    CrossSectional,
+    /// Median/MAD z-score on each date (see [`robust_standardize`])
+    Robust,
+    /// Cap-weighted mean zero, equal-weighted unit std on each date
+    /// (see [`cap_weighted_standardize`])
+    CapWeighted {
+        /// Weight column, e.g. `market_cap` or its square root
+        weight: String,
+    },
+    /// Z-score within each group on each date (see [`group_standardize`])
+    Group {
+        /// Grouping column, e.g. `sector` or `industry`
+        column: String,
+    },
+    /// Regression residual against controls, then z-scored (see [`neutralize`])
+    Neutralize {
+        /// Categorical columns expanded into dummy exposures, e.g. `industry`
+        groups: Vec<String>,
+        /// Numeric control exposures, e.g. `log_market_cap`
+        controls: Vec<String>,
+    },
+}
+
+impl Standardization {
+    /// Input columns needed beyond `symbol`, `date` and the factor values.
+    pub fn required_columns(&self) -> Vec<&str> {
+        match self {
+            Self::CrossSectional | Self::Robust => Vec::new(),
+            Self::CapWeighted { weight } => vec![weight.as_str()],
+            Self::Group { column } => vec![column.as_str()],
+            Self::Neutralize { groups, controls } => {
+                groups.iter().chain(controls).map(String::as_str).collect()
+            }
+        }
+    }
+
+    /// Apply the scheme to `value_column` of `df`.
+    ///
+    /// `df` must contain the columns listed by [`Self::required_columns`].
+    pub fn apply(&self, df: &DataFrame, value_column: &str) -> Result<DataFrame> {
+        match self {
+            Self::CrossSectional => cross_sectional_standardize(df, value_column),
+            Self::Robust => robust_standardize(df, value_column),
+            Self::CapWeighted { weight } => cap_weighted_standardize(df, value_column, weight),
+            Self::Group { column } => group_standardize(df, value_column, column),
+            Self::Neutralize { groups, controls } => {
+                let groups: Vec<&str> = groups.iter().map(String::as_str).collect();
+                let controls: Vec<&str> = controls.iter().map(String::as_str).collect();
+                let residuals = neutralize(df, value_column, &groups, &controls)?;
+                cross_sectional_standardize(&residuals, value_column)
+            }
+        }
+    }
+}
 
 /// Cross-sectional z-score standardization.
 ///
@@ -41,6 +109,182 @@ pub fn cross_sectional_standardize(df: &DataFrame, value_column: &str) -> Result
     Ok(result)
 }
 
+/// Cap-weighted cross-sectional standardization.
+///
+/// Computes z_i = (x_i - sum(w x) / sum(w)) / std(x) for each date, the
+/// Barra-style convention where the weighted mean of the estimation universe is
+/// zero and the equal-weighted standard deviation is one. Rows with a null
+/// value or weight do not contribute to the weighted mean.
+///
+/// # Arguments
+///
+/// * `df` - DataFrame with columns: `symbol`, `date`, `value_column` and `weight_column`
+/// * `value_column` - Name of the column to standardize
+/// * `weight_column` - Name of the weight column, e.g. `market_cap` or sqrt cap
+///
+/// # Returns
+///
+/// DataFrame with the value column replaced by its standardized scores.
+pub fn cap_weighted_standardize(
+    df: &DataFrame,
+    value_column: &str,
+    weight_column: &str,
+) -> Result<DataFrame> {
+    let weight = when(col(value_column).is_not_null())
+        .then(col(weight_column))
+        .otherwise(lit(NULL));
+
+    let result = df
+        .clone()
+        .lazy()
+        .with_column(
+            ((col(value_column) * col(weight_column)).sum() / weight.sum())
+                .over([col("date")])
+                .alias("weighted_mean"),
+        )
+        .with_column(
+            col(value_column)
+                .std(1)
+                .over([col("date")])
+                .alias("std_dev"),
+        )
+        .with_column(
+            ((col(value_column) - col("weighted_mean")) / col("std_dev")).alias(value_column),
+        )
+        .drop(["weighted_mean", "std_dev"])
+        .collect()?;
+
+    Ok(result)
+}
+
+/// Group-wise cross-sectional z-score standardization.
+///
+/// Like [`cross_sectional_standardize`], but the mean and standard deviation
+/// are taken within each `group_column` value (e.g. GICS sector) on each date,
+/// so the result is neutral within groups.
+///
+/// # Arguments
+///
+/// * `df` - DataFrame with columns: `symbol`, `date`, `group_column` and `value_column`
+/// * `value_column` - Name of the column to standardize
+/// * `group_column` - Name of the grouping column
+///
+/// # Returns
+///
+/// DataFrame with the value column replaced by within-group z-scores.
+pub fn group_standardize(
+    df: &DataFrame,
+    value_column: &str,
+    group_column: &str,
+) -> Result<DataFrame> {
+    let partition = [col("date"), col(group_column)];
+    let result = df
+        .clone()
+        .lazy()
+        .with_column(
+            ((col(value_column) - col(value_column).mean().over(partition.clone()))
+                / col(value_column).std(1).over(partition))
+            .alias(value_column),
+        )
+        .collect()?;
+
+    Ok(result)
+}
+
+/// Regression-based neutralization against control exposures.
+///
+/// On each date, regresses `value_column` on an intercept, one dummy per level
+/// of each `groups` column (less one reference level per group) and the
+/// numeric `controls`, and replaces the values with the regression residuals.
+/// The residuals are uncorrelated with every control on every date.
+///
+/// Rows with a null value, group or control get a null residual, as do all rows
+/// on a date with no more observations than regressors.
+///
+/// # Arguments
+///
+/// * `df` - DataFrame with columns: `date`, `value_column`, `groups` and `controls`
+/// * `value_column` - Name of the column to neutralize
+/// * `groups` - Categorical columns expanded into dummies, e.g. `industry`
+/// * `controls` - Numeric control columns, e.g. `log_market_cap`
+///
+/// # Returns
+///
+/// DataFrame with the value column replaced by residuals.
+pub fn neutralize(
+    df: &DataFrame,
+    value_column: &str,
+    groups: &[&str],
+    controls: &[&str],
+) -> Result<DataFrame> {
+    let values = float_columns(df, &[value_column])?;
+    let control_values = float_columns(df, controls)?;
+    let group_values = groups
+        .iter()
+        .map(|&name| df.column(name)?.cast(&DataType::String))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let group_values = group_values
+        .iter()
+        .map(|column| column.str())
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    // Complete rows, grouped by date.
+    let rows_by_date = rows_by_date(df, |row| {
+        values[0].get(row).is_some()
+            && group_values.iter().all(|g| g.get(row).is_some())
+            && control_values.iter().all(|c| c.get(row).is_some())
+    })?;
+
+    let mut residuals: Vec<Option<f64>> = vec![None; df.height()];
+    for rows in rows_by_date.values() {
+        // Dummy columns for every non-reference level of each group.
+        let mut dummies: Vec<BTreeMap<&str, usize>> = Vec::new();
+        let mut n_cols = 1;
+        for group in &group_values {
+            let mut levels: Vec<&str> = rows.iter().filter_map(|&row| group.get(row)).collect();
+            levels.sort_unstable();
+            levels.dedup();
+            let index: BTreeMap<&str, usize> = levels
+                .into_iter()
+                .skip(1)
+                .enumerate()
+                .map(|(i, level)| (level, n_cols + i))
+                .collect();
+            n_cols += index.len();
+            dummies.push(index);
+        }
+        let first_control = n_cols;
+        n_cols += control_values.len();
+
+        if rows.len() <= n_cols {
+            continue;
+        }
+
+        let mut x = Array2::zeros((rows.len(), n_cols));
+        let mut y = Array1::zeros(rows.len());
+        for (i, &row) in rows.iter().enumerate() {
+            x[[i, 0]] = 1.0;
+            for (group, index) in group_values.iter().zip(&dummies) {
+                if let Some(&j) = group.get(row).and_then(|level| index.get(level)) {
+                    x[[i, j]] = 1.0;
+                }
+            }
+            for (j, control) in control_values.iter().enumerate() {
+                x[[i, first_control + j]] = control.get(row).unwrap_or_default();
+            }
+            y[i] = values[0].get(row).unwrap_or_default();
+        }
+
+        let weights = Array1::ones(rows.len());
+        fit_residuals(rows, &y, &x, &weights, "neutralization", &mut residuals)?;
+    }
+
+    let mut result = df.clone();
+    result.with_column(Column::new(value_column.into(), residuals))?;
+
+    Ok(result)
+}
+
 /// Winsorization for outlier handling.
 ///
 /// Clips extreme values to specified percentile bounds. This reduces the
@@ -91,6 +335,172 @@ pub fn winsorize(
     Ok(result)
 }
 
+/// Sigma-based winsorization.
+///
+/// Clips values to `mean ± n_sigma * std` on each date.
+///
+/// # Arguments
+///
+/// * `df` - DataFrame with the value column
+/// * `value_column` - Name of the column to clip
+/// * `n_sigma` - Number of standard deviations to allow (e.g., 3.0)
+///
+/// # Returns
+///
+/// DataFrame with values beyond the bounds clipped to them.
+pub fn sigma_clip(df: &DataFrame, value_column: &str, n_sigma: f64) -> Result<DataFrame> {
+    let center = col(value_column).mean().over([col("date")]);
+    let spread = col(value_column).std(1).over([col("date")]) * lit(n_sigma);
+    clip_around(df, value_column, center, spread)
+}
+
+/// MAD-based clipping.
+///
+/// Clips values to `median ± n_mad * 1.4826 * MAD` on each date, a robust
+/// alternative to [`sigma_clip`] that is not itself inflated by the outliers.
+///
+/// # Arguments
+///
+/// * `df` - DataFrame with the value column
+/// * `value_column` - Name of the column to clip
+/// * `n_mad` - Number of scaled MADs to allow (e.g., 3.0)
+///
+/// # Returns
+///
+/// DataFrame with values beyond the bounds clipped to them.
+pub fn mad_clip(df: &DataFrame, value_column: &str, n_mad: f64) -> Result<DataFrame> {
+    const MAD_SCALE: f64 = 1.4826;
+
+    let with_median = df
+        .clone()
+        .lazy()
+        .with_column(
+            col(value_column)
+                .median()
+                .over([col("date")])
+                .alias("median_val"),
+        )
+        .collect()?;
+    let mad = (col(value_column) - col("median_val"))
+        .abs()
+        .median()
+        .over([col("date")]);
+
+    let result = clip_around(
+        &with_median,
+        value_column,
+        col("median_val"),
+        mad * lit(n_mad * MAD_SCALE),
+    )?;
+    Ok(result.drop("median_val")?)
+}
+
+/// Clip a column to `center ± spread`.
+fn clip_around(
+    df: &DataFrame,
+    value_column: &str,
+    center: Expr,
+    spread: Expr,
+) -> Result<DataFrame> {
+    let result = df
+        .clone()
+        .lazy()
+        .with_column((center.clone() - spread.clone()).alias("lower_bound"))
+        .with_column((center + spread).alias("upper_bound"))
+        .with_column(
+            when(col(value_column).lt(col("lower_bound")))
+                .then(col("lower_bound"))
+                .when(col(value_column).gt(col("upper_bound")))
+                .then(col("upper_bound"))
+                .otherwise(col(value_column))
+                .alias(value_column),
+        )
+        .drop(["lower_bound", "upper_bound"])
+        .collect()?;
+
+    Ok(result)
+}
+
+/// Cross-sectional percentile rank transform.
+///
+/// Replaces each value with its average rank divided by the number of
+/// non-null values on its date, giving scores in `(0, 1]`. Nulls stay null.
+///
+/// # Arguments
+///
+/// * `df` - DataFrame with the value column
+/// * `value_column` - Name of the column to rank
+///
+/// # Returns
+///
+/// DataFrame with the value column replaced by percentile ranks.
+pub fn rank_transform(df: &DataFrame, value_column: &str) -> Result<DataFrame> {
+    let rank = col(value_column)
+        .rank(
+            RankOptions {
+                method: RankMethod::Average,
+                descending: false,
+            },
+            None,
+        )
+        .cast(DataType::Float64);
+
+    let result = df
+        .clone()
+        .lazy()
+        .with_column(
+            (rank / col(value_column).count().cast(DataType::Float64))
+                .over([col("date")])
+                .alias(value_column),
+        )
+        .collect()?;
+
+    Ok(result)
+}
+
+/// Strategy for filling missing factor values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FillStrategy {
+    /// Replace nulls with zero (the mean of a standardized factor)
+    Zero,
+    /// Replace nulls with the cross-sectional mean on each date
+    Mean,
+    /// Replace nulls with the cross-sectional median on each date
+    Median,
+}
+
+/// Fill missing values cross-sectionally.
+///
+/// # Arguments
+///
+/// * `df` - DataFrame with the value column
+/// * `value_column` - Name of the column to fill
+/// * `strategy` - How to choose the fill value
+///
+/// # Returns
+///
+/// DataFrame with nulls in the value column replaced.
+pub fn fill_missing(
+    df: &DataFrame,
+    value_column: &str,
+    strategy: FillStrategy,
+) -> Result<DataFrame> {
+    let fill = match strategy {
+        FillStrategy::Zero => lit(0.0),
+        FillStrategy::Mean => col(value_column).mean().over([col("date")]),
+        FillStrategy::Median => col(value_column).median().over([col("date")]),
+    };
+
+    let result = df
+        .clone()
+        .lazy()
+        .with_column(col(value_column).fill_null(fill).alias(value_column))
+        .collect()?;
+
+    Ok(result)
+}
+
 /// MAD-based robust standardization.
 ///
 /// Computes z_i = (x_i - median(x)) / MAD(x) where MAD is the median absolute
@@ -154,4 +564,158 @@ mod tests {
         assert!((values.get(1).unwrap() - 0.0).abs() < 0.01);
         assert!((values.get(2).unwrap() - 1.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_cap_weighted_standardize() {
+        let df = df![
+            "symbol" => ["A", "B", "C"],
+            "date" => ["2024-01-01"; 3],
+            "market_cap" => [3.0, 1.0, 0.0],
+            "value" => [1.0, 2.0, 3.0]
+        ]
+        .unwrap();
+
+        let result = cap_weighted_standardize(&df, "value", "market_cap").unwrap();
+        let values: Vec<f64> = result
+            .column("value")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+
+        // Weighted mean = (3*1 + 1*2) / 4 = 1.25, equal-weighted std = 1.
+        for (actual, expected) in values.iter().zip([-0.25, 0.75, 1.75]) {
+            assert!((actual - expected).abs() < 1e-10);
+        }
+        let weighted: f64 = values.iter().zip([3.0, 1.0, 0.0]).map(|(v, w)| v * w).sum();
+        assert!(weighted.abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_clip_rank_and_fill() {
+        let df = df![
+            "symbol" => ["A", "B", "C", "D", "E"],
+            "date" => ["2024-01-01"; 5],
+            "value" => [Some(1.0), Some(2.0), Some(3.0), None, Some(100.0)]
+        ]
+        .unwrap();
+
+        // Median 2.5, MAD 1.0: bounds are 2.5 ± 1.4826.
+        let clipped = mad_clip(&df, "value", 1.0).unwrap();
+        let clipped = clipped.column("value").unwrap().f64().unwrap();
+        assert!((clipped.get(4).unwrap() - (2.5 + 1.4826)).abs() < 1e-10);
+        assert_eq!(clipped.get(1), Some(2.0));
+
+        let ranked = rank_transform(&df, "value").unwrap();
+        let ranked = ranked.column("value").unwrap().f64().unwrap();
+        assert!((ranked.get(0).unwrap() - 0.25).abs() < 1e-12);
+        assert_eq!(ranked.get(3), None);
+        assert!((ranked.get(4).unwrap() - 1.0).abs() < 1e-12);
+
+        let filled = fill_missing(&df, "value", FillStrategy::Median).unwrap();
+        assert_eq!(
+            filled.column("value").unwrap().f64().unwrap().get(3),
+            Some(2.5)
+        );
+    }
+
+    #[test]
+    fn test_group_standardize() {
+        let df = df![
+            "symbol" => ["A", "B", "C", "D"],
+            "date" => ["2024-01-01"; 4],
+            "sector" => ["Tech", "Tech", "Energy", "Energy"],
+            "value" => [1.0, 3.0, 10.0, 30.0]
+        ]
+        .unwrap();
+
+        let result = group_standardize(&df, "value", "sector").unwrap();
+        let values: Vec<f64> = result
+            .column("value")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+
+        // Each sector has two names, so z-scores are -1/sqrt(2) and 1/sqrt(2).
+        let z = std::f64::consts::FRAC_1_SQRT_2;
+        for (actual, expected) in values.iter().zip([-z, z, -z, z]) {
+            assert!((actual - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_neutralize_removes_group_and_control_exposure() {
+        let df = df![
+            "symbol" => ["A", "B", "C", "D", "E", "F"],
+            "date" => ["2024-01-01"; 6],
+            "sector" => ["Tech", "Tech", "Tech", "Energy", "Energy", "Energy"],
+            "log_market_cap" => [1.0, 2.0, 3.0, 1.5, 2.5, 4.0],
+            "value" => [5.0, 7.5, 8.0, 1.0, 3.0, 2.0]
+        ]
+        .unwrap();
+
+        let result = neutralize(&df, "value", &["sector"], &["log_market_cap"]).unwrap();
+        let residuals: Vec<f64> = result
+            .column("value")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        let sizes = [1.0, 2.0, 3.0, 1.5, 2.5, 4.0];
+
+        let tech: f64 = residuals[..3].iter().sum();
+        let energy: f64 = residuals[3..].iter().sum();
+        let size: f64 = residuals.iter().zip(sizes).map(|(r, s)| r * s).sum();
+        assert!(tech.abs() < 1e-10);
+        assert!(energy.abs() < 1e-10);
+        assert!(size.abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_compute_with_selects_scheme() {
+        use crate::{Factor, value::BookToPrice};
+        use chrono::NaiveDate;
+
+        let data = df![
+            "symbol" => ["A", "B", "C", "D"],
+            "date" => ["2024-03-31"; 4],
+            "sector" => ["Tech", "Tech", "Energy", "Energy"],
+            "book_equity" => [1.0, 3.0, 10.0, 30.0],
+            "market_cap" => [1.0; 4]
+        ]
+        .unwrap()
+        .lazy();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let scheme = Standardization::Group {
+            column: "sector".to_string(),
+        };
+
+        let result = BookToPrice::default()
+            .compute_with(&data, date, &scheme)
+            .unwrap();
+        assert_eq!(
+            result.get_column_names(),
+            ["symbol", "date", "book_to_price"]
+        );
+
+        let expected = group_standardize(
+            &data
+                .collect()
+                .unwrap()
+                .select(["symbol", "date", "sector", "book_equity"])
+                .unwrap(),
+            "book_equity",
+            "sector",
+        )
+        .unwrap();
+        let actual = result.column("book_to_price").unwrap().f64().unwrap();
+        let expected = expected.column("book_equity").unwrap().f64().unwrap();
+        for (a, e) in actual.into_no_null_iter().zip(expected.into_no_null_iter()) {
+            assert!((a - e).abs() < 1e-10);
+        }
+    }
 }
@@ -2,11 +2,25 @@
 //!
 //! All factors implement the [`Factor`] trait, which provides a unified interface
 //! for computing factor exposures from market data.
+//!
+//! Input data is expected to carry a polars `Date` (or `Datetime`) `date` column.
+//! ISO-8601 `YYYY-MM-DD` string dates are an opt-in compatibility mode behind
+//! the `string-dates` feature: the shared date predicates in this module then
+//! parse them strictly, so every factor selects the same rows regardless of the
+//! column's representation and a malformed date is an error.
 
-use crate::{FactorCategory, Result};
+use crate::{
+    FactorCategory, FactorError, Result,
+    adjustment::PriceAdjustment,
+    calendar::TradingCalendar,
+    features::Feature,
+    pipeline::{Pipeline, PipelineFactor},
+    standardize::Standardization,
+};
 use chrono::NaiveDate;
 use derive_more::Display;
 use polars::prelude::*;
+use std::sync::Arc;
 
 /// Data frequency for factor computation.
 #[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash)]
@@ -36,18 +50,83 @@ pub trait Factor: Send + Sync + std::fmt::Debug {
 
     /// Columns required in the input DataFrame.
     ///
-    /// The caller must ensure these columns exist before calling `compute`.
+    /// The caller must ensure these columns exist before calling `compute`;
+    /// [`Factor::validate`] checks this up front.
     fn required_columns(&self) -> &[&str];
 
     /// Number of lookback periods needed for computation.
     ///
-    /// For daily factors, this is trading days. For quarterly factors,
-    /// this is the number of quarters.
+    /// For daily factors, this is trading days, counted as rows; a registry
+    /// with a [`TradingCalendar`](crate::TradingCalendar) aligns inputs so rows
+    /// and sessions match. For quarterly factors, this is the number of
+    /// quarters.
     fn lookback(&self) -> usize;
 
     /// Data frequency required for this factor.
     fn frequency(&self) -> DataFrequency;
 
+    /// Check that `data` satisfies this factor's requirements.
+    ///
+    /// Verifies required columns and their dtypes, and that at least one symbol
+    /// has [`Factor::lookback`] observations. See [`crate::validation`].
+    fn validate(&self, data: &LazyFrame) -> Result<()> {
+        crate::validation::validate(self, data)
+    }
+
+    /// Derived inputs this factor reads, such as daily returns.
+    ///
+    /// [`FactorRegistry`](crate::FactorRegistry) computes the union of these
+    /// once per run and shares them across factors. See [`crate::features`].
+    fn features(&self) -> Vec<Feature> {
+        Vec::new()
+    }
+
+    /// Names of other factors whose scores this factor reads.
+    ///
+    /// [`FactorRegistry`](crate::FactorRegistry) computes registered
+    /// dependencies first and passes their [`Factor::compute`] scores (not the
+    /// registry pipeline's) to this factor as columns named after them. See
+    /// [`crate::composite::CompositeFactor`].
+    fn dependencies(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Pipeline attached with [`Factor::with_pipeline`], if any.
+    ///
+    /// [`FactorRegistry`](crate::FactorRegistry) processes this factor's raw
+    /// values with it in preference to the registry-wide pipeline.
+    fn attached_pipeline(&self) -> Option<&Pipeline> {
+        None
+    }
+
+    /// Corporate action adjustment this factor wants on its price and volume
+    /// inputs.
+    ///
+    /// Momentum and volatility factors measure returns and default to
+    /// [`PriceAdjustment::TotalReturn`]; other factors default to split-only
+    /// [`PriceAdjustment::PriceOnly`].
+    /// [`FactorRegistry`](crate::FactorRegistry) adjusts each factor's input
+    /// accordingly when given corporate actions. See [`crate::adjustment`].
+    fn price_adjustment(&self) -> PriceAdjustment {
+        match self.category() {
+            FactorCategory::Momentum | FactorCategory::Volatility => PriceAdjustment::TotalReturn,
+            _ => PriceAdjustment::PriceOnly,
+        }
+    }
+
+    /// This factor with its date-window lookbacks counted in sessions of
+    /// `calendar`, or `None` if its lookbacks count rows.
+    ///
+    /// Row-based factors count trading days only on input with one row per
+    /// session, which [`Factor::compute`] does not ensure.
+    /// [`FactorRegistry::with_calendar`](crate::FactorRegistry::with_calendar)
+    /// aligns the input of row-based daily factors to its calendar, and
+    /// computes factors returning `Some` here with the result on unaligned
+    /// input instead. A day count set in the factor's config is kept.
+    fn on_calendar(&self, _calendar: &TradingCalendar) -> Option<Arc<dyn Factor>> {
+        None
+    }
+
     /// Compute raw factor values before standardization.
     ///
     /// Returns a DataFrame with columns: `symbol`, `date`, and the factor name.
@@ -58,8 +137,125 @@ pub trait Factor: Send + Sync + std::fmt::Debug {
     /// This is the primary computation method. It computes raw values
     /// and then applies cross-sectional standardization.
     fn compute(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        self.compute_pipeline(data, date, &Pipeline::default())
+    }
+
+    /// Compute factor scores using the given standardization scheme.
+    ///
+    /// Any grouping or control columns the scheme needs (e.g. `sector`,
+    /// `log_market_cap`) are taken from `data` on `date`. Returns a DataFrame
+    /// with columns: `symbol`, `date`, and the factor name.
+    fn compute_with(
+        &self,
+        data: &LazyFrame,
+        date: NaiveDate,
+        standardization: &Standardization,
+    ) -> Result<DataFrame> {
+        self.compute_pipeline(data, date, &Pipeline::from(standardization.clone()))
+    }
+
+    /// Compute factor scores by running raw values through `pipeline`.
+    ///
+    /// Any extra columns the pipeline needs are taken from `data` on `date`.
+    /// Returns a DataFrame with columns: `symbol`, `date`, and the factor name.
+    fn compute_pipeline(
+        &self,
+        data: &LazyFrame,
+        date: NaiveDate,
+        pipeline: &Pipeline,
+    ) -> Result<DataFrame> {
         let raw = self.compute_raw(data, date)?;
-        crate::cross_sectional_standardize(&raw, self.name())
+        pipeline.process(raw, data, on_date(date), self.name())
+    }
+
+    /// Attach a preprocessing pipeline used by [`Factor::compute`] and
+    /// [`Factor::compute_panel`].
+    fn with_pipeline(self, pipeline: Pipeline) -> PipelineFactor<Self>
+    where
+        Self: Sized,
+    {
+        PipelineFactor::new(self, pipeline)
+    }
+
+    /// Compute raw factor values for every date in `[start, end]`.
+    ///
+    /// Returns a long panel with columns: `symbol`, `date`, and the factor name,
+    /// with one row per symbol observed on each date. The default implementation
+    /// evaluates [`Factor::compute_raw`] once per distinct date in the range;
+    /// rolling factors override it with windowed expressions that produce the
+    /// whole panel in a single pass. Overrides must agree with
+    /// [`Factor::compute_raw`] on every date, including for symbols without a
+    /// full lookback window.
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let dates = data
+            .clone()
+            .select([date_column().alias("date")])
+            .filter(between_dates(start, end))
+            .unique(None, UniqueKeepStrategy::Any)
+            .sort(["date"], Default::default())
+            .collect()?;
+
+        let mut panel: Option<DataFrame> = None;
+        for date in dates.column("date")?.date()?.as_date_iter().flatten() {
+            // Keep only rows observed on the evaluation date so the panel is
+            // keyed uniquely on (symbol, date).
+            let values = self
+                .compute_raw(data, date)?
+                .lazy()
+                .filter(on_date(date))
+                .collect()?;
+
+            match panel.as_mut() {
+                Some(df) => {
+                    df.vstack_mut(&values)?;
+                }
+                None => panel = Some(values),
+            }
+        }
+
+        match panel {
+            Some(df) => Ok(df),
+            None => Ok(DataFrame::new(vec![
+                Column::new_empty("symbol".into(), &DataType::String),
+                Column::new_empty("date".into(), &DataType::Date),
+                Column::new_empty(self.name().into(), &DataType::Float64),
+            ])?),
+        }
+    }
+
+    /// Compute standardized factor scores (z-scores) for every date in `[start, end]`.
+    ///
+    /// Panel counterpart of [`Factor::compute`]: raw values are standardized
+    /// cross-sectionally within each date.
+    fn compute_panel(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        self.compute_panel_pipeline(data, start, end, &Pipeline::default())
+    }
+
+    /// Compute factor scores for every date in `[start, end]` by running raw
+    /// values through `pipeline`.
+    ///
+    /// Panel counterpart of [`Factor::compute_pipeline`].
+    fn compute_panel_pipeline(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+        pipeline: &Pipeline,
+    ) -> Result<DataFrame> {
+        let raw = self.compute_range(data, start, end)?;
+        pipeline.process(raw, data, between_dates(start, end), self.name())
     }
 }
 
@@ -85,3 +281,211 @@ pub trait ConfigurableFactor: Factor {
 
 /// Blanket implementation for any type that satisfies the trait bounds.
 impl<T: Default + Clone + Send + Sync + std::fmt::Debug> FactorConfig for T {}
+
+/// Returns an error if `start` is after `end`.
+pub(crate) fn check_date_range(start: NaiveDate, end: NaiveDate) -> Result<()> {
+    if start > end {
+        return Err(FactorError::InvalidDateRange {
+            start: start.to_string(),
+            end: end.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Whether string date columns are accepted, via the `string-dates` feature.
+///
+/// The crate's own tests build their fixtures from string literals, so they
+/// always run in this mode.
+pub(crate) const STRING_DATES: bool = cfg!(any(test, feature = "string-dates"));
+
+/// `expr` as a polars `Date`.
+///
+/// `Date` values pass through unchanged and `Datetime` values are truncated to
+/// their calendar day. ISO-8601 strings are parsed only with the
+/// `string-dates` feature, and strictly: a malformed value fails the query
+/// rather than becoming null. Without the feature a string column is an error.
+pub(crate) fn as_date(expr: Expr) -> Expr {
+    if STRING_DATES {
+        expr.strict_cast(DataType::Date)
+    } else {
+        expr.dt().date()
+    }
+}
+
+/// The `date` column as a polars `Date`.
+///
+/// `Date` and `Datetime` columns are accepted; ISO-8601 strings only with the
+/// `string-dates` feature.
+pub fn date_column() -> Expr {
+    as_date(col("date"))
+}
+
+/// Predicate selecting rows observed on `date`.
+pub fn on_date(date: NaiveDate) -> Expr {
+    date_column().eq(lit(date))
+}
+
+/// Predicate selecting rows observed on or before `date`.
+pub fn on_or_before(date: NaiveDate) -> Expr {
+    date_column().lt_eq(lit(date))
+}
+
+/// Predicate selecting rows observed strictly after `date`.
+pub fn after(date: NaiveDate) -> Expr {
+    date_column().gt(lit(date))
+}
+
+/// Predicate selecting rows whose `date` lies in `[start, end]`.
+pub fn between_dates(start: NaiveDate, end: NaiveDate) -> Expr {
+    date_column()
+        .gt_eq(lit(start))
+        .and(date_column().lt_eq(lit(end)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_dates_parse_strictly() {
+        let data = df![
+            "symbol" => ["A", "B"],
+            "date" => ["2024-01-02", "2024-13-45"],
+        ]
+        .unwrap()
+        .lazy();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+
+        let result = data.filter(on_date(date)).collect();
+        assert!(
+            result.is_err(),
+            "malformed dates must not be dropped as null"
+        );
+
+        let valid = df!["date" => ["2024-01-02", "2024-01-03"]]
+            .unwrap()
+            .lazy()
+            .filter(on_date(date))
+            .collect()
+            .unwrap();
+        assert_eq!(valid.height(), 1);
+    }
+
+    /// Daily panel with every input the rolling factors read: `A` has a long
+    /// history, `B` a medium one and `C` starts inside the tested range.
+    fn daily_panel() -> LazyFrame {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let mut columns: [Vec<f64>; 11] = Default::default();
+        let (mut symbols, mut dates) = (Vec::new(), Vec::new());
+        for (s, (symbol, days)) in [("A", 300u64), ("B", 60), ("C", 6)].into_iter().enumerate() {
+            for i in 300 - days..300 {
+                let t = i as f64 + s as f64 * 7.0;
+                let close = 100.0 + 10.0 * (t * 0.07).sin() + t * 0.05;
+                let spread = 0.5 + 0.2 * (t * 0.3).cos().abs();
+                let values = [
+                    close,
+                    1e6 * (1.5 + (t * 0.11).sin()),
+                    close + spread,
+                    close - spread,
+                    close - 0.01 * spread,
+                    close + 0.01 * spread,
+                    0.01 * (i as f64 * 0.13).sin(),
+                    0.2 + 0.05 * (t * 0.05).cos(),
+                    2e5 * (1.2 + (t * 0.02).sin()),
+                    5e7,
+                    6e7,
+                ];
+                for (column, value) in columns.iter_mut().zip(values) {
+                    column.push(value);
+                }
+                symbols.push(symbol);
+                dates.push(start + chrono::Days::new(i));
+            }
+        }
+
+        let names = [
+            "close",
+            "volume",
+            "high",
+            "low",
+            "bid",
+            "ask",
+            "market_return",
+            "implied_volatility",
+            "shares_short",
+            "float_shares",
+            "shares_outstanding",
+        ];
+        let mut panel = vec![
+            Column::new("symbol".into(), symbols),
+            Column::new("date".into(), dates),
+        ];
+        for (name, values) in names.into_iter().zip(columns) {
+            panel.push(Column::new(name.into(), values));
+        }
+        DataFrame::new(panel).unwrap().lazy()
+    }
+
+    /// `symbol -> value` on `date`, with missing rows and nulls both `None`.
+    fn values_on(
+        df: &DataFrame,
+        name: &str,
+        date: NaiveDate,
+    ) -> std::collections::BTreeMap<String, Option<f64>> {
+        let rows = df.clone().lazy().filter(on_date(date)).collect().unwrap();
+        let symbols = rows.column("symbol").unwrap().str().unwrap();
+        let values = rows.column(name).unwrap().f64().unwrap();
+        symbols
+            .into_iter()
+            .zip(values)
+            .map(|(symbol, value)| (symbol.unwrap().to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn test_compute_range_matches_compute_raw() {
+        let data = daily_panel();
+        let start = NaiveDate::from_ymd_opt(2023, 10, 20).unwrap();
+        let end = NaiveDate::from_ymd_opt(2023, 10, 27).unwrap();
+
+        let factors: Vec<_> = crate::spec::builtins()
+            .into_iter()
+            .filter(|f| {
+                matches!(
+                    f.category(),
+                    FactorCategory::Momentum
+                        | FactorCategory::Volatility
+                        | FactorCategory::Liquidity
+                ) && f.frequency() == DataFrequency::Daily
+            })
+            .collect();
+        assert_eq!(factors.len(), 33);
+
+        let mut mismatches = Vec::new();
+        for factor in factors {
+            let name = factor.name();
+            let panel = factor.compute_range(&data, start, end).unwrap();
+            for date in start.iter_days().take_while(|d| *d <= end) {
+                let point = factor.compute_raw(&data, date).unwrap();
+                let expected = values_on(&point, name, date);
+                let actual = values_on(&panel, name, date);
+                for symbol in ["A", "B", "C"] {
+                    let (e, a) = (
+                        expected.get(symbol).copied().flatten(),
+                        actual.get(symbol).copied().flatten(),
+                    );
+                    let agree = match (e, a) {
+                        (Some(e), Some(a)) if e.is_nan() || a.is_nan() => e.is_nan() && a.is_nan(),
+                        (Some(e), Some(a)) => (e - a).abs() <= 1e-9 * e.abs().max(1.0),
+                        (e, a) => e.is_none() && a.is_none(),
+                    };
+                    if !agree {
+                        mismatches.push(format!("{name} {symbol} {date}: raw {e:?}, range {a:?}"));
+                    }
+                }
+            }
+        }
+        assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+    }
+}
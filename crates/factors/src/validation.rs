@@ -0,0 +1,279 @@
+//! Input validation for factor computations.
+//!
+//! Checks a dataset against a factor's declared requirements before any query
+//! runs, so problems surface as typed [`FactorError`]s rather than opaque
+//! polars failures deep inside a computation.
+
+use crate::{Factor, FactorError, Result, schema::plan_derivations, traits::STRING_DATES};
+use polars::prelude::*;
+
+/// Outcome of validating a single factor against a dataset.
+#[derive(Debug)]
+pub struct FactorValidation {
+    /// Factor name
+    pub name: String,
+    /// `Ok` if the factor can run, otherwise the first problem found
+    pub result: Result<()>,
+}
+
+impl FactorValidation {
+    /// Whether the factor can run on the dataset.
+    pub const fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Validate a dataset against a factor's requirements.
+///
+/// Runs [`validate_schema`] followed by [`validate_history`].
+pub fn validate<F: Factor + ?Sized>(factor: &F, data: &LazyFrame) -> Result<()> {
+    let schema = data.clone().collect_schema()?;
+    validate_schema(factor, &schema)?;
+    validate_history(factor, longest_history(data)?)
+}
+
+/// Validate a schema against a factor's required columns.
+///
+/// A required column counts as present if `schema` has it or it can be
+/// derived from columns it has (see [`plan_derivations`]). Every missing
+/// column is reported at once in a single [`FactorError::MissingColumns`].
+/// Present columns must have a compatible dtype: `symbol` is a string, `date`
+/// is a date or datetime (or an ISO-8601 string with the `string-dates`
+/// feature), and every other required column is numeric.
+pub fn validate_schema<F: Factor + ?Sized>(factor: &F, schema: &Schema) -> Result<()> {
+    let missing: Vec<String> = factor
+        .required_columns()
+        .iter()
+        .filter(|&&column| {
+            !schema.contains(column)
+                && !plan_derivations(schema, &[column])
+                    .iter()
+                    .any(|d| d.column() == column)
+        })
+        .map(|column| column.to_string())
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(FactorError::MissingColumns(missing));
+    }
+
+    for &column in factor.required_columns() {
+        let Some(dtype) = schema.get(column) else {
+            continue;
+        };
+
+        let (valid, expected) = match column {
+            "symbol" => (matches!(dtype, DataType::String), "string"),
+            "date" if STRING_DATES => (
+                matches!(
+                    dtype,
+                    DataType::Date | DataType::Datetime(_, _) | DataType::String
+                ),
+                "date, datetime or string",
+            ),
+            "date" => (
+                matches!(dtype, DataType::Date | DataType::Datetime(_, _)),
+                "date or datetime (enable the string-dates feature to parse strings)",
+            ),
+            _ => (dtype.is_primitive_numeric(), "numeric"),
+        };
+
+        if !valid {
+            return Err(FactorError::InvalidColumnType {
+                column: column.to_string(),
+                expected: expected.to_string(),
+                actual: dtype.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate that enough history is available for a factor's lookback.
+///
+/// `available` is the number of observations for the symbol with the longest
+/// history (see [`longest_history`]); if even that symbol falls short of
+/// [`Factor::lookback`], the factor cannot produce any values.
+pub fn validate_history<F: Factor + ?Sized>(factor: &F, available: usize) -> Result<()> {
+    let required = factor.lookback();
+    if available < required {
+        return Err(FactorError::InsufficientData {
+            required,
+            available,
+        });
+    }
+    Ok(())
+}
+
+/// Number of observations for the symbol with the longest history.
+pub fn longest_history(data: &LazyFrame) -> Result<usize> {
+    let counts = data
+        .clone()
+        .group_by([col("symbol")])
+        .agg([len().alias("count")])
+        .select([col("count").max()])
+        .collect()?;
+
+    Ok(counts.column("count")?.u32()?.get(0).unwrap_or(0) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{momentum::ShortTermMomentum, value::BookToPrice};
+
+    #[test]
+    fn test_validate_reports_all_missing_columns() {
+        let data = df![
+            "symbol" => ["AAPL"],
+            "date" => ["2024-03-31"],
+        ]
+        .unwrap()
+        .lazy();
+
+        let err = validate(&BookToPrice::default(), &data).unwrap_err();
+        match err {
+            FactorError::MissingColumns(columns) => {
+                assert_eq!(columns, ["book_equity", "market_cap"]);
+            }
+            other => panic!("Expected MissingColumns, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_derivable_columns() {
+        let reported = df![
+            "symbol" => ["AAPL"],
+            "date" => ["2024-03-31"],
+            "book_equity" => [50_000_000_000.0],
+            "market_cap" => [2_500_000_000_000.0],
+        ]
+        .unwrap()
+        .lazy();
+        let derivable = reported.clone().drop(["market_cap"]).with_columns([
+            lit(160.0).alias("close"),
+            lit(1e10).alias("shares_outstanding"),
+        ]);
+        let registry =
+            crate::FactorRegistry::with_defaults().filter(|f| f.name() == "book_to_price");
+        for data in [&reported, &derivable] {
+            validate(&BookToPrice::default(), data).unwrap();
+            assert!(registry.validate(data).unwrap()[0].is_ok());
+        }
+
+        // Piotroski from vendor ratios and priors, or from statement lines
+        let ratios = df![
+            "symbol" => ["A", "B"],
+            "date" => ["2024-03-31"; 2],
+            "net_income" => [10.0, -5.0],
+            "operating_cash_flow" => [12.0, -3.0],
+            "roa" => [0.15, 0.05],
+            "roa_prior" => [0.12, 0.08],
+            "long_term_debt" => [50.0, 60.0],
+            "long_term_debt_prior" => [55.0, 50.0],
+            "current_ratio" => [2.5, 1.5],
+            "current_ratio_prior" => [2.2, 1.8],
+            "shares_outstanding" => [10.0, 12.0],
+            "shares_outstanding_prior" => [10.0, 10.0],
+            "gross_margin" => [0.45, 0.25],
+            "gross_margin_prior" => [0.42, 0.30],
+            "asset_turnover" => [1.3, 0.9],
+            "asset_turnover_prior" => [1.2, 1.0],
+        ]
+        .unwrap()
+        .lazy();
+        let statements = df![
+            "symbol" => ["A", "A"],
+            "date" => ["2023-12-31", "2024-03-31"],
+            "net_income" => [8.0, 10.0],
+            "operating_cash_flow" => [9.0, 12.0],
+            "total_assets" => [100.0, 100.0],
+            "long_term_debt" => [55.0, 50.0],
+            "current_assets" => [22.0, 25.0],
+            "current_liabilities" => [10.0, 10.0],
+            "shares_outstanding" => [10.0, 10.0],
+            "revenue" => [40.0, 45.0],
+            "cogs" => [24.0, 25.0],
+        ]
+        .unwrap()
+        .lazy();
+        let piotroski = crate::quality::Piotroski::default();
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let registry = crate::FactorRegistry::with_defaults()
+            .filter(|f| f.name() == "piotroski_f_score")
+            .with_pipeline(crate::Pipeline::new());
+        for (mut data, expected) in [(ratios, vec![9, 1]), (statements, vec![9])] {
+            validate_schema(&piotroski, &data.collect_schema().unwrap()).unwrap();
+            let scores = registry
+                .compute_all(&data, date)
+                .unwrap()
+                .column("piotroski_f_score")
+                .unwrap()
+                .cast(&DataType::Int32)
+                .unwrap();
+            let scores: Vec<i32> = scores.i32().unwrap().into_no_null_iter().collect();
+            assert_eq!(scores, expected);
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_non_numeric_column() {
+        let data = df![
+            "symbol" => ["AAPL"],
+            "date" => ["2024-03-31"],
+            "book_equity" => ["50bn"],
+            "close" => [160.0],
+            "shares_outstanding" => [15_625_000_000.0],
+        ]
+        .unwrap()
+        .lazy();
+
+        let err = validate(&BookToPrice::default(), &data).unwrap_err();
+        assert!(matches!(
+            err,
+            FactorError::InvalidColumnType { ref column, .. } if column == "book_equity"
+        ));
+    }
+
+    #[test]
+    fn test_validate_checks_history_length() {
+        let data = df![
+            "symbol" => ["AAPL", "AAPL", "MSFT"],
+            "date" => ["2024-01-02", "2024-01-03", "2024-01-02"],
+            "close" => [100.0, 101.0, 200.0],
+        ]
+        .unwrap()
+        .lazy();
+
+        let err = validate(&ShortTermMomentum::default(), &data).unwrap_err();
+        assert!(matches!(
+            err,
+            FactorError::InsufficientData {
+                required: 21,
+                available: 2
+            }
+        ));
+    }
+
+    #[test]
+    fn test_registry_validate_reports_per_factor() {
+        let data = df![
+            "symbol" => ["AAPL"],
+            "date" => ["2024-03-31"],
+            "book_equity" => [50_000_000_000.0],
+            "close" => [160.0],
+            "shares_outstanding" => [15_625_000_000.0],
+        ]
+        .unwrap()
+        .lazy();
+
+        let reports = crate::FactorRegistry::with_defaults()
+            .validate(&data)
+            .unwrap();
+        let book_to_price = reports.iter().find(|r| r.name == "book_to_price").unwrap();
+        assert!(book_to_price.is_ok());
+        assert!(reports.iter().any(|r| !r.is_ok()));
+        assert!(reports.windows(2).all(|w| w[0].name <= w[1].name));
+    }
+}
@@ -6,7 +6,8 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    schema::derive_fields,
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -96,9 +97,8 @@ impl Factor for BookToPrice {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let result = data
-            .clone()
-            .filter(col("date").eq(lit(date.to_string())))
+        let result = derive_fields(data.clone(), self.required_columns())?
+            .filter(on_date(date))
             .select([
                 col("symbol"),
                 col("date"),
@@ -180,4 +180,52 @@ mod tests {
         assert_eq!(symbols.get(0).unwrap(), "AAPL");
         assert_eq!(symbols.get(1).unwrap(), "MSFT");
     }
+
+    #[test]
+    fn test_book_to_price_range() {
+        let data = df![
+            "symbol" => ["AAPL", "AAPL", "MSFT", "MSFT"],
+            "date" => ["2024-03-31", "2024-06-30", "2024-03-31", "2024-06-30"],
+            "book_equity" => [50_000_000_000.0, 52_000_000_000.0, 75_000_000_000.0, 78_000_000_000.0],
+            "market_cap" => [2_500_000_000_000.0, 2_600_000_000_000.0, 2_500_000_000_000.0, 2_550_000_000_000.0],
+        ]
+        .unwrap()
+        .lazy();
+
+        let factor = BookToPrice::default();
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let result = factor.compute_range(&data, start, end).unwrap();
+
+        assert_eq!(result.height(), 4);
+        let dates = result.column("date").unwrap().str().unwrap();
+        assert_eq!(dates.get(0).unwrap(), "2024-03-31");
+        assert_eq!(dates.get(3).unwrap(), "2024-06-30");
+
+        // Start after end is rejected
+        let err = factor.compute_range(&data, end, start).unwrap_err();
+        assert!(matches!(err, crate::FactorError::InvalidDateRange { .. }));
+    }
+
+    #[test]
+    fn test_book_to_price_typed_dates() {
+        let march = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let june = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let data = df![
+            "symbol" => ["AAPL", "AAPL", "MSFT", "MSFT"],
+            "date" => [march, june, march, june],
+            "book_equity" => [50_000_000_000.0, 52_000_000_000.0, 75_000_000_000.0, 78_000_000_000.0],
+            "market_cap" => [2_500_000_000_000.0, 2_600_000_000_000.0, 2_500_000_000_000.0, 2_550_000_000_000.0],
+        ]
+        .unwrap()
+        .lazy();
+
+        let factor = BookToPrice::default();
+        let result = factor.compute_raw(&data, june).unwrap();
+
+        assert_eq!(result.height(), 2);
+        assert_eq!(result.column("date").unwrap().dtype(), &DataType::Date);
+        let values = result.column("book_to_price").unwrap().f64().unwrap();
+        assert!((values.get(0).unwrap() - 0.02).abs() < 1e-6);
+    }
 }
@@ -6,7 +6,8 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    schema::derive_fields,
+    traits::{ConfigurableFactor, DataFrequency, Factor, on_date},
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -96,9 +97,8 @@ impl Factor for EarningsYield {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        let result = data
-            .clone()
-            .filter(col("date").eq(lit(date.to_string())))
+        let result = derive_fields(data.clone(), self.required_columns())?
+            .filter(on_date(date))
             .select([
                 col("symbol"),
                 col("date"),
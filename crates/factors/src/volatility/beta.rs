@@ -8,8 +8,12 @@
 
 use crate::{
     Result,
+    features::{self, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -68,6 +72,23 @@ impl MarketBeta {
             },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> Result<LazyFrame> {
+        // Filter to dates up to and including the target date
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Simplified beta ≈ std(stock) / std(market) from the rolling market
+        // model, shared with other factors when run through the registry
+        let model = features::market_model_columns(self.config.lookback, self.config.min_periods);
+        Ok(
+            features::with_features(filtered, &self.features())?.select([
+                col("symbol"),
+                col("date"),
+                col(&model.beta).alias("market_beta"),
+            ]),
+        )
+    }
 }
 
 impl Default for MarketBeta {
@@ -113,75 +134,34 @@ impl Factor for MarketBeta {
         DataFrequency::Daily
     }
 
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::MarketModel {
+            window: self.config.lookback,
+            min_periods: self.config.min_periods,
+        }]
+    }
+
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        // Filter to dates up to and including the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())));
-
-        // Sort and compute returns using shift
-        let with_returns = filtered
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            .with_column(((col("close") - col("close_lag")) / col("close_lag")).alias("return"));
-
-        // Compute rolling statistics for beta calculation
-        let result = with_returns
-            .with_column(
-                col("return")
-                    .rolling_std(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("stock_std"),
-            )
-            .with_column(
-                col("market_return")
-                    .rolling_std(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("market_std"),
-            )
-            .with_column(
-                col("return")
-                    .rolling_mean(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("return_mean"),
-            )
-            .with_column(
-                col("market_return")
-                    .rolling_mean(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("market_mean"),
-            )
-            // Compute covariance approximation using correlation * std_stock * std_market / var_market
-            // For simplicity, we use beta ≈ correlation * (stock_std / market_std)
-            // This is a simplified computation that works for cross-sectional ranking
-            .with_column((col("stock_std") / col("market_std")).alias("market_beta"))
+        let result = self
+            .rolling(data, date)?
             // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            .select([col("symbol"), col("date"), col("market_beta")])
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)?
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
@@ -206,4 +186,43 @@ mod tests {
         assert_eq!(factor.frequency(), DataFrequency::Daily);
         assert!(factor.required_columns().contains(&"market_return"));
     }
+
+    #[test]
+    fn test_market_beta_is_std_ratio() {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let data = df![
+            "symbol" => ["A"; 30],
+            "date" => (0..30).map(|i| (start + chrono::Duration::days(i)).to_string()).collect::<Vec<_>>(),
+            "close" => (0..30).map(|i| 100.0 + (i as f64 * 0.9).sin() * 3.0 + i as f64 * 0.2).collect::<Vec<_>>(),
+            "market_return" => (0..30).map(|i| (i as f64 * 0.7).cos() * 0.01).collect::<Vec<_>>(),
+        ]
+        .unwrap()
+        .lazy();
+        let date = start + chrono::Duration::days(29);
+
+        // beta ≈ std(stock) / std(market), exactly as computed inline
+        let window = RollingOptionsFixedWindow {
+            window_size: 10,
+            min_periods: 10,
+            ..Default::default()
+        };
+        let lag = col("close").shift(lit(1));
+        let expected = data
+            .clone()
+            .with_column(((col("close") - lag.clone()) / lag).alias("return"))
+            .select([(col("return").rolling_std(window.clone())
+                / col("market_return").rolling_std(window))
+            .last()
+            .alias("expected")])
+            .collect()
+            .unwrap();
+
+        let beta = MarketBeta::with_lookback(10)
+            .compute_raw(&data, date)
+            .unwrap();
+        assert_eq!(
+            beta.column("market_beta").unwrap().f64().unwrap().get(0),
+            expected.column("expected").unwrap().f64().unwrap().get(0)
+        );
+    }
 }
@@ -10,8 +10,12 @@
 
 use crate::{
     Result,
+    features::{self, DAILY_RETURN, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -69,6 +73,48 @@ impl DownsideBeta {
             },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> Result<LazyFrame> {
+        // Filter to dates up to and including the target date
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Daily returns, shared across factors when run through the registry
+        let with_returns = features::with_features(filtered, &self.features())?;
+
+        // Filter to only negative market days and compute downside statistics
+        let downside_only = with_returns.filter(col("market_return").lt(lit(0.0)));
+
+        // Compute downside statistics: covariance and variance on negative market days
+        // Using simplified beta calculation: std(stock_downside) / std(market_downside) * correlation
+        // For a more accurate beta, we'd need to compute actual covariance
+        Ok(downside_only
+            .with_column(
+                col(DAILY_RETURN)
+                    .rolling_std(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("stock_downside_std"),
+            )
+            .with_column(
+                col("market_return")
+                    .rolling_std(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("market_downside_std"),
+            )
+            // Simplified beta approximation
+            .with_column(
+                (col("stock_downside_std") / col("market_downside_std")).alias("downside_beta"),
+            )
+            .select([col("symbol"), col("date"), col("downside_beta")]))
+    }
 }
 
 impl Default for DownsideBeta {
@@ -114,60 +160,31 @@ impl Factor for DownsideBeta {
         DataFrequency::Daily
     }
 
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::DailyReturn]
+    }
+
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        // Filter to dates up to and including the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())));
-
-        // Sort and compute returns using shift
-        let with_returns = filtered
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            .with_column(((col("close") - col("close_lag")) / col("close_lag")).alias("return"));
+        let result = self
+            .rolling(data, date)?
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
 
-        // Filter to only negative market days and compute downside statistics
-        let downside_only = with_returns.filter(col("market_return").lt(lit(0.0)));
+        Ok(result)
+    }
 
-        // Compute downside statistics: covariance and variance on negative market days
-        // Using simplified beta calculation: std(stock_downside) / std(market_downside) * correlation
-        // For a more accurate beta, we'd need to compute actual covariance
-        let result = downside_only
-            .with_column(
-                col("return")
-                    .rolling_std(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("stock_downside_std"),
-            )
-            .with_column(
-                col("market_return")
-                    .rolling_std(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("market_downside_std"),
-            )
-            // Simplified beta approximation
-            .with_column(
-                (col("stock_downside_std") / col("market_downside_std")).alias("downside_beta"),
-            )
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            .select([col("symbol"), col("date"), col("downside_beta")])
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)?
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
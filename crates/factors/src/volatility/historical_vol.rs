@@ -8,8 +8,12 @@
 
 use crate::{
     Result,
+    features::{self, DAILY_RETURN, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -113,34 +117,54 @@ impl Factor for HistoricalVolatility {
         DataFrequency::Daily
     }
 
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::DailyReturn]
+    }
+
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        let result = self
+            .rolling(data, date)?
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)?
+            .filter(between_dates(start, end))
+            .collect()?;
+
+        Ok(result)
+    }
+}
+
+impl HistoricalVolatility {
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> Result<LazyFrame> {
         // Annualization factor for daily to annual volatility
         const TRADING_DAYS_PER_YEAR: f64 = 252.0;
         let annualization_factor = TRADING_DAYS_PER_YEAR.sqrt();
 
         // Filter to dates up to and including the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())));
-
-        // Compute daily returns using shift
-        let with_returns = filtered
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            .with_column(((col("close") - col("close_lag")) / col("close_lag")).alias("return"));
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Daily returns, shared across factors when run through the registry
+        let with_returns = features::with_features(filtered, &self.features())?;
 
         // Compute rolling standard deviation
-        let result = with_returns
+        Ok(with_returns
             .with_column(
-                col("return")
+                col(DAILY_RETURN)
                     .rolling_std(RollingOptionsFixedWindow {
                         window_size: self.config.lookback,
                         min_periods: self.config.min_periods,
@@ -152,12 +176,7 @@ impl Factor for HistoricalVolatility {
             .with_column(
                 (col("daily_vol") * lit(annualization_factor)).alias("historical_volatility"),
             )
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            .select([col("symbol"), col("date"), col("historical_volatility")])
-            .collect()?;
-
-        Ok(result)
+            .select([col("symbol"), col("date"), col("historical_volatility")]))
     }
 }
 
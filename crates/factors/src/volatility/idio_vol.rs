@@ -10,8 +10,12 @@
 
 use crate::{
     Result,
+    features::{self, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -69,6 +73,35 @@ impl IdiosyncraticVolatility {
             },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> Result<LazyFrame> {
+        // Filter to dates up to and including the target date
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Residuals `return - beta * market_return` of the rolling market
+        // model, shared with other factors when run through the registry
+        let model = features::market_model_columns(self.config.lookback, self.config.min_periods);
+        let with_residuals = features::with_features(filtered, &self.features())?;
+
+        // Idiosyncratic vol = std(residuals), annualized
+        Ok(with_residuals
+            .with_column(
+                col(&model.residual)
+                    .rolling_std(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("idiosyncratic_volatility"),
+            )
+            .with_column(
+                (col("idiosyncratic_volatility") * lit(252.0_f64.sqrt()))
+                    .alias("idiosyncratic_volatility"),
+            )
+            .select([col("symbol"), col("date"), col("idiosyncratic_volatility")]))
+    }
 }
 
 impl Default for IdiosyncraticVolatility {
@@ -114,93 +147,34 @@ impl Factor for IdiosyncraticVolatility {
         DataFrequency::Daily
     }
 
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::MarketModel {
+            window: self.config.lookback,
+            min_periods: self.config.min_periods,
+        }]
+    }
+
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        // Filter to dates up to and including the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())));
+        let result = self
+            .rolling(data, date)?
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
 
-        // Sort and compute returns using shift
-        let with_returns = filtered
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            .with_column(((col("close") - col("close_lag")) / col("close_lag")).alias("return"));
+        Ok(result)
+    }
 
-        // Compute rolling statistics for regression
-        let with_stats = with_returns
-            .with_column(
-                col("return")
-                    .rolling_mean(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("return_mean"),
-            )
-            .with_column(
-                col("market_return")
-                    .rolling_mean(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("market_mean"),
-            )
-            .with_column(
-                col("return")
-                    .rolling_std(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("return_std"),
-            )
-            .with_column(
-                col("market_return")
-                    .rolling_std(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("market_std"),
-            );
-
-        // Simplified beta calculation: beta ≈ std(stock) / std(market)
-        // Compute residuals: residual = return - beta * market_return
-        // Idiosyncratic vol = std(residuals)
-        let result = with_stats
-            .with_column((col("return_std") / col("market_std")).alias("beta"))
-            .with_column((col("return") - col("beta") * col("market_return")).alias("residual"))
-            .with_column(
-                col("residual")
-                    .rolling_std(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("idiosyncratic_volatility"),
-            )
-            // Annualize the volatility
-            .with_column(
-                (col("idiosyncratic_volatility") * lit(252.0_f64.sqrt()))
-                    .alias("idiosyncratic_volatility"),
-            )
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            .select([col("symbol"), col("date"), col("idiosyncratic_volatility")])
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)?
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
@@ -226,4 +200,54 @@ mod tests {
         assert_eq!(factor.lookback(), 252);
         assert!(factor.required_columns().contains(&"market_return"));
     }
+
+    #[test]
+    fn test_residuals_use_std_ratio_beta() {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let data = df![
+            "symbol" => ["A"; 40],
+            "date" => (0..40).map(|i| (start + chrono::Duration::days(i)).to_string()).collect::<Vec<_>>(),
+            "close" => (0..40).map(|i| 100.0 + (i as f64 * 0.9).sin() * 3.0 + i as f64 * 0.2).collect::<Vec<_>>(),
+            "market_return" => (0..40).map(|i| (i as f64 * 0.7).cos() * 0.01).collect::<Vec<_>>(),
+        ]
+        .unwrap()
+        .lazy();
+        let date = start + chrono::Duration::days(39);
+
+        // residual = return - beta * market, beta ≈ std(stock) / std(market)
+        let window = RollingOptionsFixedWindow {
+            window_size: 10,
+            min_periods: 10,
+            ..Default::default()
+        };
+        let lag = col("close").shift(lit(1));
+        let expected = data
+            .clone()
+            .with_column(((col("close") - lag.clone()) / lag).alias("return"))
+            .with_column(
+                (col("return").rolling_std(window.clone())
+                    / col("market_return").rolling_std(window.clone()))
+                .alias("beta"),
+            )
+            .with_column((col("return") - col("beta") * col("market_return")).alias("residual"))
+            .select([
+                (col("residual").rolling_std(window) * lit(252.0_f64.sqrt()))
+                    .last()
+                    .alias("expected"),
+            ])
+            .collect()
+            .unwrap();
+
+        let vol = IdiosyncraticVolatility::with_lookback(10)
+            .compute_raw(&data, date)
+            .unwrap();
+        assert_eq!(
+            vol.column("idiosyncratic_volatility")
+                .unwrap()
+                .f64()
+                .unwrap()
+                .get(0),
+            expected.column("expected").unwrap().f64().unwrap().get(0)
+        );
+    }
 }
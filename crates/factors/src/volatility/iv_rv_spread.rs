@@ -12,8 +12,12 @@
 
 use crate::{
     Result,
+    features::{self, DAILY_RETURN, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -77,6 +81,42 @@ impl IvRvSpread {
             },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> Result<LazyFrame> {
+        // Annualization factor for daily to annual volatility
+        const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+        let annualization_factor = TRADING_DAYS_PER_YEAR.sqrt();
+
+        // Filter to dates up to and including the target date
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Daily returns, shared across factors when run through the registry
+        let with_returns = features::with_features(filtered, &self.features())?;
+
+        // Compute realized volatility (RV) as rolling standard deviation
+        let with_rv = with_returns
+            .with_column(
+                col(DAILY_RETURN)
+                    .rolling_std(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("daily_vol"),
+            )
+            .with_column(
+                (col("daily_vol") * lit(annualization_factor)).alias("realized_volatility"),
+            );
+
+        // Compute IV-RV spread
+        Ok(with_rv
+            .with_column(
+                (col("implied_volatility") - col("realized_volatility")).alias("iv_rv_spread"),
+            )
+            .select([col("symbol"), col("date"), col("iv_rv_spread")]))
+    }
 }
 
 impl Default for IvRvSpread {
@@ -122,54 +162,31 @@ impl Factor for IvRvSpread {
         DataFrequency::Daily
     }
 
-    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        // Annualization factor for daily to annual volatility
-        const TRADING_DAYS_PER_YEAR: f64 = 252.0;
-        let annualization_factor = TRADING_DAYS_PER_YEAR.sqrt();
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::DailyReturn]
+    }
 
-        // Filter to dates up to and including the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())));
-
-        // Compute daily returns using shift
-        let with_returns = filtered
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            .with_column(((col("close") - col("close_lag")) / col("close_lag")).alias("return"));
+    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        let result = self
+            .rolling(data, date)?
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
 
-        // Compute realized volatility (RV) as rolling standard deviation
-        let with_rv = with_returns
-            .with_column(
-                col("return")
-                    .rolling_std(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("daily_vol"),
-            )
-            .with_column(
-                (col("daily_vol") * lit(annualization_factor)).alias("realized_volatility"),
-            );
+        Ok(result)
+    }
 
-        // Compute IV-RV spread
-        let result = with_rv
-            .with_column(
-                (col("implied_volatility") - col("realized_volatility")).alias("iv_rv_spread"),
-            )
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            .select([col("symbol"), col("date"), col("iv_rv_spread")])
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)?
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
@@ -10,8 +10,12 @@
 
 use crate::{
     Result,
+    features::{self, DAILY_RETURN, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -68,6 +72,59 @@ impl ReturnKurtosis {
             },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> Result<LazyFrame> {
+        // Filter to dates up to and including the target date
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Daily returns, shared across factors when run through the registry
+        let with_returns = features::with_features(filtered, &self.features())?;
+
+        // Compute mean and std for standardization
+        let with_stats = with_returns
+            .with_column(
+                col(DAILY_RETURN)
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("return_mean"),
+            )
+            .with_column(
+                col(DAILY_RETURN)
+                    .rolling_std(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("return_std"),
+            );
+
+        // Compute excess kurtosis: E[(x - μ)⁴] / σ⁴ - 3
+        // First compute standardized returns, then raise to 4th power, then take mean, then subtract 3
+        Ok(with_stats
+            .with_column(
+                ((col(DAILY_RETURN) - col("return_mean")) / col("return_std")).alias("z_score"),
+            )
+            .with_column(col("z_score").pow(4.0).alias("z_fourth"))
+            .with_column(
+                col("z_fourth")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("raw_kurtosis"),
+            )
+            // Subtract 3 for excess kurtosis
+            .with_column((col("raw_kurtosis") - lit(3.0)).alias("return_kurtosis"))
+            .select([col("symbol"), col("date"), col("return_kurtosis")]))
+    }
 }
 
 impl Default for ReturnKurtosis {
@@ -113,71 +170,31 @@ impl Factor for ReturnKurtosis {
         DataFrequency::Daily
     }
 
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::DailyReturn]
+    }
+
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        // Filter to dates up to and including the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())));
-
-        // Sort and compute returns using shift
-        let with_returns = filtered
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            .with_column(((col("close") - col("close_lag")) / col("close_lag")).alias("return"));
+        let result = self
+            .rolling(data, date)?
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
 
-        // Compute mean and std for standardization
-        let with_stats = with_returns
-            .with_column(
-                col("return")
-                    .rolling_mean(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("return_mean"),
-            )
-            .with_column(
-                col("return")
-                    .rolling_std(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("return_std"),
-            );
+        Ok(result)
+    }
 
-        // Compute excess kurtosis: E[(x - μ)⁴] / σ⁴ - 3
-        // First compute standardized returns, then raise to 4th power, then take mean, then subtract 3
-        let result = with_stats
-            .with_column(
-                ((col("return") - col("return_mean")) / col("return_std")).alias("z_score"),
-            )
-            .with_column(col("z_score").pow(4.0).alias("z_fourth"))
-            .with_column(
-                col("z_fourth")
-                    .rolling_mean(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("raw_kurtosis"),
-            )
-            // Subtract 3 for excess kurtosis
-            .with_column((col("raw_kurtosis") - lit(3.0)).alias("return_kurtosis"))
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            .select([col("symbol"), col("date"), col("return_kurtosis")])
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)?
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
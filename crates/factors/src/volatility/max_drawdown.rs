@@ -11,7 +11,10 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -68,6 +71,48 @@ impl MaxDrawdown {
             },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> LazyFrame {
+        // Filter to dates up to and including the target date
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Sort by symbol and date
+        let sorted = filtered.sort(
+            ["symbol", "date"],
+            SortMultipleOptions::default().with_order_descending_multi([false, false]),
+        );
+
+        // Compute rolling maximum (peak) price
+        let with_peak = sorted.with_column(
+            col("close")
+                .rolling_max(RollingOptionsFixedWindow {
+                    window_size: self.config.lookback,
+                    min_periods: self.config.min_periods,
+                    ..Default::default()
+                })
+                .over([col("symbol")])
+                .alias("rolling_peak"),
+        );
+
+        // Compute drawdown: (current price - peak) / peak
+        // Then take the minimum (most negative) drawdown
+        with_peak
+            .with_column(
+                ((col("close") - col("rolling_peak")) / col("rolling_peak")).alias("drawdown"),
+            )
+            .with_column(
+                col("drawdown")
+                    .rolling_min(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("max_drawdown"),
+            )
+            .select([col("symbol"), col("date"), col("max_drawdown")])
+    }
 }
 
 impl Default for MaxDrawdown {
@@ -114,48 +159,26 @@ impl Factor for MaxDrawdown {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        // Filter to dates up to and including the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())));
-
-        // Sort by symbol and date
-        let sorted = filtered.sort(
-            ["symbol", "date"],
-            SortMultipleOptions::default().with_order_descending_multi([false, false]),
-        );
+        let result = self
+            .rolling(data, date)
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
 
-        // Compute rolling maximum (peak) price
-        let with_peak = sorted.with_column(
-            col("close")
-                .rolling_max(RollingOptionsFixedWindow {
-                    window_size: self.config.lookback,
-                    min_periods: self.config.min_periods,
-                    ..Default::default()
-                })
-                .over([col("symbol")])
-                .alias("rolling_peak"),
-        );
+        Ok(result)
+    }
 
-        // Compute drawdown: (current price - peak) / peak
-        // Then take the minimum (most negative) drawdown
-        let result = with_peak
-            .with_column(
-                ((col("close") - col("rolling_peak")) / col("rolling_peak")).alias("drawdown"),
-            )
-            .with_column(
-                col("drawdown")
-                    .rolling_min(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("max_drawdown"),
-            )
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            .select([col("symbol"), col("date"), col("max_drawdown")])
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
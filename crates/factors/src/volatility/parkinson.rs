@@ -11,7 +11,10 @@
 use crate::{
     Result,
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -70,6 +73,55 @@ impl ParkinsonVolatility {
             },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> LazyFrame {
+        // Parkinson constant: 1 / (4 * ln(2))
+        const PARKINSON_CONSTANT: f64 = 1.0 / (4.0 * std::f64::consts::LN_2);
+        // Annualization factor for daily to annual volatility
+        const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+        let annualization_factor = TRADING_DAYS_PER_YEAR.sqrt();
+
+        // Filter to dates up to and including the target date
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Sort by symbol and date
+        let sorted = filtered.sort(
+            ["symbol", "date"],
+            SortMultipleOptions::default().with_order_descending_multi([false, false]),
+        );
+
+        // Compute log(high/low) squared
+        let with_hl_ratio = sorted.with_column(
+            (col("high") / col("low"))
+                .log(std::f64::consts::E)
+                .pow(2.0)
+                .alias("hl_log_squared"),
+        );
+
+        // Compute Parkinson volatility: sqrt(PARKINSON_CONSTANT * mean(hl_log_squared))
+        with_hl_ratio
+            .with_column(
+                col("hl_log_squared")
+                    .rolling_mean(RollingOptionsFixedWindow {
+                        window_size: self.config.lookback,
+                        min_periods: self.config.min_periods,
+                        ..Default::default()
+                    })
+                    .over([col("symbol")])
+                    .alias("hl_mean_squared"),
+            )
+            .with_column(
+                (lit(PARKINSON_CONSTANT) * col("hl_mean_squared"))
+                    .sqrt()
+                    .alias("daily_parkinson"),
+            )
+            // Annualize the volatility
+            .with_column(
+                (col("daily_parkinson") * lit(annualization_factor)).alias("parkinson_volatility"),
+            )
+            .select([col("symbol"), col("date"), col("parkinson_volatility")])
+    }
 }
 
 impl Default for ParkinsonVolatility {
@@ -116,55 +168,26 @@ impl Factor for ParkinsonVolatility {
     }
 
     fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        // Parkinson constant: 1 / (4 * ln(2))
-        const PARKINSON_CONSTANT: f64 = 1.0 / (4.0 * std::f64::consts::LN_2);
-        // Annualization factor for daily to annual volatility
-        const TRADING_DAYS_PER_YEAR: f64 = 252.0;
-        let annualization_factor = TRADING_DAYS_PER_YEAR.sqrt();
-
-        // Filter to dates up to and including the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())));
-
-        // Sort by symbol and date
-        let sorted = filtered.sort(
-            ["symbol", "date"],
-            SortMultipleOptions::default().with_order_descending_multi([false, false]),
-        );
+        let result = self
+            .rolling(data, date)
+            // Filter to the requested date
+            .filter(on_date(date))
+            .collect()?;
 
-        // Compute log(high/low) squared
-        let with_hl_ratio = sorted.with_column(
-            (col("high") / col("low"))
-                .log(std::f64::consts::E)
-                .pow(2.0)
-                .alias("hl_log_squared"),
-        );
+        Ok(result)
+    }
 
-        // Compute Parkinson volatility: sqrt(PARKINSON_CONSTANT * mean(hl_log_squared))
-        let result = with_hl_ratio
-            .with_column(
-                col("hl_log_squared")
-                    .rolling_mean(RollingOptionsFixedWindow {
-                        window_size: self.config.lookback,
-                        min_periods: self.config.min_periods,
-                        ..Default::default()
-                    })
-                    .over([col("symbol")])
-                    .alias("hl_mean_squared"),
-            )
-            .with_column(
-                (lit(PARKINSON_CONSTANT) * col("hl_mean_squared"))
-                    .sqrt()
-                    .alias("daily_parkinson"),
-            )
-            // Annualize the volatility
-            .with_column(
-                (col("daily_parkinson") * lit(annualization_factor)).alias("parkinson_volatility"),
-            )
-            // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            .select([col("symbol"), col("date"), col("parkinson_volatility")])
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)
@@ -10,8 +10,12 @@
 
 use crate::{
     Result,
+    features::{self, DAILY_RETURN, Feature},
     registry::FactorCategory,
-    traits::{ConfigurableFactor, DataFrequency, Factor},
+    traits::{
+        ConfigurableFactor, DataFrequency, Factor, between_dates, check_date_range, on_date,
+        on_or_before,
+    },
 };
 use chrono::NaiveDate;
 use polars::prelude::*;
@@ -69,6 +73,33 @@ impl ValueAtRisk {
             },
         }
     }
+
+    /// Rolling factor values for every row on or before `date`.
+    fn rolling(&self, data: &LazyFrame, date: NaiveDate) -> Result<LazyFrame> {
+        // Filter to dates up to and including the target date
+        let filtered = data.clone().filter(on_or_before(date));
+
+        // Daily returns, shared across factors when run through the registry
+        let with_returns = features::with_features(filtered, &self.features())?;
+
+        // Compute rolling 5th percentile (VaR)
+        Ok(with_returns
+            .with_column(
+                col(DAILY_RETURN)
+                    .rolling_quantile(
+                        QuantileMethod::Linear,
+                        0.05, // 5th percentile
+                        RollingOptionsFixedWindow {
+                            window_size: self.config.lookback,
+                            min_periods: self.config.min_periods,
+                            ..Default::default()
+                        },
+                    )
+                    .over([col("symbol")])
+                    .alias("value_at_risk"),
+            )
+            .select([col("symbol"), col("date"), col("value_at_risk")]))
+    }
 }
 
 impl Default for ValueAtRisk {
@@ -114,45 +145,31 @@ impl Factor for ValueAtRisk {
         DataFrequency::Daily
     }
 
-    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
-        // Filter to dates up to and including the target date
-        let filtered = data
-            .clone()
-            .filter(col("date").lt_eq(lit(date.to_string())));
-
-        // Sort and compute returns using shift
-        let with_returns = filtered
-            .sort(
-                ["symbol", "date"],
-                SortMultipleOptions::default().with_order_descending_multi([false, false]),
-            )
-            .with_column(
-                col("close")
-                    .shift(lit(1))
-                    .over([col("symbol")])
-                    .alias("close_lag"),
-            )
-            .with_column(((col("close") - col("close_lag")) / col("close_lag")).alias("return"));
+    fn features(&self) -> Vec<Feature> {
+        vec![Feature::DailyReturn]
+    }
 
-        // Compute rolling 5th percentile (VaR)
-        let result = with_returns
-            .with_column(
-                col("return")
-                    .rolling_quantile(
-                        QuantileMethod::Linear,
-                        0.05, // 5th percentile
-                        RollingOptionsFixedWindow {
-                            window_size: self.config.lookback,
-                            min_periods: self.config.min_periods,
-                            ..Default::default()
-                        },
-                    )
-                    .over([col("symbol")])
-                    .alias("value_at_risk"),
-            )
+    fn compute_raw(&self, data: &LazyFrame, date: NaiveDate) -> Result<DataFrame> {
+        let result = self
+            .rolling(data, date)?
             // Filter to the requested date
-            .filter(col("date").eq(lit(date.to_string())))
-            .select([col("symbol"), col("date"), col("value_at_risk")])
+            .filter(on_date(date))
+            .collect()?;
+
+        Ok(result)
+    }
+
+    fn compute_range(
+        &self,
+        data: &LazyFrame,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<DataFrame> {
+        check_date_range(start, end)?;
+
+        let result = self
+            .rolling(data, end)?
+            .filter(between_dates(start, end))
             .collect()?;
 
         Ok(result)